@@ -0,0 +1,254 @@
+//! The `agent` module provides the [`Agent`] abstraction that agent-based
+//! simulations are built from along with the runtime that drives agents.
+//!
+//! An [`Agent`] is attached to an [`Environment`] via the [`Manager`], which
+//! gives it its own [`RevmMiddleware`] seeded by the agent's label and runs it
+//! on a separate thread. The runtime calls the agent's hooks as the
+//! [`Environment`] moves on:
+//! - [`Agent::startup`] once, before anything else.
+//! - [`Agent::on_block`] whenever the [`Environment`] moves on to a new block,
+//!   i.e., once it has finished the previous one.
+//! - [`Agent::on_event`] for every emitted log matching one of the agent's
+//!   [`Agent::filters`].
+//! - [`Agent::shutdown`] once, when the [`Manager`] stops the [`Environment`].
+//!   The [`Environment`] is still running at that point so the agent may still
+//!   transact.
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use crossbeam_channel::{select, unbounded, Receiver};
+use ethers::{
+    contract::ContractError,
+    providers::{Middleware, ProviderError},
+    types::{Address, Filter, FilteredParams, Log, U64},
+};
+use thiserror::Error;
+
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::manager::Manager;
+use crate::{
+    environment::{BlockHeader, EmittedLogs, Environment},
+    middleware::{revm_logs_to_ethers_logs, RevmMiddleware, RevmMiddlewareError},
+};
+
+/// Errors that can occur while running an [`Agent`].
+#[derive(Error, Debug)]
+pub enum AgentError {
+    /// [`AgentError::Middleware`] is thrown when the agent's
+    /// [`RevmMiddleware`] fails.
+    #[error("middleware error! the source error is: {0}")]
+    Middleware(#[from] RevmMiddlewareError),
+
+    /// [`AgentError::Provider`] is thrown when a provider call of the agent
+    /// fails.
+    #[error("provider error! the source error is: {0}")]
+    Provider(#[from] ProviderError),
+
+    /// [`AgentError::Contract`] is thrown when a contract call or deployment
+    /// of the agent fails.
+    #[error("contract error! the source error is: {0}")]
+    Contract(#[from] ContractError<RevmMiddleware>),
+
+    /// [`AgentError::Runtime`] is thrown when the async runtime of the agent
+    /// cannot be created.
+    #[error("runtime error! the source error is: {0}")]
+    Runtime(String),
+
+    /// [`AgentError::ThreadPanic`] is thrown when the thread of the agent has
+    /// panicked.
+    #[error("joining on the agent thread resulted in a panic")]
+    ThreadPanic,
+
+    /// [`AgentError::EventBroadcaster`] is thrown when the agent could not
+    /// subscribe to the events of its [`Environment`].
+    #[error("error subscribing to the environment's events! due to: {0}")]
+    EventBroadcaster(String),
+
+    /// [`AgentError::Custom`] is thrown by an agent for any other reason.
+    #[error("agent error: {0}")]
+    Custom(String),
+}
+
+/// An actor in a simulation. Every hook receives the agent's own
+/// [`RevmMiddleware`], which can be used to call and transact with the
+/// contracts in the [`Environment`]. Returning an error from any hook stops
+/// the agent.
+#[async_trait]
+pub trait Agent: Send {
+    /// The filters that emitted logs must match, any one of them, to be passed
+    /// to [`Agent::on_event`]. No logs are passed on by default.
+    fn filters(&self) -> Vec<Filter> {
+        vec![]
+    }
+
+    /// Called once when the agent is attached to the [`Environment`].
+    async fn startup(&mut self, _client: Arc<RevmMiddleware>) -> Result<(), AgentError> {
+        Ok(())
+    }
+
+    /// Called whenever the [`Environment`] has moved on to the block with the
+    /// given number.
+    async fn on_block(
+        &mut self,
+        _client: Arc<RevmMiddleware>,
+        _block_number: U64,
+    ) -> Result<(), AgentError> {
+        Ok(())
+    }
+
+    /// Called for every emitted log that matches one of the agent's
+    /// [`Agent::filters`].
+    async fn on_event(
+        &mut self,
+        _client: Arc<RevmMiddleware>,
+        _log: Log,
+    ) -> Result<(), AgentError> {
+        Ok(())
+    }
+
+    /// Called once when the [`Manager`] stops the [`Environment`], before it is
+    /// actually stopped.
+    async fn shutdown(&mut self, _client: Arc<RevmMiddleware>) -> Result<(), AgentError> {
+        Ok(())
+    }
+}
+
+/// An [`Agent`] running on its own thread.
+#[derive(Debug)]
+pub struct AgentRuntime {
+    /// The label of the [`Environment`] the agent is attached to.
+    pub environment: String,
+
+    /// The address the agent's [`RevmMiddleware`] transacts from.
+    pub address: Address,
+
+    /// Tells the thread of the agent to shut the agent down.
+    stop: Arc<AtomicBool>,
+
+    /// [`JoinHandle`] for the thread in which the agent is running.
+    handle: Option<JoinHandle<Result<(), AgentError>>>,
+}
+
+impl AgentRuntime {
+    /// Privately accessible constructor function for creating an
+    /// [`AgentRuntime`]. Creates the agent's [`RevmMiddleware`], subscribes to
+    /// the events and blocks of the `environment` and starts the agent on a
+    /// new thread. This function should be accessed by the [`Manager`].
+    pub(crate) fn new<A: Agent + 'static>(
+        label: String,
+        environment: &Environment,
+        mut agent: A,
+    ) -> Result<Self, AgentError> {
+        let client = Arc::new(RevmMiddleware::new(environment, Some(label)));
+        let address = client.default_sender().unwrap_or_default();
+        let (event_sender, event_receiver) = unbounded();
+        let (header_sender, header_receiver) = unbounded();
+        {
+            let mut event_broadcaster = environment
+                .socket
+                .event_broadcaster
+                .lock()
+                .map_err(|e| AgentError::EventBroadcaster(format!("{:?}", e)))?;
+            event_broadcaster.add_sender(event_sender);
+            event_broadcaster.add_header_sender(header_sender);
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| AgentError::Runtime(e.to_string()))?;
+            runtime.block_on(run(
+                &mut agent,
+                client,
+                event_receiver,
+                header_receiver,
+                thread_stop,
+            ))
+        });
+
+        Ok(Self {
+            environment: environment.label.clone(),
+            address,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Shuts the agent down and waits for its thread to finish. Must be called
+    /// while the [`Environment`] is still running so that the agent's
+    /// [`Agent::shutdown`] hook may transact.
+    pub(crate) fn stop(&mut self) -> Result<(), AgentError> {
+        self.stop.store(true, Ordering::SeqCst);
+        match self.handle.take() {
+            Some(handle) => handle.join().unwrap_or(Err(AgentError::ThreadPanic)),
+            None => Ok(()),
+        }
+    }
+
+    /// Tells the agent to shut down without waiting for its thread to finish.
+    pub(crate) fn detach(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.handle.take();
+    }
+}
+
+/// Drives an [`Agent`] until it is told to stop or one of its hooks fails.
+async fn run<A: Agent>(
+    agent: &mut A,
+    client: Arc<RevmMiddleware>,
+    event_receiver: Receiver<EmittedLogs>,
+    header_receiver: Receiver<BlockHeader>,
+    stop: Arc<AtomicBool>,
+) -> Result<(), AgentError> {
+    let filters: Vec<FilteredParams> = agent
+        .filters()
+        .into_iter()
+        .map(|filter| FilteredParams::new(Some(filter)))
+        .collect();
+    agent.startup(client.clone()).await?;
+    while !stop.load(Ordering::SeqCst) {
+        let (received_logs, next_block): (Vec<EmittedLogs>, Option<U64>) = select! {
+            recv(event_receiver) -> emitted_logs => match emitted_logs {
+                Ok(emitted_logs) => (vec![emitted_logs], None),
+                Err(_) => break,
+            },
+            // A finished block means the environment has moved on to the next one. The
+            // logs of the finished block that are still waiting are passed on first
+            recv(header_receiver) -> header => match header {
+                Ok(header) => (event_receiver.try_iter().collect(), Some(header.number + 1)),
+                Err(_) => break,
+            },
+            default(Duration::from_millis(100)) => continue,
+        };
+        for emitted_logs in received_logs {
+            for mut log in revm_logs_to_ethers_logs(emitted_logs.logs) {
+                if filters
+                    .iter()
+                    .any(|filter| filter.filter_address(&log) && filter.filter_topics(&log))
+                {
+                    log.block_number = Some(emitted_logs.block_number);
+                    agent.on_event(client.clone(), log).await?;
+                }
+            }
+        }
+        if let Some(block_number) = next_block {
+            agent.on_block(client.clone(), block_number).await?;
+        }
+    }
+    agent.shutdown(client).await
+}
@@ -0,0 +1,310 @@
+//! The `arbitrageur` module provides the [`Arbitrageur`], a reference [`Agent`]
+//! that keeps a [`ConstantProductPool`] in line with a [`LiquidExchange`].
+//!
+//! Whenever the price of the [`LiquidExchange`] changes, the arbitrageur
+//! computes the [`optimal_trade`] against the pool, i.e., the trade after which
+//! the marginal price of the pool matches the price of the exchange up to the
+//! pool's fee. It makes that trade on the pool and closes it on the exchange,
+//! keeping the profit in the token it traded in. Both venues are traded
+//! through `ArbiterToken` approvals given once when the arbitrageur starts.
+//!
+//! The arbitrageur trades out of its own inventory, so its tokens have to be
+//! minted to the address of its [`RevmMiddleware`], which is derived from its
+//! label. Its [`Inventory`] and profit and loss are kept up to date after
+//! every trade and can be read through [`Arbitrageur::inventory`].
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use ethers::{
+    contract::EthLogDecode,
+    providers::Middleware,
+    types::{Address, Filter, Log, U256},
+};
+
+use crate::{
+    agent::{Agent, AgentError},
+    bindings::{
+        arbiter_token::ArbiterToken,
+        constant_product_pool::ConstantProductPool,
+        liquid_exchange::{LiquidExchange, PriceChangeFilter},
+    },
+    math::{float_to_wad, wad_to_float},
+    middleware::RevmMiddleware,
+};
+
+/// The fee a [`ConstantProductPool`] takes from the amount traded in.
+pub const POOL_FEE: f64 = 0.003;
+
+/// A trade on a [`ConstantProductPool`] given by the amount traded in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trade {
+    /// Buy token X from the pool with the given amount of token Y.
+    BuyX(f64),
+
+    /// Sell the given amount of token X to the pool for token Y.
+    SellX(f64),
+}
+
+/// Computes the trade on a constant product pool with the given reserves that
+/// is the most profitable when closed at `price`, the price of token X in
+/// token Y on the other venue.
+///
+/// # Returns
+///
+/// The optimal [`Trade`], or `None` if the pool's price is within its fee of
+/// `price` so that no trade is profitable.
+///
+/// # Examples
+///
+/// ```
+/// # use arbiter_core::arbitrageur::{optimal_trade, Trade};
+/// // The pool sells token X at 1000 while it is worth 1100 elsewhere
+/// let trade = optimal_trade(1100.0, 1000.0, 1_000_000.0);
+/// assert!(matches!(trade, Some(Trade::BuyX(_))));
+/// // Prices within the fee of one another are left alone
+/// assert_eq!(optimal_trade(1001.0, 1000.0, 1_000_000.0), None);
+/// ```
+pub fn optimal_trade(price: f64, reserve_x: f64, reserve_y: f64) -> Option<Trade> {
+    let gamma = 1.0 - POOL_FEE;
+    let pool_price = reserve_y / reserve_x;
+    if pool_price < gamma * price {
+        // Trade in until the pool's reserve of Y reaches sqrt(price * gamma * k)
+        let amount_y = ((price * gamma * reserve_x * reserve_y).sqrt() - reserve_y) / gamma;
+        Some(Trade::BuyX(amount_y))
+    } else if pool_price > price / gamma {
+        let amount_x = ((gamma * reserve_x * reserve_y / price).sqrt() - reserve_x) / gamma;
+        Some(Trade::SellX(amount_x))
+    } else {
+        None
+    }
+}
+
+/// The tokens held by an [`Arbitrageur`] and what they are worth.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Inventory {
+    /// The balance of token X.
+    pub token_x: U256,
+
+    /// The balance of token Y.
+    pub token_y: U256,
+
+    /// The amount of arbitrages made.
+    pub trades: u64,
+
+    /// The value of the inventory in token Y when the arbitrageur started.
+    pub initial_value: f64,
+
+    /// The value of the inventory in token Y at the last seen price of the
+    /// [`LiquidExchange`].
+    pub value: f64,
+
+    /// The last price of the [`LiquidExchange`] the arbitrageur has finished
+    /// acting on, whether it traded or not.
+    pub price: f64,
+}
+
+impl Inventory {
+    /// The profit and loss in token Y since the arbitrageur started.
+    pub fn pnl(&self) -> f64 {
+        self.value - self.initial_value
+    }
+}
+
+/// An [`Agent`] that arbitrages a [`ConstantProductPool`] against a
+/// [`LiquidExchange`] trading the same pair of tokens.
+#[derive(Debug, Clone)]
+pub struct Arbitrageur {
+    /// The address of the [`LiquidExchange`].
+    pub liquid_exchange: Address,
+
+    /// The address of the [`ConstantProductPool`].
+    pub pool: Address,
+
+    /// The address of token X, whose price is given by the
+    /// [`LiquidExchange`].
+    pub token_x: Address,
+
+    /// The address of token Y, which prices are given in.
+    pub token_y: Address,
+
+    /// The inventory of the arbitrageur, shared with whoever asked for it.
+    inventory: Arc<Mutex<Inventory>>,
+}
+
+impl Arbitrageur {
+    /// Constructs a new [`Arbitrageur`] between the [`LiquidExchange`] and the
+    /// [`ConstantProductPool`] at the given addresses.
+    pub fn new(
+        liquid_exchange: Address,
+        pool: Address,
+        token_x: Address,
+        token_y: Address,
+    ) -> Self {
+        Self {
+            liquid_exchange,
+            pool,
+            token_x,
+            token_y,
+            inventory: Arc::new(Mutex::new(Inventory::default())),
+        }
+    }
+
+    /// Returns a handle to the [`Inventory`] of the arbitrageur that stays up
+    /// to date once the arbitrageur runs.
+    pub fn inventory(&self) -> Arc<Mutex<Inventory>> {
+        self.inventory.clone()
+    }
+
+    /// Locks the [`Inventory`] of the arbitrageur.
+    fn lock_inventory(&self) -> Result<MutexGuard<'_, Inventory>, AgentError> {
+        self.inventory
+            .lock()
+            .map_err(|e| AgentError::Custom(format!("{:?}", e)))
+    }
+
+    /// Reads the balances of the arbitrageur and values them at `price`.
+    async fn update_inventory(
+        &self,
+        client: Arc<RevmMiddleware>,
+        price: f64,
+    ) -> Result<Inventory, AgentError> {
+        let (token_x, token_y) = self.balances(client).await?;
+        let mut inventory = self.lock_inventory()?;
+        inventory.token_x = token_x;
+        inventory.token_y = token_y;
+        inventory.value = wad_to_float(token_x) * price + wad_to_float(token_y);
+        Ok(inventory.clone())
+    }
+
+    /// Returns the balances of token X and token Y of the arbitrageur.
+    async fn balances(&self, client: Arc<RevmMiddleware>) -> Result<(U256, U256), AgentError> {
+        let address = client.default_sender().unwrap_or_default();
+        let token_x = ArbiterToken::new(self.token_x, client.clone())
+            .balance_of(address)
+            .call()
+            .await?;
+        let token_y = ArbiterToken::new(self.token_y, client)
+            .balance_of(address)
+            .call()
+            .await?;
+        Ok((token_x, token_y))
+    }
+
+    /// Makes the optimal trade on the pool, if any, and closes it on the
+    /// [`LiquidExchange`] at `price`.
+    async fn arbitrage(
+        &mut self,
+        client: Arc<RevmMiddleware>,
+        price: f64,
+    ) -> Result<(), AgentError> {
+        let pool = ConstantProductPool::new(self.pool, client.clone());
+        let liquid_exchange = LiquidExchange::new(self.liquid_exchange, client.clone());
+        let reserve_x = wad_to_float(pool.reserve_x().call().await?);
+        let reserve_y = wad_to_float(pool.reserve_y().call().await?);
+        let inventory = self.update_inventory(client.clone(), price).await?;
+        let (token_in, token_out, amount_in, balance_in) =
+            match optimal_trade(price, reserve_x, reserve_y) {
+                Some(Trade::BuyX(amount_y)) => {
+                    (self.token_y, self.token_x, amount_y, inventory.token_y)
+                }
+                Some(Trade::SellX(amount_x)) => {
+                    (self.token_x, self.token_y, amount_x, inventory.token_x)
+                }
+                None => return Ok(()),
+            };
+        // The trade is still profitable when cut down to what the arbitrageur holds
+        let amount_in = float_to_wad(amount_in).min(balance_in);
+        if amount_in.is_zero() {
+            return Ok(());
+        }
+        let address = client.default_sender().unwrap_or_default();
+        let token_out = ArbiterToken::new(token_out, client.clone());
+        let balance_out = token_out.balance_of(address).call().await?;
+        pool.swap(token_in, amount_in).send().await?.await?;
+        let bought = token_out.balance_of(address).call().await? - balance_out;
+        liquid_exchange
+            .swap(token_out.address(), bought)
+            .send()
+            .await?
+            .await?;
+        self.update_inventory(client, price).await?;
+        self.lock_inventory()?.trades += 1;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Agent for Arbitrageur {
+    fn filters(&self) -> Vec<Filter> {
+        vec![Filter::new()
+            .address(self.liquid_exchange)
+            .event("PriceChange(uint256)")]
+    }
+
+    async fn startup(&mut self, client: Arc<RevmMiddleware>) -> Result<(), AgentError> {
+        for token in [self.token_x, self.token_y] {
+            let token = ArbiterToken::new(token, client.clone());
+            for spender in [self.liquid_exchange, self.pool] {
+                token.approve(spender, U256::MAX).send().await?.await?;
+            }
+        }
+        let price = LiquidExchange::new(self.liquid_exchange, client.clone())
+            .price()
+            .call()
+            .await?;
+        let inventory = self.update_inventory(client, wad_to_float(price)).await?;
+        self.lock_inventory()?.initial_value = inventory.value;
+        Ok(())
+    }
+
+    async fn on_event(&mut self, client: Arc<RevmMiddleware>, log: Log) -> Result<(), AgentError> {
+        let price_change = PriceChangeFilter::decode_log(&log.into())
+            .map_err(|e| AgentError::Custom(e.to_string()))?;
+        let price = wad_to_float(price_change.price);
+        self.arbitrage(client, price).await?;
+        self.lock_inventory()?.price = price;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The marginal price of the pool after trading in `amount_in`.
+    fn price_after(trade: Trade, reserve_x: f64, reserve_y: f64) -> f64 {
+        let gamma = 1.0 - POOL_FEE;
+        let k = reserve_x * reserve_y;
+        match trade {
+            Trade::BuyX(amount_y) => {
+                let reserve_y = reserve_y + gamma * amount_y;
+                reserve_y / (k / reserve_y)
+            }
+            Trade::SellX(amount_x) => {
+                let reserve_x = reserve_x + gamma * amount_x;
+                (k / reserve_x) / reserve_x
+            }
+        }
+    }
+
+    #[test]
+    fn optimal_trade_moves_the_pool_to_the_price() {
+        let (reserve_x, reserve_y) = (1000.0, 1_000_000.0);
+        let gamma = 1.0 - POOL_FEE;
+
+        let trade = optimal_trade(1100.0, reserve_x, reserve_y).unwrap();
+        assert!(matches!(trade, Trade::BuyX(amount) if amount > 0.0));
+        let price = price_after(trade, reserve_x, reserve_y);
+        assert!((price - 1100.0 * gamma).abs() < 1e-6);
+
+        let trade = optimal_trade(900.0, reserve_x, reserve_y).unwrap();
+        assert!(matches!(trade, Trade::SellX(amount) if amount > 0.0));
+        let price = price_after(trade, reserve_x, reserve_y);
+        assert!((price - 900.0 / gamma).abs() < 1e-6);
+
+        assert_eq!(optimal_trade(999.0, reserve_x, reserve_y), None);
+    }
+}
@@ -0,0 +1,621 @@
+pub use arbiter_bridge::*;
+/// This module follows the layout of the ethers-rs Abigen output for
+/// `contracts/ArbiterBridge.sol`, but its bytecode was assembled by hand as the
+/// contract could not be compiled. Regenerate it via `arbiter bind` to replace
+/// it with the compiled contract.
+///
+/// More information at: <https://github.com/gakonst/ethers-rs>
+#[allow(
+    clippy::enum_variant_names,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms,
+    clippy::type_complexity,
+    dead_code,
+    non_camel_case_types
+)]
+pub mod arbiter_bridge {
+    #[allow(deprecated)]
+    fn __abi() -> ::ethers::core::abi::Abi {
+        ::ethers::core::abi::ethabi::Contract {
+            constructor: ::core::option::Option::Some(::ethers::core::abi::ethabi::Constructor {
+                inputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                    name: ::std::borrow::ToOwned::to_owned("_operator"),
+                    kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                    internal_type: ::core::option::Option::Some(::std::borrow::ToOwned::to_owned(
+                        "address"
+                    ),),
+                },],
+            }),
+            functions: ::core::convert::From::from([
+                (
+                    ::std::borrow::ToOwned::to_owned("balanceOf"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("balanceOf"),
+                        inputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("address"),
+                            ),
+                        },],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("uint256"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("deliver"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("deliver"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("recipient"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("address"),
+                                ),
+                            },
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("amount"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("uint256"),
+                                ),
+                            },
+                        ],
+                        outputs: ::std::vec![],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("mint"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("mint"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("receiver"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("address"),
+                                ),
+                            },
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("amount"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("uint256"),
+                                ),
+                            },
+                        ],
+                        outputs: ::std::vec![],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("operator"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("operator"),
+                        inputs: ::std::vec![],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("address"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("send"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("send"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("recipient"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("address"),
+                                ),
+                            },
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("amount"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("uint256"),
+                                ),
+                            },
+                        ],
+                        outputs: ::std::vec![],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
+                    },],
+                ),
+            ]),
+            events: ::core::convert::From::from([
+                (
+                    ::std::borrow::ToOwned::to_owned("MessageDelivered"),
+                    ::std::vec![::ethers::core::abi::ethabi::Event {
+                        name: ::std::borrow::ToOwned::to_owned("MessageDelivered"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("recipient"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                indexed: true,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("amount"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize,),
+                                indexed: false,
+                            },
+                        ],
+                        anonymous: false,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("MessageSent"),
+                    ::std::vec![::ethers::core::abi::ethabi::Event {
+                        name: ::std::borrow::ToOwned::to_owned("MessageSent"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("sender"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                indexed: true,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("recipient"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                indexed: true,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("amount"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize,),
+                                indexed: false,
+                            },
+                        ],
+                        anonymous: false,
+                    },],
+                ),
+            ]),
+            errors: ::std::collections::BTreeMap::new(),
+            receive: false,
+            fallback: false,
+        }
+    }
+    /// The parsed JSON ABI of the contract.
+    pub static ARBITERBRIDGE_ABI: ::ethers::contract::Lazy<::ethers::core::abi::Abi> =
+        ::ethers::contract::Lazy::new(__abi);
+    #[rustfmt::skip]
+    const __BYTECODE: &[u8] = b"4a\0 W` a\x01l`\09`\0Q`\x01Ua\x01G\x80a\0%`\09`\0\xF3[`\0\x80\xFD4a\0JW`\x046\x10a\0JW`\05`\xE0\x1C\x80c@\xC1\x0F\x19\x14a\0OW\x80c\xD0g\x9D4\x14a\0{W\x80c:S{\x0C\x14a\0\xC9W\x80cp\xA0\x821\x14a\x01!W\x80cW\x0C\xA75\x14a\x01;W[`\0\x80\xFD[`\x01T3\x14\x15a\0JW`$5`\x045`\0R`\0` R`@`\0 \x80T\x82\x01\x80\x83\x11a\0JW\x90U\0[`$53`\0R`\0` R`@`\0 \x80T\x80\x83\x11a\0JW\x82\x90\x03\x90U`\0R`\x0453\x7F\x8E<\xE0\xA3\x7FB\xBF\xB0\xE8_\x8F\x02\xC4@\xFF(C\xD1\x18-\x7F\x1F\xCE\x91t\xF7\x98\x0E^\x9D\x13\x0C` `\0\xA3\0[`\x01T3\x14\x15a\0JW`$5`\x045`\0R`\0` R`@`\0 \x80T\x82\x01\x80\x83\x11a\0JW\x90U`\0R`\x045\x7F\xDC8\xD1\x9C\x1E){\xAC\x0C\xF6\xF9k\xF4\xA1t\x10Y\xF8\xB2\xEA\xCF\xF6\xE1\xBD\xAA\x99T\r\xDB\xC3>\x17` `\0\xA2\0[`\x045`\0R`\0` R`@`\0 T`\0R` `\0\xF3[`\x01T`\0R` `\0\xF3";
+    /// The bytecode of the contract.
+    pub static ARBITERBRIDGE_BYTECODE: ::ethers::core::types::Bytes =
+        ::ethers::core::types::Bytes::from_static(__BYTECODE);
+    #[rustfmt::skip]
+    const __DEPLOYED_BYTECODE: &[u8] = b"4a\0JW`\x046\x10a\0JW`\05`\xE0\x1C\x80c@\xC1\x0F\x19\x14a\0OW\x80c\xD0g\x9D4\x14a\0{W\x80c:S{\x0C\x14a\0\xC9W\x80cp\xA0\x821\x14a\x01!W\x80cW\x0C\xA75\x14a\x01;W[`\0\x80\xFD[`\x01T3\x14\x15a\0JW`$5`\x045`\0R`\0` R`@`\0 \x80T\x82\x01\x80\x83\x11a\0JW\x90U\0[`$53`\0R`\0` R`@`\0 \x80T\x80\x83\x11a\0JW\x82\x90\x03\x90U`\0R`\x0453\x7F\x8E<\xE0\xA3\x7FB\xBF\xB0\xE8_\x8F\x02\xC4@\xFF(C\xD1\x18-\x7F\x1F\xCE\x91t\xF7\x98\x0E^\x9D\x13\x0C` `\0\xA3\0[`\x01T3\x14\x15a\0JW`$5`\x045`\0R`\0` R`@`\0 \x80T\x82\x01\x80\x83\x11a\0JW\x90U`\0R`\x045\x7F\xDC8\xD1\x9C\x1E){\xAC\x0C\xF6\xF9k\xF4\xA1t\x10Y\xF8\xB2\xEA\xCF\xF6\xE1\xBD\xAA\x99T\r\xDB\xC3>\x17` `\0\xA2\0[`\x045`\0R`\0` R`@`\0 T`\0R` `\0\xF3[`\x01T`\0R` `\0\xF3";
+    /// The deployed bytecode of the contract.
+    pub static ARBITERBRIDGE_DEPLOYED_BYTECODE: ::ethers::core::types::Bytes =
+        ::ethers::core::types::Bytes::from_static(__DEPLOYED_BYTECODE);
+    pub struct ArbiterBridge<M>(::ethers::contract::Contract<M>);
+    impl<M> ::core::clone::Clone for ArbiterBridge<M> {
+        fn clone(&self) -> Self {
+            Self(::core::clone::Clone::clone(&self.0))
+        }
+    }
+    impl<M> ::core::ops::Deref for ArbiterBridge<M> {
+        type Target = ::ethers::contract::Contract<M>;
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+    impl<M> ::core::ops::DerefMut for ArbiterBridge<M> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+    impl<M> ::core::fmt::Debug for ArbiterBridge<M> {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_tuple(::core::stringify!(ArbiterBridge))
+                .field(&self.address())
+                .finish()
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> ArbiterBridge<M> {
+        /// Creates a new contract instance with the specified `ethers` client
+        /// at `address`. The contract derefs to a `ethers::Contract`
+        /// object.
+        pub fn new<T: Into<::ethers::core::types::Address>>(
+            address: T,
+            client: ::std::sync::Arc<M>,
+        ) -> Self {
+            Self(::ethers::contract::Contract::new(
+                address.into(),
+                ARBITERBRIDGE_ABI.clone(),
+                client,
+            ))
+        }
+        /// Constructs the general purpose `Deployer` instance based on the
+        /// provided constructor arguments and sends it. Returns a new
+        /// instance of a deployer that returns an instance of this contract
+        /// after sending the transaction
+        ///
+        /// Notes:
+        /// - If there are no constructor arguments, you should pass `()` as the
+        ///   argument.
+        /// - The default poll duration is 7 seconds.
+        /// - The default number of confirmations is 1 block.
+        ///
+        ///
+        /// # Example
+        ///
+        /// Generate contract bindings with `abigen!` and deploy a new contract
+        /// instance.
+        ///
+        /// *Note*: this requires a `bytecode` and `abi` object in the
+        /// `greeter.json` artifact.
+        ///
+        /// ```ignore
+        /// # async fn deploy<M: ethers::providers::Middleware>(client: ::std::sync::Arc<M>) {
+        ///     abigen!(Greeter, "../greeter.json");
+        ///
+        ///    let greeter_contract = Greeter::deploy(client, "Hello world!".to_string()).unwrap().send().await.unwrap();
+        ///    let msg = greeter_contract.greet().call().await.unwrap();
+        /// # }
+        /// ```
+        pub fn deploy<T: ::ethers::core::abi::Tokenize>(
+            client: ::std::sync::Arc<M>,
+            constructor_args: T,
+        ) -> ::core::result::Result<
+            ::ethers::contract::builders::ContractDeployer<M, Self>,
+            ::ethers::contract::ContractError<M>,
+        > {
+            let factory = ::ethers::contract::ContractFactory::new(
+                ARBITERBRIDGE_ABI.clone(),
+                ARBITERBRIDGE_BYTECODE.clone().into(),
+                client,
+            );
+            let deployer = factory.deploy(constructor_args)?;
+            let deployer = ::ethers::contract::ContractDeployer::new(deployer);
+            Ok(deployer)
+        }
+        /// Calls the contract's `balanceOf` (0x70a08231) function
+        pub fn balance_of(
+            &self,
+            p0: ::ethers::core::types::Address,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([112, 160, 130, 49], p0)
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `deliver` (0x3a537b0c) function
+        pub fn deliver(
+            &self,
+            recipient: ::ethers::core::types::Address,
+            amount: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ()> {
+            self.0
+                .method_hash([58, 83, 123, 12], (recipient, amount))
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `mint` (0x40c10f19) function
+        pub fn mint(
+            &self,
+            receiver: ::ethers::core::types::Address,
+            amount: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ()> {
+            self.0
+                .method_hash([64, 193, 15, 25], (receiver, amount))
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `operator` (0x570ca735) function
+        pub fn operator(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::Address> {
+            self.0
+                .method_hash([87, 12, 167, 53], ())
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `send` (0xd0679d34) function
+        pub fn send(
+            &self,
+            recipient: ::ethers::core::types::Address,
+            amount: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ()> {
+            self.0
+                .method_hash([208, 103, 157, 52], (recipient, amount))
+                .expect("method not found (this should never happen)")
+        }
+        /// Gets the contract's `MessageDelivered` event
+        pub fn message_delivered_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, MessageDeliveredFilter>
+        {
+            self.0.event()
+        }
+        /// Gets the contract's `MessageSent` event
+        pub fn message_sent_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, MessageSentFilter>
+        {
+            self.0.event()
+        }
+        /// Returns an `Event` builder for all the events of this contract.
+        pub fn events(
+            &self,
+        ) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, ArbiterBridgeEvents>
+        {
+            self.0
+                .event_with_filter(::core::default::Default::default())
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> From<::ethers::contract::Contract<M>>
+        for ArbiterBridge<M>
+    {
+        fn from(contract: ::ethers::contract::Contract<M>) -> Self {
+            Self::new(contract.address(), contract.client())
+        }
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethevent(name = "MessageDelivered", abi = "MessageDelivered(address,uint256)")]
+    pub struct MessageDeliveredFilter {
+        #[ethevent(indexed)]
+        pub recipient: ::ethers::core::types::Address,
+        pub amount: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethevent(name = "MessageSent", abi = "MessageSent(address,address,uint256)")]
+    pub struct MessageSentFilter {
+        #[ethevent(indexed)]
+        pub sender: ::ethers::core::types::Address,
+        #[ethevent(indexed)]
+        pub recipient: ::ethers::core::types::Address,
+        pub amount: ::ethers::core::types::U256,
+    }
+    /// Container type for all of the contract's events
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum ArbiterBridgeEvents {
+        MessageDeliveredFilter(MessageDeliveredFilter),
+        MessageSentFilter(MessageSentFilter),
+    }
+    impl ::ethers::contract::EthLogDecode for ArbiterBridgeEvents {
+        fn decode_log(
+            log: &::ethers::core::abi::RawLog,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::Error> {
+            if let Ok(decoded) = MessageDeliveredFilter::decode_log(log) {
+                return Ok(ArbiterBridgeEvents::MessageDeliveredFilter(decoded));
+            }
+            if let Ok(decoded) = MessageSentFilter::decode_log(log) {
+                return Ok(ArbiterBridgeEvents::MessageSentFilter(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData)
+        }
+    }
+    impl ::core::fmt::Display for ArbiterBridgeEvents {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::MessageDeliveredFilter(element) => ::core::fmt::Display::fmt(element, f),
+                Self::MessageSentFilter(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<MessageDeliveredFilter> for ArbiterBridgeEvents {
+        fn from(value: MessageDeliveredFilter) -> Self {
+            Self::MessageDeliveredFilter(value)
+        }
+    }
+    impl ::core::convert::From<MessageSentFilter> for ArbiterBridgeEvents {
+        fn from(value: MessageSentFilter) -> Self {
+            Self::MessageSentFilter(value)
+        }
+    }
+    /// Container type for all input parameters for the `balanceOf` function
+    /// with signature `balanceOf(address)` and selector `0x70a08231`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "balanceOf", abi = "balanceOf(address)")]
+    pub struct BalanceOfCall(pub ::ethers::core::types::Address);
+    /// Container type for all input parameters for the `deliver` function with
+    /// signature `deliver(address,uint256)` and selector `0x3a537b0c`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "deliver", abi = "deliver(address,uint256)")]
+    pub struct DeliverCall {
+        pub recipient: ::ethers::core::types::Address,
+        pub amount: ::ethers::core::types::U256,
+    }
+    /// Container type for all input parameters for the `mint` function with
+    /// signature `mint(address,uint256)` and selector `0x40c10f19`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "mint", abi = "mint(address,uint256)")]
+    pub struct MintCall {
+        pub receiver: ::ethers::core::types::Address,
+        pub amount: ::ethers::core::types::U256,
+    }
+    /// Container type for all input parameters for the `operator` function with
+    /// signature `operator()` and selector `0x570ca735`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "operator", abi = "operator()")]
+    pub struct OperatorCall;
+    /// Container type for all input parameters for the `send` function with
+    /// signature `send(address,uint256)` and selector `0xd0679d34`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "send", abi = "send(address,uint256)")]
+    pub struct SendCall {
+        pub recipient: ::ethers::core::types::Address,
+        pub amount: ::ethers::core::types::U256,
+    }
+    /// Container type for all of the contract's call
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum ArbiterBridgeCalls {
+        BalanceOf(BalanceOfCall),
+        Deliver(DeliverCall),
+        Mint(MintCall),
+        Operator(OperatorCall),
+        Send(SendCall),
+    }
+    impl ::ethers::core::abi::AbiDecode for ArbiterBridgeCalls {
+        fn decode(
+            data: impl AsRef<[u8]>,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::AbiError> {
+            let data = data.as_ref();
+            if let Ok(decoded) = <BalanceOfCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::BalanceOf(decoded));
+            }
+            if let Ok(decoded) = <DeliverCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::Deliver(decoded));
+            }
+            if let Ok(decoded) = <MintCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::Mint(decoded));
+            }
+            if let Ok(decoded) = <OperatorCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::Operator(decoded));
+            }
+            if let Ok(decoded) = <SendCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::Send(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData.into())
+        }
+    }
+    impl ::ethers::core::abi::AbiEncode for ArbiterBridgeCalls {
+        fn encode(self) -> Vec<u8> {
+            match self {
+                Self::BalanceOf(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::Deliver(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::Mint(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::Operator(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::Send(element) => ::ethers::core::abi::AbiEncode::encode(element),
+            }
+        }
+    }
+    impl ::core::fmt::Display for ArbiterBridgeCalls {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::BalanceOf(element) => ::core::fmt::Display::fmt(element, f),
+                Self::Deliver(element) => ::core::fmt::Display::fmt(element, f),
+                Self::Mint(element) => ::core::fmt::Display::fmt(element, f),
+                Self::Operator(element) => ::core::fmt::Display::fmt(element, f),
+                Self::Send(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<BalanceOfCall> for ArbiterBridgeCalls {
+        fn from(value: BalanceOfCall) -> Self {
+            Self::BalanceOf(value)
+        }
+    }
+    impl ::core::convert::From<DeliverCall> for ArbiterBridgeCalls {
+        fn from(value: DeliverCall) -> Self {
+            Self::Deliver(value)
+        }
+    }
+    impl ::core::convert::From<MintCall> for ArbiterBridgeCalls {
+        fn from(value: MintCall) -> Self {
+            Self::Mint(value)
+        }
+    }
+    impl ::core::convert::From<OperatorCall> for ArbiterBridgeCalls {
+        fn from(value: OperatorCall) -> Self {
+            Self::Operator(value)
+        }
+    }
+    impl ::core::convert::From<SendCall> for ArbiterBridgeCalls {
+        fn from(value: SendCall) -> Self {
+            Self::Send(value)
+        }
+    }
+    /// Container type for all return fields from the `balanceOf` function with
+    /// signature `balanceOf(address)` and selector `0x70a08231`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct BalanceOfReturn(pub ::ethers::core::types::U256);
+    /// Container type for all return fields from the `operator` function with
+    /// signature `operator()` and selector `0x570ca735`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct OperatorReturn(pub ::ethers::core::types::Address);
+}
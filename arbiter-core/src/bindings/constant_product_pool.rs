@@ -0,0 +1,804 @@
+pub use constant_product_pool::*;
+/// This module follows the layout of the ethers-rs Abigen output for
+/// `contracts/ConstantProductPool.sol`, but its bytecode was assembled by hand
+/// as the contract could not be compiled. Regenerate it via `arbiter bind` to
+/// replace it with the compiled contract.
+///
+/// More information at: <https://github.com/gakonst/ethers-rs>
+#[allow(
+    clippy::enum_variant_names,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms,
+    clippy::type_complexity,
+    dead_code,
+    non_camel_case_types
+)]
+pub mod constant_product_pool {
+    #[allow(deprecated)]
+    fn __abi() -> ::ethers::core::abi::Abi {
+        ::ethers::core::abi::ethabi::Contract {
+            constructor: ::core::option::Option::Some(::ethers::core::abi::ethabi::Constructor {
+                inputs: ::std::vec![
+                    ::ethers::core::abi::ethabi::Param {
+                        name: ::std::borrow::ToOwned::to_owned("tokenX_"),
+                        kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                        internal_type: ::core::option::Option::Some(
+                            ::std::borrow::ToOwned::to_owned("address"),
+                        ),
+                    },
+                    ::ethers::core::abi::ethabi::Param {
+                        name: ::std::borrow::ToOwned::to_owned("tokenY_"),
+                        kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                        internal_type: ::core::option::Option::Some(
+                            ::std::borrow::ToOwned::to_owned("address"),
+                        ),
+                    },
+                ],
+            }),
+            functions: ::core::convert::From::from([
+                (
+                    ::std::borrow::ToOwned::to_owned("addLiquidity"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("addLiquidity"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("amountX"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("uint256"),
+                                ),
+                            },
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("amountY"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("uint256"),
+                                ),
+                            },
+                        ],
+                        outputs: ::std::vec![],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("getAmountOut"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("getAmountOut"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("tokenIn"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("address"),
+                                ),
+                            },
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("amountIn"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("uint256"),
+                                ),
+                            },
+                        ],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("uint256"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("reserveX"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("reserveX"),
+                        inputs: ::std::vec![],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("uint256"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("reserveY"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("reserveY"),
+                        inputs: ::std::vec![],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("uint256"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("swap"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("swap"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("tokenIn"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("address"),
+                                ),
+                            },
+                            ::ethers::core::abi::ethabi::Param {
+                                name: ::std::borrow::ToOwned::to_owned("amountIn"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                                internal_type: ::core::option::Option::Some(
+                                    ::std::borrow::ToOwned::to_owned("uint256"),
+                                ),
+                            },
+                        ],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::borrow::ToOwned::to_owned("amountOut"),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize),
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("uint256"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("tokenX"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("tokenX"),
+                        inputs: ::std::vec![],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("address"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("tokenY"),
+                    ::std::vec![::ethers::core::abi::ethabi::Function {
+                        name: ::std::borrow::ToOwned::to_owned("tokenY"),
+                        inputs: ::std::vec![],
+                        outputs: ::std::vec![::ethers::core::abi::ethabi::Param {
+                            name: ::std::string::String::new(),
+                            kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                            internal_type: ::core::option::Option::Some(
+                                ::std::borrow::ToOwned::to_owned("address"),
+                            ),
+                        },],
+                        constant: ::core::option::Option::None,
+                        state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
+                    },],
+                ),
+            ]),
+            events: ::core::convert::From::from([
+                (
+                    ::std::borrow::ToOwned::to_owned("LiquidityAdded"),
+                    ::std::vec![::ethers::core::abi::ethabi::Event {
+                        name: ::std::borrow::ToOwned::to_owned("LiquidityAdded"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("provider"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                indexed: false,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("amountX"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize,),
+                                indexed: false,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("amountY"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize,),
+                                indexed: false,
+                            },
+                        ],
+                        anonymous: false,
+                    },],
+                ),
+                (
+                    ::std::borrow::ToOwned::to_owned("Swap"),
+                    ::std::vec![::ethers::core::abi::ethabi::Event {
+                        name: ::std::borrow::ToOwned::to_owned("Swap"),
+                        inputs: ::std::vec![
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("tokenIn"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                indexed: false,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("tokenOut"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                indexed: false,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("amountIn"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize,),
+                                indexed: false,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("amountOut"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Uint(256usize,),
+                                indexed: false,
+                            },
+                            ::ethers::core::abi::ethabi::EventParam {
+                                name: ::std::borrow::ToOwned::to_owned("to"),
+                                kind: ::ethers::core::abi::ethabi::ParamType::Address,
+                                indexed: false,
+                            },
+                        ],
+                        anonymous: false,
+                    },],
+                ),
+            ]),
+            errors: ::std::collections::BTreeMap::new(),
+            receive: false,
+            fallback: false,
+        }
+    }
+    /// The parsed JSON ABI of the contract.
+    pub static CONSTANTPRODUCTPOOL_ABI: ::ethers::contract::Lazy<::ethers::core::abi::Abi> =
+        ::ethers::contract::Lazy::new(__abi);
+    #[rustfmt::skip]
+    const __BYTECODE: &[u8] = b"`@`@8\x03`\09`\0Q`\0U` Q`\x01Ua\x03\xD6\x80a\0\"`\09`\0\xF34a\0`W`\x046\x10a\0`W`\05`\xE0\x1C\x80c\x16\xDC\x16[\x14a\0eW\x80c\xB7\xD1\x9F\xC4\x14a\0qW\x80c\x08\xEA\xBD\xDA\x14a\0}W\x80c\xFA\xDF\xA6[\x14a\0\x89W\x80c\xCApk\xCF\x14a\0\x95W\x80c\xD0\x04\xF0\xF7\x14a\x01\x1FW\x80c\x9C\xD4A\xDA\x14a\x02\xDEW[`\0\x80\xFD[`\0T`\0R` `\0\xF3[`\x01T`\0R` `\0\xF3[`\x02T`\0R` `\0\xF3[`\x03T`\0R` `\0\xF3[`\x045\x80a\x01\0R\x80`\0T\x14a\0\xB3W`\x01T\x14a\0\xD4Wa\0`V[P`\x02Ta\x01\x80R`\x03Ta\x01\xA0R`\x01Ta\x01 R`\x01a\x01\xC0Ra\0\xF0V[`\x03Ta\x01\x80R`\x02Ta\x01\xA0R`\0Ta\x01 R`\0a\x01\xC0R[`$5\x80a\x01@Ra\x03\xE5\x02\x80a\x01\xA0Q\x02\x90a\x01\x80Qa\x03\xE8\x02\x01\x90\x04a\x01`Ra\x01`Q`\0R` `\0\xF3[`\x045\x80a\x01\0R\x80`\0T\x14a\x01=W`\x01T\x14a\x01^Wa\0`V[P`\x02Ta\x01\x80R`\x03Ta\x01\xA0R`\x01Ta\x01 R`\x01a\x01\xC0Ra\x01zV[`\x03Ta\x01\x80R`\x02Ta\x01\xA0R`\0Ta\x01 R`\0a\x01\xC0R[`$5\x80a\x01@Ra\x03\xE5\x02\x80a\x01\xA0Q\x02\x90a\x01\x80Qa\x03\xE8\x02\x01\x90\x04a\x01`Ra\x01`Q\x15a\0`W\x7F#\xB8r\xDD\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04R0`$Ra\x01@Q`DR`\0`\xC0R` `\xC0`d`\0`\0a\x01\0QZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`W\x7F\xA9\x05\x9C\xBB\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04Ra\x01`Q`$R`\0`\xC0R` `\xC0`D`\0`\0a\x01 QZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`Wa\x01\xC0Qa\x02tW`\x03Ta\x01@Q\x01`\x03Ua\x01`Q`\x02T\x03`\x02Ua\x02\x8BV[`\x02Ta\x01@Q\x01`\x02Ua\x01`Q`\x03T\x03`\x03U[a\x01\0Q`\0Ra\x01 Q` Ra\x01@Q`@Ra\x01`Q``R3`\x80R\x7F\xB3\x9C\x9B\xC4?\x81\x1E\x1A|\xE1Y\xC5\xF1GE\x8F\xDB\x80&k\xF2<\x172 \x131n'\xE0\x86\xD0`\xA0`\0\xA1a\x01`Q`\0R` `\0\xF3[\x7F#\xB8r\xDD\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04R0`$R`\x045`DR`\0`\xC0R` `\xC0`d`\0`\0`\0TZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`W\x7F#\xB8r\xDD\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04R0`$R`$5`DR`\0`\xC0R` `\xC0`d`\0`\0`\x01TZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`W`\x02T`\x045\x01`\x02U`\x03T`$5\x01`\x03U3`\0R`\x045` R`$5`@R\x7F\xAC\x1Dvt\x9ETG\xB7\xB1oZ\xB6\x14G\xE1\xBDP/;\xB4\x80z\xF3\xB2\x8Eb\r\x17\0\xA6\xEEE```\0\xA1\0";
+    /// The bytecode of the contract.
+    pub static CONSTANTPRODUCTPOOL_BYTECODE: ::ethers::core::types::Bytes =
+        ::ethers::core::types::Bytes::from_static(__BYTECODE);
+    #[rustfmt::skip]
+    const __DEPLOYED_BYTECODE: &[u8] = b"4a\0`W`\x046\x10a\0`W`\05`\xE0\x1C\x80c\x16\xDC\x16[\x14a\0eW\x80c\xB7\xD1\x9F\xC4\x14a\0qW\x80c\x08\xEA\xBD\xDA\x14a\0}W\x80c\xFA\xDF\xA6[\x14a\0\x89W\x80c\xCApk\xCF\x14a\0\x95W\x80c\xD0\x04\xF0\xF7\x14a\x01\x1FW\x80c\x9C\xD4A\xDA\x14a\x02\xDEW[`\0\x80\xFD[`\0T`\0R` `\0\xF3[`\x01T`\0R` `\0\xF3[`\x02T`\0R` `\0\xF3[`\x03T`\0R` `\0\xF3[`\x045\x80a\x01\0R\x80`\0T\x14a\0\xB3W`\x01T\x14a\0\xD4Wa\0`V[P`\x02Ta\x01\x80R`\x03Ta\x01\xA0R`\x01Ta\x01 R`\x01a\x01\xC0Ra\0\xF0V[`\x03Ta\x01\x80R`\x02Ta\x01\xA0R`\0Ta\x01 R`\0a\x01\xC0R[`$5\x80a\x01@Ra\x03\xE5\x02\x80a\x01\xA0Q\x02\x90a\x01\x80Qa\x03\xE8\x02\x01\x90\x04a\x01`Ra\x01`Q`\0R` `\0\xF3[`\x045\x80a\x01\0R\x80`\0T\x14a\x01=W`\x01T\x14a\x01^Wa\0`V[P`\x02Ta\x01\x80R`\x03Ta\x01\xA0R`\x01Ta\x01 R`\x01a\x01\xC0Ra\x01zV[`\x03Ta\x01\x80R`\x02Ta\x01\xA0R`\0Ta\x01 R`\0a\x01\xC0R[`$5\x80a\x01@Ra\x03\xE5\x02\x80a\x01\xA0Q\x02\x90a\x01\x80Qa\x03\xE8\x02\x01\x90\x04a\x01`Ra\x01`Q\x15a\0`W\x7F#\xB8r\xDD\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04R0`$Ra\x01@Q`DR`\0`\xC0R` `\xC0`d`\0`\0a\x01\0QZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`W\x7F\xA9\x05\x9C\xBB\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04Ra\x01`Q`$R`\0`\xC0R` `\xC0`D`\0`\0a\x01 QZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`Wa\x01\xC0Qa\x02tW`\x03Ta\x01@Q\x01`\x03Ua\x01`Q`\x02T\x03`\x02Ua\x02\x8BV[`\x02Ta\x01@Q\x01`\x02Ua\x01`Q`\x03T\x03`\x03U[a\x01\0Q`\0Ra\x01 Q` Ra\x01@Q`@Ra\x01`Q``R3`\x80R\x7F\xB3\x9C\x9B\xC4?\x81\x1E\x1A|\xE1Y\xC5\xF1GE\x8F\xDB\x80&k\xF2<\x172 \x131n'\xE0\x86\xD0`\xA0`\0\xA1a\x01`Q`\0R` `\0\xF3[\x7F#\xB8r\xDD\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04R0`$R`\x045`DR`\0`\xC0R` `\xC0`d`\0`\0`\0TZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`W\x7F#\xB8r\xDD\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0`\0R3`\x04R0`$R`$5`DR`\0`\xC0R` `\xC0`d`\0`\0`\x01TZ\xF1\x15a\0`W`\xC0Q`\x01\x14\x15a\0`W`\x02T`\x045\x01`\x02U`\x03T`$5\x01`\x03U3`\0R`\x045` R`$5`@R\x7F\xAC\x1Dvt\x9ETG\xB7\xB1oZ\xB6\x14G\xE1\xBDP/;\xB4\x80z\xF3\xB2\x8Eb\r\x17\0\xA6\xEEE```\0\xA1\0";
+    /// The deployed bytecode of the contract.
+    pub static CONSTANTPRODUCTPOOL_DEPLOYED_BYTECODE: ::ethers::core::types::Bytes =
+        ::ethers::core::types::Bytes::from_static(__DEPLOYED_BYTECODE);
+    pub struct ConstantProductPool<M>(::ethers::contract::Contract<M>);
+    impl<M> ::core::clone::Clone for ConstantProductPool<M> {
+        fn clone(&self) -> Self {
+            Self(::core::clone::Clone::clone(&self.0))
+        }
+    }
+    impl<M> ::core::ops::Deref for ConstantProductPool<M> {
+        type Target = ::ethers::contract::Contract<M>;
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+    impl<M> ::core::ops::DerefMut for ConstantProductPool<M> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+    impl<M> ::core::fmt::Debug for ConstantProductPool<M> {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_tuple(::core::stringify!(ConstantProductPool))
+                .field(&self.address())
+                .finish()
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> ConstantProductPool<M> {
+        /// Creates a new contract instance with the specified `ethers` client
+        /// at `address`. The contract derefs to a `ethers::Contract`
+        /// object.
+        pub fn new<T: Into<::ethers::core::types::Address>>(
+            address: T,
+            client: ::std::sync::Arc<M>,
+        ) -> Self {
+            Self(::ethers::contract::Contract::new(
+                address.into(),
+                CONSTANTPRODUCTPOOL_ABI.clone(),
+                client,
+            ))
+        }
+        /// Constructs the general purpose `Deployer` instance based on the
+        /// provided constructor arguments and sends it. Returns a new
+        /// instance of a deployer that returns an instance of this contract
+        /// after sending the transaction
+        ///
+        /// Notes:
+        /// - If there are no constructor arguments, you should pass `()` as the
+        ///   argument.
+        /// - The default poll duration is 7 seconds.
+        /// - The default number of confirmations is 1 block.
+        ///
+        ///
+        /// # Example
+        ///
+        /// Generate contract bindings with `abigen!` and deploy a new contract
+        /// instance.
+        ///
+        /// *Note*: this requires a `bytecode` and `abi` object in the
+        /// `greeter.json` artifact.
+        ///
+        /// ```ignore
+        /// # async fn deploy<M: ethers::providers::Middleware>(client: ::std::sync::Arc<M>) {
+        ///     abigen!(Greeter, "../greeter.json");
+        ///
+        ///    let greeter_contract = Greeter::deploy(client, "Hello world!".to_string()).unwrap().send().await.unwrap();
+        ///    let msg = greeter_contract.greet().call().await.unwrap();
+        /// # }
+        /// ```
+        pub fn deploy<T: ::ethers::core::abi::Tokenize>(
+            client: ::std::sync::Arc<M>,
+            constructor_args: T,
+        ) -> ::core::result::Result<
+            ::ethers::contract::builders::ContractDeployer<M, Self>,
+            ::ethers::contract::ContractError<M>,
+        > {
+            let factory = ::ethers::contract::ContractFactory::new(
+                CONSTANTPRODUCTPOOL_ABI.clone(),
+                CONSTANTPRODUCTPOOL_BYTECODE.clone().into(),
+                client,
+            );
+            let deployer = factory.deploy(constructor_args)?;
+            let deployer = ::ethers::contract::ContractDeployer::new(deployer);
+            Ok(deployer)
+        }
+        /// Calls the contract's `addLiquidity` (0x9cd441da) function
+        pub fn add_liquidity(
+            &self,
+            amount_x: ::ethers::core::types::U256,
+            amount_y: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ()> {
+            self.0
+                .method_hash([156, 212, 65, 218], (amount_x, amount_y))
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `getAmountOut` (0xca706bcf) function
+        pub fn get_amount_out(
+            &self,
+            token_in: ::ethers::core::types::Address,
+            amount_in: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([202, 112, 107, 207], (token_in, amount_in))
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `reserveX` (0x08eabdda) function
+        pub fn reserve_x(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([8, 234, 189, 218], ())
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `reserveY` (0xfadfa65b) function
+        pub fn reserve_y(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([250, 223, 166, 91], ())
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `swap` (0xd004f0f7) function
+        pub fn swap(
+            &self,
+            token_in: ::ethers::core::types::Address,
+            amount_in: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([208, 4, 240, 247], (token_in, amount_in))
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `tokenX` (0x16dc165b) function
+        pub fn token_x(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::Address> {
+            self.0
+                .method_hash([22, 220, 22, 91], ())
+                .expect("method not found (this should never happen)")
+        }
+        /// Calls the contract's `tokenY` (0xb7d19fc4) function
+        pub fn token_y(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::Address> {
+            self.0
+                .method_hash([183, 209, 159, 196], ())
+                .expect("method not found (this should never happen)")
+        }
+        /// Gets the contract's `LiquidityAdded` event
+        pub fn liquidity_added_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, LiquidityAddedFilter>
+        {
+            self.0.event()
+        }
+        /// Gets the contract's `Swap` event
+        pub fn swap_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, SwapFilter> {
+            self.0.event()
+        }
+        /// Returns an `Event` builder for all the events of this contract.
+        pub fn events(
+            &self,
+        ) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, ConstantProductPoolEvents>
+        {
+            self.0
+                .event_with_filter(::core::default::Default::default())
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> From<::ethers::contract::Contract<M>>
+        for ConstantProductPool<M>
+    {
+        fn from(contract: ::ethers::contract::Contract<M>) -> Self {
+            Self::new(contract.address(), contract.client())
+        }
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethevent(
+        name = "LiquidityAdded",
+        abi = "LiquidityAdded(address,uint256,uint256)"
+    )]
+    pub struct LiquidityAddedFilter {
+        pub provider: ::ethers::core::types::Address,
+        pub amount_x: ::ethers::core::types::U256,
+        pub amount_y: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethevent(name = "Swap", abi = "Swap(address,address,uint256,uint256,address)")]
+    pub struct SwapFilter {
+        pub token_in: ::ethers::core::types::Address,
+        pub token_out: ::ethers::core::types::Address,
+        pub amount_in: ::ethers::core::types::U256,
+        pub amount_out: ::ethers::core::types::U256,
+        pub to: ::ethers::core::types::Address,
+    }
+    /// Container type for all of the contract's events
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum ConstantProductPoolEvents {
+        LiquidityAddedFilter(LiquidityAddedFilter),
+        SwapFilter(SwapFilter),
+    }
+    impl ::ethers::contract::EthLogDecode for ConstantProductPoolEvents {
+        fn decode_log(
+            log: &::ethers::core::abi::RawLog,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::Error> {
+            if let Ok(decoded) = LiquidityAddedFilter::decode_log(log) {
+                return Ok(ConstantProductPoolEvents::LiquidityAddedFilter(decoded));
+            }
+            if let Ok(decoded) = SwapFilter::decode_log(log) {
+                return Ok(ConstantProductPoolEvents::SwapFilter(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData)
+        }
+    }
+    impl ::core::fmt::Display for ConstantProductPoolEvents {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::LiquidityAddedFilter(element) => ::core::fmt::Display::fmt(element, f),
+                Self::SwapFilter(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<LiquidityAddedFilter> for ConstantProductPoolEvents {
+        fn from(value: LiquidityAddedFilter) -> Self {
+            Self::LiquidityAddedFilter(value)
+        }
+    }
+    impl ::core::convert::From<SwapFilter> for ConstantProductPoolEvents {
+        fn from(value: SwapFilter) -> Self {
+            Self::SwapFilter(value)
+        }
+    }
+    /// Container type for all input parameters for the `addLiquidity` function
+    /// with signature `addLiquidity(uint256,uint256)` and selector `0x9cd441da`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "addLiquidity", abi = "addLiquidity(uint256,uint256)")]
+    pub struct AddLiquidityCall {
+        pub amount_x: ::ethers::core::types::U256,
+        pub amount_y: ::ethers::core::types::U256,
+    }
+    /// Container type for all input parameters for the `getAmountOut` function
+    /// with signature `getAmountOut(address,uint256)` and selector `0xca706bcf`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "getAmountOut", abi = "getAmountOut(address,uint256)")]
+    pub struct GetAmountOutCall {
+        pub token_in: ::ethers::core::types::Address,
+        pub amount_in: ::ethers::core::types::U256,
+    }
+    /// Container type for all input parameters for the `reserveX` function with
+    /// signature `reserveX()` and selector `0x08eabdda`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "reserveX", abi = "reserveX()")]
+    pub struct ReserveXCall;
+    /// Container type for all input parameters for the `reserveY` function with
+    /// signature `reserveY()` and selector `0xfadfa65b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "reserveY", abi = "reserveY()")]
+    pub struct ReserveYCall;
+    /// Container type for all input parameters for the `swap` function with
+    /// signature `swap(address,uint256)` and selector `0xd004f0f7`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "swap", abi = "swap(address,uint256)")]
+    pub struct SwapCall {
+        pub token_in: ::ethers::core::types::Address,
+        pub amount_in: ::ethers::core::types::U256,
+    }
+    /// Container type for all input parameters for the `tokenX` function with
+    /// signature `tokenX()` and selector `0x16dc165b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "tokenX", abi = "tokenX()")]
+    pub struct TokenXCall;
+    /// Container type for all input parameters for the `tokenY` function with
+    /// signature `tokenY()` and selector `0xb7d19fc4`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    #[ethcall(name = "tokenY", abi = "tokenY()")]
+    pub struct TokenYCall;
+    /// Container type for all of the contract's call
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum ConstantProductPoolCalls {
+        AddLiquidity(AddLiquidityCall),
+        GetAmountOut(GetAmountOutCall),
+        ReserveX(ReserveXCall),
+        ReserveY(ReserveYCall),
+        Swap(SwapCall),
+        TokenX(TokenXCall),
+        TokenY(TokenYCall),
+    }
+    impl ::ethers::core::abi::AbiDecode for ConstantProductPoolCalls {
+        fn decode(
+            data: impl AsRef<[u8]>,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::AbiError> {
+            let data = data.as_ref();
+            if let Ok(decoded) = <AddLiquidityCall as ::ethers::core::abi::AbiDecode>::decode(data)
+            {
+                return Ok(Self::AddLiquidity(decoded));
+            }
+            if let Ok(decoded) = <GetAmountOutCall as ::ethers::core::abi::AbiDecode>::decode(data)
+            {
+                return Ok(Self::GetAmountOut(decoded));
+            }
+            if let Ok(decoded) = <ReserveXCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::ReserveX(decoded));
+            }
+            if let Ok(decoded) = <ReserveYCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::ReserveY(decoded));
+            }
+            if let Ok(decoded) = <SwapCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::Swap(decoded));
+            }
+            if let Ok(decoded) = <TokenXCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::TokenX(decoded));
+            }
+            if let Ok(decoded) = <TokenYCall as ::ethers::core::abi::AbiDecode>::decode(data) {
+                return Ok(Self::TokenY(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData.into())
+        }
+    }
+    impl ::ethers::core::abi::AbiEncode for ConstantProductPoolCalls {
+        fn encode(self) -> Vec<u8> {
+            match self {
+                Self::AddLiquidity(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::GetAmountOut(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::ReserveX(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::ReserveY(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::Swap(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::TokenX(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::TokenY(element) => ::ethers::core::abi::AbiEncode::encode(element),
+            }
+        }
+    }
+    impl ::core::fmt::Display for ConstantProductPoolCalls {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::AddLiquidity(element) => ::core::fmt::Display::fmt(element, f),
+                Self::GetAmountOut(element) => ::core::fmt::Display::fmt(element, f),
+                Self::ReserveX(element) => ::core::fmt::Display::fmt(element, f),
+                Self::ReserveY(element) => ::core::fmt::Display::fmt(element, f),
+                Self::Swap(element) => ::core::fmt::Display::fmt(element, f),
+                Self::TokenX(element) => ::core::fmt::Display::fmt(element, f),
+                Self::TokenY(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<AddLiquidityCall> for ConstantProductPoolCalls {
+        fn from(value: AddLiquidityCall) -> Self {
+            Self::AddLiquidity(value)
+        }
+    }
+    impl ::core::convert::From<GetAmountOutCall> for ConstantProductPoolCalls {
+        fn from(value: GetAmountOutCall) -> Self {
+            Self::GetAmountOut(value)
+        }
+    }
+    impl ::core::convert::From<ReserveXCall> for ConstantProductPoolCalls {
+        fn from(value: ReserveXCall) -> Self {
+            Self::ReserveX(value)
+        }
+    }
+    impl ::core::convert::From<ReserveYCall> for ConstantProductPoolCalls {
+        fn from(value: ReserveYCall) -> Self {
+            Self::ReserveY(value)
+        }
+    }
+    impl ::core::convert::From<SwapCall> for ConstantProductPoolCalls {
+        fn from(value: SwapCall) -> Self {
+            Self::Swap(value)
+        }
+    }
+    impl ::core::convert::From<TokenXCall> for ConstantProductPoolCalls {
+        fn from(value: TokenXCall) -> Self {
+            Self::TokenX(value)
+        }
+    }
+    impl ::core::convert::From<TokenYCall> for ConstantProductPoolCalls {
+        fn from(value: TokenYCall) -> Self {
+            Self::TokenY(value)
+        }
+    }
+    /// Container type for all return fields from the `getAmountOut` function
+    /// with signature `getAmountOut(address,uint256)` and selector `0xca706bcf`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct GetAmountOutReturn(pub ::ethers::core::types::U256);
+    /// Container type for all return fields from the `reserveX` function with
+    /// signature `reserveX()` and selector `0x08eabdda`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct ReserveXReturn(pub ::ethers::core::types::U256);
+    /// Container type for all return fields from the `reserveY` function with
+    /// signature `reserveY()` and selector `0xfadfa65b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct ReserveYReturn(pub ::ethers::core::types::U256);
+    /// Container type for all return fields from the `swap` function with
+    /// signature `swap(address,uint256)` and selector `0xd004f0f7`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct SwapReturn(pub ::ethers::core::types::U256);
+    /// Container type for all return fields from the `tokenX` function with
+    /// signature `tokenX()` and selector `0x16dc165b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct TokenXReturn(pub ::ethers::core::types::Address);
+    /// Container type for all return fields from the `tokenY` function with
+    /// signature `tokenY()` and selector `0xb7d19fc4`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+    )]
+    pub struct TokenYReturn(pub ::ethers::core::types::Address);
+}
@@ -1,6 +1,8 @@
 #![allow(clippy::all)]
 #![allow(missing_docs)]
 
+pub mod arbiter_bridge;
 pub mod arbiter_math;
 pub mod arbiter_token;
+pub mod constant_product_pool;
 pub mod liquid_exchange;
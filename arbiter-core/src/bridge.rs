@@ -0,0 +1,313 @@
+//! The `bridge` module provides a way to pass messages between two
+//! [`Environment`]s, e.g., to model a rollup and the L1 it settles to.
+//!
+//! A [`Bridge`] watches the events emitted in a source [`Environment`] via its
+//! `EventBroadcaster`. Every log that matches the bridge's [`Filter`] is handed
+//! to a user supplied relay function that decides what message, if any, should
+//! be delivered. After a configurable amount of blocks has passed in the source
+//! [`Environment`], the message is delivered as a transaction into the
+//! destination [`Environment`]'s socket on behalf of a relayer address. Queued
+//! messages are delivered as soon as the source [`Environment`] finishes the
+//! block they are due in, whether or not it emits further events.
+//!
+//! Bridges are created and attached to environments via the [`Manager`].
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossbeam_channel::{select, unbounded, RecvTimeoutError};
+use ethers::types::{Address, Bytes, Filter, FilteredParams, Log, U64};
+use log::{info, warn};
+use revm::primitives::{ExecutionResult, TransactTo, TxEnv, B160, U256};
+use thiserror::Error;
+
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::manager::Manager;
+use crate::{
+    environment::{
+        AtomicState, EmittedLogs, Environment, Instruction, InstructionSender, State,
+        TransactionOutcome,
+    },
+    middleware::revm_logs_to_ethers_logs,
+};
+
+/// Alias for the function that turns a log emitted in the source
+/// [`Environment`] into a [`BridgeMessage`] for the destination
+/// [`Environment`]. Returning `None` drops the log.
+pub type Relay = Box<dyn Fn(&Log) -> Option<BridgeMessage> + Send>;
+
+/// Parameters necessary for creating a [`Bridge`].
+#[derive(Debug, Clone)]
+pub struct BridgeParameters {
+    /// The filter that logs emitted in the source [`Environment`] must match
+    /// in order to be relayed.
+    pub filter: Filter,
+
+    /// The amount of blocks in the source [`Environment`] that must pass
+    /// before a message is delivered to the destination [`Environment`].
+    pub block_delay: u64,
+
+    /// The address that delivered transactions are sent from in the
+    /// destination [`Environment`].
+    pub relayer: Address,
+}
+
+/// A message to be delivered as a transaction into the destination
+/// [`Environment`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeMessage {
+    /// The contract in the destination [`Environment`] that is called.
+    pub to: Address,
+
+    /// The calldata of the delivered transaction.
+    pub data: Bytes,
+}
+
+/// Errors that can occur while relaying messages with a [`Bridge`].
+#[derive(Error, Debug, Clone)]
+pub enum BridgeError {
+    /// [`BridgeError::Communication`] is thrown when the channel to either of
+    /// the bridged environments was closed while delivering a message.
+    #[error("error communicating! the source error is: {0}")]
+    Communication(String),
+
+    /// [`BridgeError::EventBroadcaster`] is thrown when the bridge could not
+    /// subscribe to the events of its source [`Environment`].
+    #[error("error subscribing to the environment's events! due to: {0}")]
+    EventBroadcaster(String),
+
+    /// [`BridgeError::ThreadPanic`] is thrown when the thread of the bridge
+    /// has panicked.
+    #[error("joining on the bridge thread resulted in a panic")]
+    ThreadPanic,
+}
+
+/// Relays messages from a source [`Environment`] to a destination
+/// [`Environment`] on a separate thread.
+pub struct Bridge {
+    /// The label of the [`Environment`] whose events are watched.
+    pub source: String,
+
+    /// The label of the [`Environment`] messages are delivered to.
+    pub destination: String,
+
+    /// The [`BridgeParameters`] this [`Bridge`] was created with.
+    pub parameters: BridgeParameters,
+
+    /// [`JoinHandle`] for the thread in which the [`Bridge`] is relaying.
+    /// The thread finishes once either of the bridged environments is
+    /// stopped, which joins it.
+    pub(crate) handle: Option<JoinHandle<Result<(), BridgeError>>>,
+}
+
+impl Debug for Bridge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bridge")
+            .field("source", &self.source)
+            .field("destination", &self.destination)
+            .field("parameters", &self.parameters)
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
+/// A [`BridgeMessage`] waiting in the queue of a [`Bridge`].
+struct PendingMessage {
+    /// The block of the source [`Environment`] from which on the message may
+    /// be delivered.
+    deliver_at: U64,
+
+    /// The message to deliver.
+    message: BridgeMessage,
+}
+
+impl Bridge {
+    /// Privately accessible constructor function for creating a [`Bridge`].
+    /// Subscribes to the events of the `source` [`Environment`] and starts
+    /// relaying on a new thread. This function should be accessed by the
+    /// [`Manager`]. Fails if the events of the `source` cannot be subscribed
+    /// to.
+    pub(crate) fn new(
+        source: &Environment,
+        destination: &Environment,
+        parameters: BridgeParameters,
+        relay: Relay,
+    ) -> Result<Self, BridgeError> {
+        let (event_sender, event_receiver) = unbounded();
+        let (header_sender, header_receiver) = unbounded();
+        {
+            let mut event_broadcaster = source
+                .socket
+                .event_broadcaster
+                .lock()
+                .map_err(|e| BridgeError::EventBroadcaster(format!("{:?}", e)))?;
+            event_broadcaster.add_sender(event_sender);
+            event_broadcaster.add_header_sender(header_sender);
+        }
+
+        let source_state = Arc::clone(&source.state);
+        let destination_state = Arc::clone(&destination.state);
+        let instruction_sender = destination.socket.instruction_sender.clone();
+        let filtered_params = FilteredParams::new(Some(parameters.filter.clone()));
+        let block_delay = parameters.block_delay;
+        let relayer = B160::from(parameters.relayer.to_fixed_bytes());
+        let label = format!("{} -> {}", source.label, destination.label);
+
+        let handle = thread::spawn(move || {
+            let mut queue: VecDeque<PendingMessage> = VecDeque::new();
+            loop {
+                // The block the source `Environment` is known to have reached
+                let reached = select! {
+                    recv(event_receiver) -> emitted_logs => match emitted_logs {
+                        Ok(EmittedLogs {
+                            logs, block_number, ..
+                        }) => {
+                            for log in revm_logs_to_ethers_logs(logs) {
+                                if !(filtered_params.filter_address(&log)
+                                    && filtered_params.filter_topics(&log))
+                                {
+                                    continue;
+                                }
+                                if let Some(message) = relay(&log) {
+                                    queue.push_back(PendingMessage {
+                                        deliver_at: block_number + block_delay,
+                                        message,
+                                    });
+                                }
+                            }
+                            Some(block_number)
+                        }
+                        Err(_) => break,
+                    },
+                    // A finished block means the source has moved on to the next one
+                    recv(header_receiver) -> header => match header {
+                        Ok(header) => Some(header.number + 1),
+                        Err(_) => break,
+                    },
+                    default(Duration::from_millis(100)) => None,
+                };
+                if let Some(block_number) = reached {
+                    // Messages are queued in order of their source block, so only the front
+                    // of the queue has to be checked
+                    while queue
+                        .front()
+                        .is_some_and(|pending| pending.deliver_at <= block_number)
+                    {
+                        let pending = queue.pop_front().unwrap();
+                        deliver(
+                            &label,
+                            &instruction_sender,
+                            &destination_state,
+                            relayer,
+                            pending.message,
+                        )?;
+                    }
+                }
+                if is_stopped(&source_state) || is_stopped(&destination_state) {
+                    break;
+                }
+            }
+            if !queue.is_empty() {
+                warn!(
+                    "Bridge {} stopped with {} undelivered messages",
+                    label,
+                    queue.len()
+                );
+            }
+            Ok(())
+        });
+
+        Ok(Self {
+            source: source.label.clone(),
+            destination: destination.label.clone(),
+            parameters,
+            handle: Some(handle),
+        })
+    }
+
+    /// Waits for the relaying thread to finish, which it does once either of
+    /// the bridged environments has been stopped.
+    pub(crate) fn join(&mut self) -> Result<(), BridgeError> {
+        match self.handle.take() {
+            Some(handle) => handle.join().unwrap_or(Err(BridgeError::ThreadPanic)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Sends a [`BridgeMessage`] as a transaction into the destination
+/// [`Environment`] and waits for its result.
+fn deliver(
+    label: &str,
+    instruction_sender: &InstructionSender,
+    destination_state: &AtomicState,
+    relayer: B160,
+    message: BridgeMessage,
+) -> Result<(), BridgeError> {
+    let tx_env = TxEnv {
+        caller: relayer,
+        gas_limit: u64::MAX,
+        gas_price: U256::ZERO,
+        gas_priority_fee: None,
+        transact_to: TransactTo::Call(B160::from(message.to.to_fixed_bytes())),
+        value: U256::ZERO,
+        data: bytes::Bytes::from(message.data.to_vec()),
+        chain_id: None,
+        nonce: None,
+        access_list: Vec::new(),
+    };
+    let (result_sender, result_receiver) = unbounded();
+    instruction_sender
+        .send(Instruction::Transaction(
+            true,
+            Box::new(tx_env),
+            result_sender,
+        ))
+        .map_err(|e| BridgeError::Communication(format!("{:?}", e)))?;
+
+    // The destination may be stopped before it gets to the message, so do not
+    // wait on it forever
+    let revm_result = loop {
+        match result_receiver.recv_timeout(Duration::from_millis(100)) {
+            Ok(revm_result) => break revm_result,
+            Err(RecvTimeoutError::Timeout) if !is_stopped(destination_state) => continue,
+            Err(e) => return Err(BridgeError::Communication(format!("{:?}", e))),
+        }
+    };
+    match revm_result.outcome {
+        TransactionOutcome::Success(ExecutionResult::Success { .. }) => {
+            info!(
+                "Bridge {} delivered a message to {:?} in block {}",
+                label, message.to, revm_result.block_number
+            );
+        }
+        TransactionOutcome::Success(result) => {
+            warn!(
+                "Bridge {} failed to deliver a message to {:?}: {:?}",
+                label, message.to, result
+            );
+        }
+        TransactionOutcome::Error(e) => {
+            warn!(
+                "Bridge {} failed to deliver a message to {:?}: {}",
+                label, message.to, e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether an [`Environment`] has been stopped.
+#[inline]
+fn is_stopped(state: &AtomicState) -> bool {
+    state.load(std::sync::atomic::Ordering::SeqCst) == State::Stopped
+}
@@ -0,0 +1,255 @@
+//! The `clock` module provides a global clock that keeps a group of
+//! [`Environment`]s in lockstep.
+//!
+//! Related markets are often simulated in separate environments, but they
+//! should still agree on what block they are in and what time it is. A
+//! [`Clock`] acts as a barrier at each block boundary: no [`Environment`] in
+//! the group may move on to block `N + 1` until every member has finished
+//! block `N`. The shared [`SimulationTime`] can be read at any point by agents
+//! holding a clone of the [`Clock`].
+//!
+//! Since an [`Environment`] only finishes a block as transactions come in,
+//! every member of the group has to keep finishing blocks for the others to
+//! move on. An [`Environment`] without traffic of its own holds back every
+//! other [`Environment`] on its [`Clock`], so it should either be mined
+//! explicitly via [`RevmMiddleware::mine`] or be stopped, which removes it
+//! from the [`Clock`].
+//!
+//! A [`Clock`] can also be suspended, e.g., while contracts are deployed into
+//! its environments before a simulation starts. While suspended, every
+//! [`Environment`] moves on to a new block as soon as it has finished its
+//! current one, which skips the block numbers the others have used in the
+//! meantime, and lockstep picks up again once the [`Clock`] is resumed.
+//!
+//! Clocks are created and attached to environments via the [`Manager`].
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use log::info;
+use thiserror::Error;
+
+use crate::environment::{AtomicState, State};
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::{environment::Environment, manager::Manager, middleware::RevmMiddleware};
+
+/// Parameters necessary for creating a [`Clock`].
+#[derive(Debug, Clone, Copy)]
+pub struct ClockParameters {
+    /// The timestamp (in seconds) of block zero for every [`Environment`]
+    /// synchronized by the [`Clock`].
+    pub genesis_timestamp: u64,
+
+    /// The amount of seconds that pass between two consecutive blocks.
+    pub block_time: u64,
+}
+
+/// A snapshot of the shared simulation time given by a [`Clock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationTime {
+    /// The block number that every synchronized [`Environment`] is currently
+    /// building.
+    pub block_number: u64,
+
+    /// The timestamp (in seconds) of the current block.
+    pub timestamp: u64,
+}
+
+/// Errors that can occur while keeping environments in lockstep with a
+/// [`Clock`].
+#[derive(Error, Debug)]
+pub enum ClockError {
+    /// [`ClockError::Lock`] is thrown when the state of the [`Clock`] cannot be
+    /// locked because a thread panicked while holding it.
+    #[error("failed to gain the clock lock! due to: {0}")]
+    Lock(String),
+}
+
+/// A global clock that coordinates block boundaries across a group of
+/// [`Environment`]s.
+///
+/// Cloning a [`Clock`] is cheap and every clone refers to the same underlying
+/// clock, so a clone can be handed to any agent that needs to read the shared
+/// [`SimulationTime`].
+#[derive(Debug, Clone)]
+pub struct Clock {
+    parameters: ClockParameters,
+    inner: Arc<(Mutex<ClockState>, Condvar)>,
+}
+
+/// The mutable state of a [`Clock`] that is guarded by its [`Mutex`].
+#[derive(Debug)]
+struct ClockState {
+    /// The labels of the [`Environment`]s that are synchronized by the
+    /// [`Clock`].
+    participants: HashSet<String>,
+
+    /// The labels of the [`Environment`]s that have finished the current block
+    /// and are waiting to move on to the next.
+    arrived: HashSet<String>,
+
+    /// The block number that is currently being built.
+    block_number: u64,
+
+    /// Whether the environments currently move on without waiting for each
+    /// other.
+    suspended: bool,
+}
+
+impl Clock {
+    /// Privately accessible constructor function for creating a [`Clock`].
+    /// This function should be accessed by the [`Manager`].
+    pub(crate) fn new(parameters: ClockParameters) -> Self {
+        Self {
+            parameters,
+            inner: Arc::new((
+                Mutex::new(ClockState {
+                    participants: HashSet::new(),
+                    arrived: HashSet::new(),
+                    block_number: 0,
+                    suspended: false,
+                }),
+                Condvar::new(),
+            )),
+        }
+    }
+
+    /// Returns the [`ClockParameters`] this [`Clock`] was created with.
+    pub fn parameters(&self) -> ClockParameters {
+        self.parameters
+    }
+
+    /// Returns the current [`SimulationTime`] shared by every synchronized
+    /// [`Environment`].
+    pub fn now(&self) -> Result<SimulationTime, ClockError> {
+        let block_number = self.lock()?.block_number;
+        Ok(self.time_at(block_number))
+    }
+
+    /// Locks the [`ClockState`].
+    fn lock(&self) -> Result<MutexGuard<'_, ClockState>, ClockError> {
+        let (lock, _) = &*self.inner;
+        lock.lock()
+            .map_err(|e| ClockError::Lock(format!("{:?}", e)))
+    }
+
+    /// Computes the [`SimulationTime`] for a given block number.
+    fn time_at(&self, block_number: u64) -> SimulationTime {
+        SimulationTime {
+            block_number,
+            timestamp: self.parameters.genesis_timestamp
+                + block_number * self.parameters.block_time,
+        }
+    }
+
+    /// Registers the [`Environment`] with the given label with the [`Clock`].
+    pub(crate) fn join(&self, label: &str) -> Result<(), ClockError> {
+        self.lock()?.participants.insert(label.to_string());
+        Ok(())
+    }
+
+    /// Removes the [`Environment`] with the given label from the [`Clock`] so
+    /// that the remaining environments do not wait on it any longer. From then
+    /// on, the [`Environment`] moves on to a new block by itself, even if it
+    /// is waiting on the [`Clock`] at the time.
+    pub(crate) fn leave(&self, label: &str) -> Result<(), ClockError> {
+        let mut clock_state = self.lock()?;
+        clock_state.participants.remove(label);
+        clock_state.arrived.remove(label);
+        if !clock_state.participants.is_empty()
+            && clock_state.arrived.len() >= clock_state.participants.len()
+        {
+            clock_state.arrived.clear();
+            clock_state.block_number += 1;
+            info!("Clock advanced to block {}", clock_state.block_number);
+        }
+        self.release();
+        Ok(())
+    }
+
+    /// Lets every synchronized [`Environment`] move on to a new block without
+    /// waiting for the others until the [`Clock`] is resumed. Environments
+    /// that are waiting on the [`Clock`] are released.
+    pub(crate) fn suspend(&self) -> Result<(), ClockError> {
+        let mut clock_state = self.lock()?;
+        clock_state.suspended = true;
+        if !clock_state.arrived.is_empty() {
+            clock_state.arrived.clear();
+            clock_state.block_number += 1;
+            info!("Clock advanced to block {}", clock_state.block_number);
+        }
+        self.release();
+        Ok(())
+    }
+
+    /// Keeps the synchronized environments in lockstep again from the next
+    /// block boundary on.
+    pub(crate) fn resume(&self) -> Result<(), ClockError> {
+        self.lock()?.suspended = false;
+        Ok(())
+    }
+
+    /// Wakes up every [`Environment`] waiting on the [`Clock`] so that they may
+    /// check whether they have been stopped.
+    pub(crate) fn release(&self) {
+        let (_, cvar) = &*self.inner;
+        cvar.notify_all();
+    }
+
+    /// Called by the [`Environment`] with the given label once it has finished
+    /// its current block, the one numbered `block_number`. Blocks until every
+    /// synchronized [`Environment`] has finished the same block and returns
+    /// the [`SimulationTime`] of the next block. An [`Environment`] that has
+    /// left the [`Clock`] does not wait and simply moves on to the block after
+    /// its current one.
+    ///
+    /// Returns `None` if the [`Environment`] was stopped while waiting.
+    pub(crate) fn tick(
+        &self,
+        label: &str,
+        block_number: u64,
+        state: &AtomicState,
+    ) -> Result<Option<SimulationTime>, ClockError> {
+        let (_, cvar) = &*self.inner;
+        let mut clock_state = self.lock()?;
+        if !clock_state.participants.contains(label) {
+            return Ok(Some(self.time_at(block_number + 1)));
+        }
+        let target = clock_state.block_number + 1;
+        if clock_state.suspended {
+            clock_state.block_number = target;
+            return Ok(Some(self.time_at(target)));
+        }
+        clock_state.arrived.insert(label.to_string());
+        if clock_state.arrived.len() >= clock_state.participants.len() {
+            clock_state.arrived.clear();
+            clock_state.block_number = target;
+            info!("Clock advanced to block {}", target);
+            cvar.notify_all();
+            return Ok(Some(self.time_at(target)));
+        }
+        while clock_state.block_number < target {
+            if state.load(std::sync::atomic::Ordering::SeqCst) == State::Stopped {
+                clock_state.arrived.remove(label);
+                return Ok(None);
+            }
+            if !clock_state.participants.contains(label) {
+                return Ok(Some(self.time_at(block_number + 1)));
+            }
+            // The timeout only guards against a missed notification, the
+            // `Condvar` is otherwise alerted on every change of the clock.
+            clock_state = cvar
+                .wait_timeout(clock_state, Duration::from_millis(100))
+                .map_err(|e| ClockError::Lock(format!("{:?}", e)))?
+                .0;
+        }
+        Ok(Some(self.time_at(target)))
+    }
+}
@@ -0,0 +1,353 @@
+//! The `differential` module provides a harness that checks a Rust model of a
+//! contract against the contract itself.
+//!
+//! A [`DifferentialTest`] draws inputs from a seeded generator and evaluates
+//! both the model and a contract method of any abigen binding on each of them,
+//! all against the same
+//! [`Environment`](crate::environment::Environment). Both sides must agree on
+//! which inputs fail, i.e., the model returns an error exactly where the
+//! contract reverts, and their outputs must agree up to a [`Tolerance`]. A
+//! contract call that fails for any other reason than a revert, e.g., because
+//! its output cannot be decoded, never agrees with the model.
+//! Everything else is reported as a [`Mismatch`] in the [`DifferentialReport`],
+//! along with the error statistics of the agreeing outputs.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use arbiter_core::{
+//!     bindings::arbiter_math::ArbiterMath,
+//!     differential::{DifferentialTest, Tolerance},
+//!     math::sqrt,
+//!     middleware::RevmMiddleware,
+//! };
+//! use ethers::types::U256;
+//! use rand::Rng;
+//! # use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+//! # use std::sync::Arc;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let mut manager = Manager::new();
+//! # let params = EnvironmentParameters { block_rate: 1.0, seed: 1 };
+//! # manager.add_environment("example_env", params)?;
+//! # manager.start_environment("example_env")?;
+//! # let client = Arc::new(RevmMiddleware::new(manager.environments.get("example_env").unwrap(), None));
+//! let arbiter_math = ArbiterMath::deploy(client, ())?.send().await?;
+//!
+//! let mut test = DifferentialTest::new("sqrt", 1000, 42);
+//! test.tolerance = Tolerance::Absolute(1.0);
+//! let report = test
+//!     .run(
+//!         |rng| U256::from(rng.gen::<u128>()),
+//!         |x| Ok::<_, String>(sqrt(*x)),
+//!         |x| arbiter_math.sqrt(*x),
+//!     )
+//!     .await;
+//! println!("{report}");
+//! assert!(report.passed());
+//! # Ok(())
+//! # }
+//! ```
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::fmt;
+
+use ethers::{
+    abi::Detokenize,
+    contract::{ContractCall, ContractError},
+    providers::Middleware,
+    types::{I256, U256},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{fixed_point::u256_to_f64, middleware::RevmMiddlewareError};
+
+/// How close the outputs of the model and the contract have to be.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// The outputs must be equal.
+    Exact,
+
+    /// The outputs may differ by at most the given amount, in the units of
+    /// the output, e.g., wei.
+    Absolute(f64),
+
+    /// The outputs may differ by at most the given fraction of the output of
+    /// the contract.
+    Relative(f64),
+}
+
+/// Outputs whose distance from one another can be measured so that the error
+/// of a model can be computed.
+pub trait Distance: PartialEq {
+    /// The absolute difference between `self` and `other`.
+    fn distance(&self, other: &Self) -> f64;
+
+    /// The absolute value of `self`, which relative errors are taken of.
+    fn magnitude(&self) -> f64;
+}
+
+impl Distance for U256 {
+    fn distance(&self, other: &Self) -> f64 {
+        u256_to_f64(if self > other {
+            *self - *other
+        } else {
+            *other - *self
+        })
+    }
+
+    fn magnitude(&self) -> f64 {
+        u256_to_f64(*self)
+    }
+}
+
+impl Distance for I256 {
+    fn distance(&self, other: &Self) -> f64 {
+        // The difference of two `int256` always fits in a `uint256`
+        u256_to_f64(if self > other {
+            self.into_raw().overflowing_sub(other.into_raw()).0
+        } else {
+            other.into_raw().overflowing_sub(self.into_raw()).0
+        })
+    }
+
+    fn magnitude(&self) -> f64 {
+        u256_to_f64(self.unsigned_abs())
+    }
+}
+
+macro_rules! impl_distance {
+    ($($t:ty),*) => {
+        $(
+            impl Distance for $t {
+                fn distance(&self, other: &Self) -> f64 {
+                    (*self as f64 - *other as f64).abs()
+                }
+
+                fn magnitude(&self) -> f64 {
+                    (*self as f64).abs()
+                }
+            }
+        )*
+    };
+}
+
+impl_distance!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f64);
+
+impl<A: Distance, B: Distance> Distance for (A, B) {
+    fn distance(&self, other: &Self) -> f64 {
+        self.0.distance(&other.0).max(self.1.distance(&other.1))
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.0.magnitude().max(self.1.magnitude())
+    }
+}
+
+/// An input on which the model and the contract disagree. Errors and reverts
+/// are kept as their messages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch<I, O> {
+    /// The input both were evaluated on.
+    pub input: I,
+
+    /// The output of the model.
+    pub model: Result<O, String>,
+
+    /// The output of the contract.
+    pub contract: Result<O, String>,
+}
+
+/// The outcome of a [`DifferentialTest`].
+#[derive(Debug, Clone)]
+pub struct DifferentialReport<I, O> {
+    /// The name of the test.
+    pub name: String,
+
+    /// The amount of inputs evaluated.
+    pub samples: usize,
+
+    /// The amount of inputs on which the model failed and the contract
+    /// reverted.
+    pub reverts: usize,
+
+    /// The largest absolute error of the outputs both sides returned.
+    pub max_absolute_error: f64,
+
+    /// The largest error of the outputs both sides returned relative to the
+    /// output of the contract.
+    pub max_relative_error: f64,
+
+    /// The mean absolute error of the outputs both sides returned.
+    pub mean_absolute_error: f64,
+
+    /// The inputs on which only one side failed, the contract failed without
+    /// reverting or the outputs are not within the [`Tolerance`].
+    pub mismatches: Vec<Mismatch<I, O>>,
+}
+
+impl<I, O> DifferentialReport<I, O> {
+    /// Whether the model agreed with the contract on every input.
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl<I, O> fmt::Display for DifferentialReport<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} samples, {} reverts, {} mismatches, max absolute error {:e}, max relative \
+             error {:e}, mean absolute error {:e}",
+            self.name,
+            self.samples,
+            self.reverts,
+            self.mismatches.len(),
+            self.max_absolute_error,
+            self.max_relative_error,
+            self.mean_absolute_error
+        )
+    }
+}
+
+/// A comparison of a Rust model with a contract method over seeded inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DifferentialTest {
+    /// The name of the test used in its report.
+    pub name: String,
+
+    /// The amount of inputs to evaluate.
+    pub samples: usize,
+
+    /// The seed of the random number generator handed to the input
+    /// generator.
+    pub seed: u64,
+
+    /// How close the outputs have to be.
+    pub tolerance: Tolerance,
+}
+
+impl DifferentialTest {
+    /// Constructs a new [`DifferentialTest`] that requires exact agreement
+    /// over `samples` inputs generated from `seed`.
+    pub fn new(name: impl Into<String>, samples: usize, seed: u64) -> Self {
+        Self {
+            name: name.into(),
+            samples,
+            seed,
+            tolerance: Tolerance::Exact,
+        }
+    }
+
+    /// Runs the test.
+    ///
+    /// # Arguments
+    ///
+    /// * `generate` - Draws an input from the seeded random number generator.
+    /// * `model` - Evaluates the Rust model on an input, returning an error
+    ///   wherever the contract is expected to revert.
+    /// * `contract` - Builds the call of the contract method for an input,
+    ///   e.g., `|x| contract.method(*x)` for an abigen binding.
+    pub async fn run<I, O, E, M, G, R, C>(
+        &self,
+        mut generate: G,
+        mut model: R,
+        mut contract: C,
+    ) -> DifferentialReport<I, O>
+    where
+        O: Distance + Detokenize,
+        E: fmt::Display,
+        M: Middleware,
+        M::Error: 'static,
+        G: FnMut(&mut StdRng) -> I,
+        R: FnMut(&I) -> Result<O, E>,
+        C: FnMut(&I) -> ContractCall<M, O>,
+    {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut report = DifferentialReport {
+            name: self.name.clone(),
+            samples: self.samples,
+            reverts: 0,
+            max_absolute_error: 0.0,
+            max_relative_error: 0.0,
+            mean_absolute_error: 0.0,
+            mismatches: vec![],
+        };
+        let mut compared = 0;
+        for _ in 0..self.samples {
+            let input = generate(&mut rng);
+            let model_output = model(&input).map_err(|e| e.to_string());
+            let (contract_output, reverted) = match contract(&input).call().await {
+                Ok(output) => (Ok(output), false),
+                Err(e) => (Err(e.to_string()), is_revert(&e)),
+            };
+            let within_tolerance = match (&model_output, &contract_output) {
+                (Ok(model_output), Ok(contract_output)) => {
+                    let absolute_error = model_output.distance(contract_output);
+                    let relative_error = match contract_output.magnitude() {
+                        magnitude if magnitude > 0.0 => absolute_error / magnitude,
+                        _ if absolute_error == 0.0 => 0.0,
+                        _ => f64::INFINITY,
+                    };
+                    compared += 1;
+                    report.mean_absolute_error +=
+                        (absolute_error - report.mean_absolute_error) / compared as f64;
+                    report.max_absolute_error = report.max_absolute_error.max(absolute_error);
+                    report.max_relative_error = report.max_relative_error.max(relative_error);
+                    match self.tolerance {
+                        Tolerance::Exact => model_output == contract_output,
+                        Tolerance::Absolute(tolerance) => absolute_error <= tolerance,
+                        Tolerance::Relative(tolerance) => relative_error <= tolerance,
+                    }
+                }
+                (Err(_), Err(_)) if reverted => {
+                    report.reverts += 1;
+                    true
+                }
+                _ => false,
+            };
+            if !within_tolerance {
+                report.mismatches.push(Mismatch {
+                    input,
+                    model: model_output,
+                    contract: contract_output,
+                });
+            }
+        }
+        report
+    }
+}
+
+/// Whether a contract call failed because the contract reverted, as opposed
+/// to, e.g., its output not decoding. A [`RevmMiddleware`] reports reverts
+/// through its own error rather than a JSON-RPC error response.
+///
+/// [`RevmMiddleware`]: crate::middleware::RevmMiddleware
+fn is_revert<M>(error: &ContractError<M>) -> bool
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    match error {
+        ContractError::Revert(_) => true,
+        ContractError::MiddlewareError { e } => matches!(
+            (e as &(dyn std::error::Error + 'static)).downcast_ref(),
+            Some(RevmMiddlewareError::ExecutionRevert { .. })
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(U256::from(3).distance(&U256::from(10)), 7.0);
+        assert_eq!(I256::from(-3).distance(&I256::from(4)), 7.0);
+        assert_eq!(I256::MIN.distance(&I256::MAX), u256_to_f64(U256::MAX));
+        assert_eq!(I256::MIN.magnitude(), 2f64.powi(255));
+        assert_eq!((1_u64, -5_i64).distance(&(2, 5)), 10.0);
+    }
+}
@@ -14,12 +14,25 @@
 //!   outside world.
 //! - `RevmResult`: Wraps the result of a transaction along with the block
 //!   number.
-//! - `EventBroadcaster`: Responsible for broadcasting Ethereum logs to
-//!   subscribers.
+//! - `EventBroadcaster`: Responsible for broadcasting Ethereum logs and the
+//!   headers of finished blocks to subscribers.
+//! - `BlockHeader`: Summarizes a block once the environment has moved on from
+//!   it.
+//! - `LogStore`: Keeps the logs emitted in recent blocks indexed so that past
+//!   logs can be queried.
+//! - `Clock`: Optionally keeps the blocks of a group of environments in
+//!   lockstep.
+//! - `EnvironmentMetrics`: Counters and histograms describing what the
+//!   environment has processed.
+//! - `Observer`: A read-only call made at the end of every block whose results
+//!   form a time series.
+//! - `Checkpoints`: The retained state at the end of past blocks that calls and
+//!   state reads can be made against.
 
 #![warn(missing_docs, unsafe_code)]
 
 use std::{
+    collections::{HashMap, VecDeque},
     convert::Infallible,
     fmt::Debug,
     sync::{Arc, Condvar, Mutex},
@@ -27,16 +40,30 @@ use std::{
 };
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
-use ethers::core::types::U64;
+use ethers::{
+    abi::{self, Token},
+    core::types::{Address, Block, Filter, FilteredParams, ValueOrArray, H256, U64},
+    utils::keccak256,
+};
 use log::error;
 use revm::{
-    db::{CacheDB, EmptyDB},
-    primitives::{EVMError, ExecutionResult, Log, TxEnv, U256},
+    db::{AccountState, CacheDB, Database, EmptyDB},
+    primitives::{
+        AccountInfo, Bytecode, Bytes, EVMError, ExecutionResult, InvalidTransaction, Log, TxEnv,
+        B160, U256,
+    },
     EVM,
 };
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::math::SeededPoisson;
+use crate::{
+    clock::Clock,
+    math::{BlockSizeDistribution, MathError, SeededBlockSizes},
+    metrics::EnvironmentMetrics,
+    middleware::revm_logs_to_ethers_logs,
+    observer::{self, Observers},
+};
 #[cfg_attr(doc, doc(hidden))]
 #[cfg_attr(doc, allow(unused_imports))]
 #[cfg(doc)]
@@ -55,15 +82,23 @@ pub(crate) type ResultSender = Sender<RevmResult>;
 /// emitted from transactions.
 pub(crate) type ResultReceiver = Receiver<RevmResult>;
 
-/// Alias for the sender of the channel for transmitting transactions.
-pub(crate) type TxSender = Sender<(ToTransact, TxEnv, ResultSender)>;
+/// Alias for the sender of the channel for transmitting [`Instruction`]s.
+pub(crate) type InstructionSender = Sender<Instruction>;
+
+/// Alias for the receiver of the channel for transmitting [`Instruction`]s.
+pub(crate) type InstructionReceiver = Receiver<Instruction>;
 
-/// Alias for the receiver of the channel for transmitting transactions.
-pub(crate) type TxReceiver = Receiver<(ToTransact, TxEnv, ResultSender)>;
+/// Alias for the sender of the channel for transmitting the answer to an
+/// [`EnvironmentQuery`].
+pub(crate) type QuerySender = Sender<Result<QueryOutcome, EnvironmentError>>;
 
 /// Alias for the sender used in the [`EventBroadcaster`] that transmits
-/// contract events via [`Log`].
-pub(crate) type EventSender = Sender<Vec<Log>>;
+/// contract events via [`EmittedLogs`].
+pub(crate) type EventSender = Sender<EmittedLogs>;
+
+/// Alias for the sender used in the [`EventBroadcaster`] that transmits the
+/// [`BlockHeader`]s of finished blocks.
+pub(crate) type HeaderSender = Sender<BlockHeader>;
 
 /// Represents a [`Manager`]-controllable version of the Ethereum execution
 /// environment.
@@ -102,13 +137,20 @@ pub(crate) type EventSender = Sender<Vec<Log>>;
 ///
 /// ## Controlling Block Rate
 /// The blocks for the [`Environment`] are chosen using a Poisson distribution
-/// via the [`SeededPoisson`] field. The idea is that we can choose a rate
+/// via the [`SeededBlockSizes`] field. The idea is that we can choose a rate
 /// paramater, typically denoted by the Greek letter lambda, and set this to be
 /// the expected number of transactions per block while allowing blocks to be
 /// built with random size. This is useful in stepping forward the
 /// [`EVM`](https://github.com/bluealloy/revm/blob/main/crates/revm/src/evm.rs)
 /// and being able to move time forward for contracts that depend explicitly on
-/// time.
+/// time. The [`Manager`] may swap the Poisson distribution for any other
+/// [`BlockSizeDistribution`] before the [`Environment`] is ran.
+///
+/// ## Synchronizing Environments
+/// An [`Environment`] may be attached to a [`Clock`] by the [`Manager`]. In
+/// that case it will not move on to the next block until every other
+/// [`Environment`] attached to the same [`Clock`] has finished the current
+/// block, and its block timestamps are given by the [`Clock`].
 pub struct Environment {
     /// A label for the [`Environment`].
     /// Used to allow the [`Manager`] to locate the [`Environment`] in order to
@@ -116,10 +158,11 @@ pub struct Environment {
     /// post-process results.
     pub label: String,
 
-    /// A seeded Poisson distribution that is sampled from in order to determine
-    /// the average block size. [`SeededPoisson`] is created with a seed in
-    /// order to have repeatable simulations.
-    pub seeded_poisson: SeededPoisson,
+    /// A seeded distribution that is sampled from in order to determine the
+    /// size of each block, Poisson unless the [`Manager`] chose another one.
+    /// [`SeededBlockSizes`] is created with a seed in order to have repeatable
+    /// simulations.
+    pub block_sizes: SeededBlockSizes,
 
     // Private fields
     /// The [`State`] of the [`Environment`] which is shared across threads,
@@ -144,6 +187,34 @@ pub struct Environment {
     /// Used for assuring that the environment is stopped properly or for
     /// performing any blocking action the end user needs.
     pub(crate) handle: Option<JoinHandle<Result<(), EnvironmentError>>>,
+
+    /// The [`Clock`] that this [`Environment`] is synchronized with, if any.
+    /// Can only be set by the [`Manager`] prior to the [`Environment`] being
+    /// ran.
+    pub(crate) clock: Option<Clock>,
+
+    /// The [`EnvironmentMetrics`] that are updated as the [`Environment`]
+    /// processes calls and transactions.
+    pub(crate) metrics: Arc<Mutex<EnvironmentMetrics>>,
+
+    /// The [`LogStore`] that keeps the logs emitted by the transactions of the
+    /// recent blocks of the [`Environment`] so that clients can query past
+    /// logs.
+    pub(crate) log_store: Arc<Mutex<LogStore>>,
+
+    /// The [`Observer`](crate::observer::Observer)s whose calls are made at the
+    /// end of every block, keyed by their labels.
+    pub(crate) observers: Observers,
+
+    /// Whether transactions that come with a nonce are rejected if it is not
+    /// the current nonce of their caller. Can only be disabled by the
+    /// [`Manager`] prior to the [`Environment`] being ran.
+    pub(crate) nonce_checks: bool,
+
+    /// The amount of past blocks whose state is kept in [`Checkpoints`] so
+    /// that calls and state reads can be made as of those blocks. Zero unless
+    /// the [`Manager`] chose otherwise prior to the [`Environment`] being ran.
+    pub(crate) state_retention: usize,
 }
 
 /// Parameters necessary for creating or modifying an `Environment`.
@@ -153,7 +224,7 @@ pub struct Environment {
 pub struct EnvironmentParameters {
     /// The mean of the rate at which the environment will
     /// process blocks (e.g., the rate parameter in the Poisson distribution
-    /// used in the [`SeededPoisson`] field of an [`Environment`]).
+    /// used in the [`SeededBlockSizes`] field of an [`Environment`]).
     pub block_rate: f64,
 
     /// A value chosen to generate randomly chosen block sizes
@@ -168,11 +239,17 @@ impl Debug for Environment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Environment")
             .field("label", &self.label)
-            .field("seeded_poisson", &self.seeded_poisson)
+            .field("block_sizes", &self.block_sizes)
             .field("state", &self.state)
             .field("socket", &self.socket)
             .field("pausevar", &self.pausevar)
             .field("handle", &self.handle)
+            .field("clock", &self.clock)
+            .field("metrics", &self.metrics)
+            .field("log_store", &self.log_store)
+            .field("observers", &self.observers)
+            .field("nonce_checks", &self.nonce_checks)
+            .field("state_retention", &self.state_retention)
             .finish()
     }
 }
@@ -218,13 +295,28 @@ pub enum EnvironmentError {
     /// this will be (hopefully) unnecessary!
     #[error("conversion error! the source error is: {0}")]
     Conversion(String),
+
+    /// [`EnvironmentError::Synchronization`] is thrown when the
+    /// [`Environment`] fails to move on to the next block together with the
+    /// other environments attached to its [`Clock`]. This happens when the
+    /// [`Environment`] is stopped while waiting on the [`Clock`].
+    #[error("synchronization error! the source error is: {0}")]
+    Synchronization(String),
+
+    /// [`EnvironmentError::Query`] is thrown when the [`Environment`] cannot
+    /// answer an [`EnvironmentQuery`] about the state of its [`EVM`].
+    #[error("query error! the source error is: {0}")]
+    Query(String),
 }
 
 impl Environment {
     /// Privately accessible constructor function for creating an
     /// [`Environment`]. This function should be accessed by the
-    /// [`Manager`].
-    pub(crate) fn new<S: Into<String>>(label: S, params: EnvironmentParameters) -> Self {
+    /// [`Manager`]. Fails if the block rate of the `params` is not positive.
+    pub(crate) fn new<S: Into<String>>(
+        label: S,
+        params: EnvironmentParameters,
+    ) -> Result<Self, MathError> {
         // Initialize the EVM used
         let mut evm = EVM::new();
         let db = CacheDB::new(EmptyDB {});
@@ -234,24 +326,33 @@ impl Environment {
         evm.env.cfg.limit_contract_code_size = Some(0x100000);
         evm.env.block.gas_limit = U256::MAX;
 
-        let seeded_poisson = SeededPoisson::new(params.block_rate, params.seed);
+        let block_sizes = BlockSizeDistribution::Poisson {
+            rate: params.block_rate,
+        }
+        .seeded(params.seed)?;
 
-        let (tx_sender, tx_receiver) = unbounded();
+        let (instruction_sender, instruction_receiver) = unbounded();
         let socket = Socket {
-            tx_sender,
-            tx_receiver,
+            instruction_sender,
+            instruction_receiver,
             event_broadcaster: Arc::new(Mutex::new(EventBroadcaster::new())),
         };
 
-        Self {
+        Ok(Self {
             label: label.into(),
             state: Arc::new(AtomicState::new(State::Initialization)),
             evm,
             socket,
-            seeded_poisson,
+            block_sizes,
             handle: None,
             pausevar: Arc::new((Mutex::new(()), Condvar::new())),
-        }
+            clock: None,
+            metrics: Arc::new(Mutex::new(EnvironmentMetrics::default())),
+            log_store: Arc::new(Mutex::new(LogStore::new(DEFAULT_LOG_RETENTION))),
+            observers: Observers::default(),
+            nonce_checks: true,
+            state_retention: 0,
+        })
     }
 
     /// Privately accessible function to take an [`Environment`] that is in
@@ -265,9 +366,15 @@ impl Environment {
         // Pull clones of the relevant data prepare to send into a new thread
         let label = self.label.clone();
         let mut evm = self.evm.clone();
-        let tx_receiver = self.socket.tx_receiver.clone();
+        let instruction_receiver = self.socket.instruction_receiver.clone();
         let event_broadcaster = self.socket.event_broadcaster.clone();
-        let mut seeded_poisson = self.seeded_poisson.clone();
+        let mut block_sizes = self.block_sizes.clone();
+        let clock = self.clock.clone();
+        let metrics = Arc::clone(&self.metrics);
+        let log_store = Arc::clone(&self.log_store);
+        let observers = Arc::clone(&self.observers);
+        let nonce_checks = self.nonce_checks;
+        let mut checkpoints = Checkpoints::new(self.state_retention);
 
         // Set up the state and tx counter
         self.state
@@ -275,11 +382,61 @@ impl Environment {
         let state = Arc::clone(&self.state);
         let pausevar = Arc::clone(&self.pausevar);
         let mut counter: usize = 0;
+        let mut block_gas_used: u64 = 0;
 
         // Move the EVM and its socket to a new thread and retrieve this handle
         let handle = thread::spawn(move || {
+            // Start from the block given by the `Clock` if there is one
+            if let Some(clock) = &clock {
+                let time = clock
+                    .now()
+                    .map_err(|e| EnvironmentError::Synchronization(e.to_string()))?;
+                evm.env.block.number = U256::from(time.block_number);
+                evm.env.block.timestamp = U256::from(time.timestamp);
+            }
+
             // Get the first amount of transactions per block from the distribution
-            let mut transactions_per_block = seeded_poisson.sample();
+            let mut transactions_per_block = block_sizes.sample();
+
+            // Samples and keeps the committed state of the finished block, lets
+            // subscribers know it is finished and moves on to the next block. Returns
+            // `false` if the `Environment` was stopped while waiting on its `Clock`.
+            let finish_block = |evm: &mut EVM<CacheDB<EmptyDB>>,
+                                checkpoints: &mut Checkpoints,
+                                transaction_count: usize,
+                                gas_used: u64|
+             -> Result<bool, EnvironmentError> {
+                observer::observe(evm, &observers, &label)?;
+                checkpoints.record(evm);
+                let header = BlockHeader::new(evm, transaction_count, gas_used)?;
+                event_broadcaster
+                    .lock()
+                    .map_err(|e| EnvironmentError::Communication(format!("{:?}", e)))?
+                    .broadcast_header(header);
+                match &clock {
+                    // Wait for the rest of the synchronized environments to finish this
+                    // block before moving on to the next
+                    Some(clock) => match clock
+                        .tick(
+                            &label,
+                            convert_uint_to_u64(evm.env.block.number)
+                                .map_err(|e| EnvironmentError::Conversion(format!("{:?}", e)))?
+                                .as_u64(),
+                            &state,
+                        )
+                        .map_err(|e| EnvironmentError::Synchronization(e.to_string()))?
+                    {
+                        Some(time) => {
+                            evm.env.block.number = U256::from(time.block_number);
+                            evm.env.block.timestamp = U256::from(time.timestamp);
+                        }
+                        None => return Ok(false),
+                    },
+                    None => evm.env.block.number += U256::from(1),
+                }
+                lock_metrics(&metrics)?.record_block();
+                Ok(true)
+            };
 
             // Loop over the reception of calls/transactions sent through the socket
             loop {
@@ -298,7 +455,23 @@ impl Environment {
                         // this logic here ensures we catch any edge case last transactions and send
                         // the appropriate error so that we dont hang in
                         // limbo forever
-                        while let Ok((_, _, sender)) = tx_receiver.try_recv() {
+                        while let Ok(instruction) = instruction_receiver.try_recv() {
+                            let sender = match instruction {
+                                Instruction::Transaction(_, _, sender)
+                                | Instruction::HistoricalCall(_, _, sender) => sender,
+                                Instruction::Query(_, _, sender)
+                                | Instruction::Cheat(_, sender)
+                                | Instruction::Mine(sender) => {
+                                    sender
+                                        .send(Err(EnvironmentError::Pause(
+                                            "Environment is paused".into(),
+                                        )))
+                                        .map_err(|e| {
+                                            EnvironmentError::Communication(format!("{:?}", e))
+                                        })?;
+                                    continue;
+                                }
+                            };
                             let error_outcome = TransactionOutcome::Error(EnvironmentError::Pause(
                                 "Environment is paused".into(),
                             ));
@@ -322,19 +495,118 @@ impl Environment {
 
                     // Receive new transactions
                     State::Running => {
-                        if let Ok((to_transact, tx, sender)) = tx_receiver.try_recv() {
+                        if let Ok(instruction) = instruction_receiver.try_recv() {
+                            lock_metrics(&metrics)?.record_queue_depth(instruction_receiver.len());
+
+                            // Queries and cheats are not transactions, so handle them right away
+                            // without counting them towards the block
+                            let (to_transact, tx, sender) = match instruction {
+                                Instruction::Transaction(to_transact, tx, sender) => {
+                                    (to_transact, tx, sender)
+                                }
+                                Instruction::Query(query, block_number, sender) => {
+                                    let answer = match block_number {
+                                        Some(block_number) => checkpoints
+                                            .at(&mut evm, block_number)
+                                            .and_then(|evm| answer(evm, query)),
+                                        None => answer(&mut evm, query),
+                                    };
+                                    sender.send(answer).map_err(|e| {
+                                        EnvironmentError::Communication(format!("{:?}", e))
+                                    })?;
+                                    continue;
+                                }
+                                Instruction::HistoricalCall(block_number, tx, sender) => {
+                                    let revm_result = match checkpoints.at(&mut evm, block_number) {
+                                        Ok(evm) => call(evm, *tx)?,
+                                        Err(e) => RevmResult {
+                                            outcome: TransactionOutcome::Error(e),
+                                            block_number: U64::from(block_number),
+                                        },
+                                    };
+                                    if matches!(revm_result.outcome, TransactionOutcome::Success(_))
+                                    {
+                                        lock_metrics(&metrics)?.record_call();
+                                    }
+                                    sender.send(revm_result).map_err(|e| {
+                                        EnvironmentError::Communication(format!("{:?}", e))
+                                    })?;
+                                    continue;
+                                }
+                                Instruction::Cheat(change, sender) => {
+                                    sender.send(cheat(&mut evm, change)).map_err(|e| {
+                                        EnvironmentError::Communication(format!("{:?}", e))
+                                    })?;
+                                    continue;
+                                }
+                                Instruction::Mine(sender) => {
+                                    let answer = if finish_block(
+                                        &mut evm,
+                                        &mut checkpoints,
+                                        counter,
+                                        block_gas_used,
+                                    )? {
+                                        Ok(QueryOutcome::Uint(evm.env.block.number))
+                                    } else {
+                                        Err(EnvironmentError::Synchronization(
+                                            "Environment was stopped while waiting on its clock"
+                                                .into(),
+                                        ))
+                                    };
+                                    let stopped = answer.is_err();
+                                    sender.send(answer).map_err(|e| {
+                                        EnvironmentError::Communication(format!("{:?}", e))
+                                    })?;
+                                    if stopped {
+                                        break;
+                                    }
+                                    counter = 0;
+                                    block_gas_used = 0;
+                                    transactions_per_block = block_sizes.sample();
+                                    continue;
+                                }
+                            };
+
                             // Check whether we need to increment the block number given the amount
                             // of transactions that have occured on the current block and increment
-                            // if need be and draw a new sample from the `SeededPoisson`
-                            // distribution
-                            if counter == transactions_per_block {
+                            // if need be and draw a new sample from the `SeededBlockSizes`
+                            // distribution. The transaction that finishes a block is counted
+                            // towards the next one, so a freshly drawn sample of zero is already
+                            // exceeded; compare with `>=` so that such a block still ends.
+                            if counter >= transactions_per_block {
+                                if !finish_block(
+                                    &mut evm,
+                                    &mut checkpoints,
+                                    counter,
+                                    block_gas_used,
+                                )? {
+                                    let revm_result = RevmResult {
+                                        outcome: TransactionOutcome::Error(
+                                            EnvironmentError::Synchronization(
+                                                "Environment was stopped while waiting on its clock"
+                                                    .into(),
+                                            ),
+                                        ),
+                                        block_number: convert_uint_to_u64(evm.env.block.number)
+                                            .map_err(|e| {
+                                                EnvironmentError::Conversion(format!("{:?}", e))
+                                            })?,
+                                    };
+                                    sender.send(revm_result).map_err(|e| {
+                                        EnvironmentError::Communication(format!("{:?}", e))
+                                    })?;
+                                    break;
+                                }
                                 counter = 0;
-                                evm.env.block.number += U256::from(1);
-                                transactions_per_block = seeded_poisson.sample();
+                                block_gas_used = 0;
+                                transactions_per_block = block_sizes.sample();
                             }
 
                             // Set the tx_env and prepare to process it
-                            evm.env.tx = tx;
+                            evm.env.tx = *tx;
+                            if !nonce_checks {
+                                evm.env.tx.nonce = None;
+                            }
 
                             // If the transaction is a state-changing transaction, `to_transact ==
                             // true` and the state will be written to the database via a
@@ -348,6 +620,12 @@ impl Environment {
                                     // [`ExecutionResult::Success`], [`ExecutionResult::Revert`], or
                                     // [`ExecutionResult::Halt`].
                                     Ok(val) => val,
+                                    // Invalid transactions, e.g., ones whose caller cannot afford
+                                    // their value, are rejected without pausing
+                                    Err(EVMError::Transaction(invalid)) => {
+                                        reject(&sender, invalid, evm.env.block.number)?;
+                                        continue;
+                                    }
                                     Err(e) => {
                                         state.store(
                                             State::Paused,
@@ -357,16 +635,35 @@ impl Environment {
                                         return Err(EnvironmentError::Execution(e));
                                     }
                                 };
-                                let event_broadcaster = event_broadcaster.lock().map_err(|e| {
-                                    EnvironmentError::Communication(format!("{:?}", e))
-                                })?;
-                                event_broadcaster.broadcast(execution_result.logs())?;
+                                lock_metrics(&metrics)?
+                                    .record_transaction(evm.env.tx.caller, &execution_result);
+                                block_gas_used += execution_result.gas_used();
+                                let block_number = convert_uint_to_u64(evm.env.block.number)
+                                    .map_err(|e| {
+                                        EnvironmentError::Conversion(format!("{:?}", e))
+                                    })?;
+                                let mut event_broadcaster =
+                                    event_broadcaster.lock().map_err(|e| {
+                                        EnvironmentError::Communication(format!("{:?}", e))
+                                    })?;
+                                let block_timestamp = convert_uint_to_u64(evm.env.block.timestamp)
+                                    .map_err(|e| {
+                                        EnvironmentError::Conversion(format!("{:?}", e))
+                                    })?;
+                                log_store
+                                    .lock()
+                                    .map_err(|e| {
+                                        EnvironmentError::Communication(format!("{:?}", e))
+                                    })?
+                                    .record(execution_result.logs(), block_number, counter);
+                                event_broadcaster.broadcast(EmittedLogs {
+                                    logs: execution_result.logs(),
+                                    block_number,
+                                    block_timestamp,
+                                });
                                 let revm_result = RevmResult {
                                     outcome: TransactionOutcome::Success(execution_result),
-                                    block_number: convert_uint_to_u64(evm.env.block.number)
-                                        .map_err(|e| {
-                                            EnvironmentError::Conversion(format!("{:?}", e))
-                                        })?,
+                                    block_number,
                                 };
                                 sender.send(revm_result).map_err(|e| {
                                     EnvironmentError::Communication(format!("{:?}", e))
@@ -378,7 +675,14 @@ impl Environment {
                                     // but pass to the middleware to determine if the result is
                                     // [`ExecutionResult::Success`], [`ExecutionResult::Revert`], or
                                     // [`ExecutionResult::Halt`].
-                                    Ok(result_and_state) => result_and_state.result,
+                                    Ok(result_and_state) => {
+                                        lock_metrics(&metrics)?.record_call();
+                                        result_and_state.result
+                                    }
+                                    Err(EVMError::Transaction(invalid)) => {
+                                        reject(&sender, invalid, evm.env.block.number)?;
+                                        continue;
+                                    }
                                     Err(e) => {
                                         state.store(
                                             State::Paused,
@@ -450,15 +754,97 @@ pub enum State {
 
 /// Provides channels for communication between the EVM and external entities.
 ///
-/// The socket contains senders and receivers for transactions, as well as an
+/// The socket contains senders and receivers for instructions, as well as an
 /// event broadcaster to broadcast logs from the EVM to subscribers.
 #[derive(Debug, Clone)]
 pub(crate) struct Socket {
-    pub(crate) tx_sender: TxSender,
-    pub(crate) tx_receiver: TxReceiver,
+    pub(crate) instruction_sender: InstructionSender,
+    pub(crate) instruction_receiver: InstructionReceiver,
     pub(crate) event_broadcaster: Arc<Mutex<EventBroadcaster>>,
 }
 
+/// What can be sent to an [`Environment`] through its `Socket`.
+#[derive(Debug, Clone)]
+pub(crate) enum Instruction {
+    /// A call (`false`) or transaction (`true`) along with the sender its
+    /// [`RevmResult`] is sent back through.
+    Transaction(ToTransact, Box<TxEnv>, ResultSender),
+
+    /// A read of the state of the [`EVM`], as of the end of the given block if
+    /// any, along with the sender its [`QueryOutcome`] is sent back through.
+    Query(EnvironmentQuery, Option<u64>, QuerySender),
+
+    /// Changes the state of the [`EVM`] directly rather than through a
+    /// transaction, along with the sender the new value is sent back through.
+    Cheat(Cheat, QuerySender),
+
+    /// Finishes the current block right away, whether or not it is full,
+    /// along with the sender the number of the next block is sent back
+    /// through.
+    Mine(QuerySender),
+
+    /// A call made against the state as of the end of the given block along
+    /// with the sender its [`RevmResult`] is sent back through.
+    HistoricalCall(u64, Box<TxEnv>, ResultSender),
+}
+
+/// A read of the state of the [`EVM`] of an [`Environment`] that is answered
+/// without executing anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EnvironmentQuery {
+    /// The balance of an account.
+    Balance(B160),
+
+    /// The runtime bytecode of an account.
+    Code(B160),
+
+    /// The value of a storage slot of an account.
+    Storage(B160, U256),
+
+    /// The nonce of an account.
+    TransactionCount(B160),
+
+    /// The number of the current block.
+    BlockNumber,
+
+    /// The chain ID of the [`EVM`].
+    ChainId,
+
+    /// The timestamp of the current block.
+    Timestamp,
+
+    /// The base fee per gas of the current block.
+    BaseFee,
+}
+
+/// A change of the state of the [`EVM`] of an [`Environment`] that is made
+/// directly rather than through a transaction, e.g., to set up a scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Cheat {
+    /// Sets the balance of an account to the given amount of wei, acting as a
+    /// faucet.
+    Deal(B160, U256),
+
+    /// Sets the runtime bytecode of an account.
+    Etch(B160, Bytes),
+
+    /// Sets the value of a storage slot of an account.
+    Store(B160, U256, U256),
+
+    /// Sets the nonce of an account.
+    SetNonce(B160, u64),
+}
+
+/// The answer to an [`EnvironmentQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QueryOutcome {
+    /// A number, e.g., a balance or the value of a storage slot.
+    Uint(U256),
+
+    /// Raw bytes, e.g., the runtime bytecode of an account.
+    Bytes(Bytes),
+}
+
 /// Represents the possible outcomes of an EVM transaction.
 ///
 /// This enum is used to encapsulate both successful transaction results and
@@ -494,45 +880,539 @@ pub(crate) struct RevmResult {
     pub(crate) block_number: U64,
 }
 
-/// Responsible for broadcasting Ethereum logs to subscribers.
+/// The [`Log`]s emitted by a single transaction along with the block the
+/// transaction was included in.
+#[derive(Clone, Debug)]
+pub(crate) struct EmittedLogs {
+    /// The logs emitted by the transaction.
+    pub(crate) logs: Vec<Log>,
+
+    /// The number of the block the transaction was included in.
+    pub(crate) block_number: U64,
+
+    /// The timestamp of the block the transaction was included in.
+    pub(crate) block_timestamp: U64,
+}
+
+/// Summarizes a block of an [`Environment`], which is broadcast once the
+/// [`Environment`] has moved on to the next block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    /// The number of the block.
+    pub number: U64,
+
+    /// The timestamp of the block.
+    pub timestamp: U64,
+
+    /// The base fee per gas of the block.
+    pub base_fee: ethers::types::U256,
+
+    /// The amount of transactions included in the block.
+    pub transaction_count: usize,
+
+    /// The total gas used by the transactions included in the block.
+    pub gas_used: u64,
+}
+
+impl BlockHeader {
+    /// The header of the block the [`EVM`] is at, which has seen
+    /// `transaction_count` transactions use `gas_used` gas.
+    fn new(
+        evm: &EVM<CacheDB<EmptyDB>>,
+        transaction_count: usize,
+        gas_used: u64,
+    ) -> Result<Self, EnvironmentError> {
+        let convert = |input| {
+            convert_uint_to_u64(input).map_err(|e| EnvironmentError::Conversion(format!("{:?}", e)))
+        };
+        Ok(Self {
+            number: convert(evm.env.block.number)?,
+            timestamp: convert(evm.env.block.timestamp)?,
+            base_fee: ethers::types::U256::from_big_endian(
+                &evm.env.block.basefee.to_be_bytes::<32>(),
+            ),
+            transaction_count,
+            gas_used,
+        })
+    }
+
+    /// Stands in for the hash of the block, which is the hash of the
+    /// ABI-encoded fields of the header since the [`Environment`] does not
+    /// build real blocks.
+    pub fn hash(&self) -> H256 {
+        H256::from(keccak256(abi::encode(&[
+            Token::Uint(self.number.as_u64().into()),
+            Token::Uint(self.timestamp.as_u64().into()),
+            Token::Uint(self.base_fee),
+            Token::Uint(self.transaction_count.into()),
+            Token::Uint(self.gas_used.into()),
+        ])))
+    }
+}
+
+impl From<&BlockHeader> for Block<H256> {
+    /// Fills in the fields of a [`Block`] the header knows about. The parent
+    /// hash is left empty since a header does not know its parent.
+    fn from(header: &BlockHeader) -> Self {
+        Block {
+            hash: Some(header.hash()),
+            number: Some(header.number),
+            timestamp: header.timestamp.as_u64().into(),
+            base_fee_per_gas: Some(header.base_fee),
+            gas_used: header.gas_used.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The amount of blocks, including the current one, whose logs an
+/// [`Environment`] keeps unless the [`Manager`] chose otherwise.
+pub const DEFAULT_LOG_RETENTION: usize = 10_000;
+
+/// Keeps the logs emitted by the transactions of an [`Environment`] in the
+/// order they were emitted, indexed by address and by topic, so that past logs
+/// can be queried by block range like `eth_getLogs`. Only the logs of the
+/// last `retention` blocks are kept, the older ones are forgotten as the
+/// [`Environment`] moves on.
+#[derive(Clone, Debug)]
+pub(crate) struct LogStore {
+    /// The amount of blocks, including the current one, whose logs are kept.
+    retention: usize,
+
+    /// The logs in the order they were emitted, hence sorted by block.
+    logs: VecDeque<ethers::types::Log>,
+
+    /// The amount of logs forgotten, which positions are offset by.
+    forgotten: usize,
+
+    /// The positions of the logs emitted by each address, counting the
+    /// forgotten logs.
+    by_address: HashMap<Address, VecDeque<usize>>,
+
+    /// The positions of the logs with each topic at each of the four topic
+    /// positions, counting the forgotten logs.
+    by_topic: HashMap<(usize, H256), VecDeque<usize>>,
+}
+
+impl LogStore {
+    /// Constructs an empty [`LogStore`] that keeps the logs of the last
+    /// `retention` blocks.
+    pub(crate) fn new(retention: usize) -> Self {
+        Self {
+            retention,
+            logs: VecDeque::new(),
+            forgotten: 0,
+            by_address: HashMap::new(),
+            by_topic: HashMap::new(),
+        }
+    }
+
+    /// Sets the amount of blocks whose logs are kept, which takes effect with
+    /// the next logs recorded.
+    pub(crate) fn set_retention(&mut self, retention: usize) {
+        self.retention = retention;
+    }
+
+    /// Keeps the logs emitted by the transaction at `transaction_index` of
+    /// the block `block_number`, forgetting the logs of the blocks that are
+    /// no longer retained.
+    fn record(&mut self, logs: Vec<Log>, block_number: U64, transaction_index: usize) {
+        self.forget_before((block_number + 1).saturating_sub(U64::from(self.retention)));
+        if self.retention == 0 {
+            return;
+        }
+        // Logs are indexed within their block
+        let first_log_index = self.logs.len()
+            - self
+                .logs
+                .partition_point(|log| log.block_number < Some(block_number));
+        for (log_index, mut log) in (first_log_index..).zip(revm_logs_to_ethers_logs(logs)) {
+            let position = self.forgotten + self.logs.len();
+            log.block_number = Some(block_number);
+            log.transaction_index = Some(transaction_index.into());
+            log.log_index = Some(log_index.into());
+            self.by_address
+                .entry(log.address)
+                .or_default()
+                .push_back(position);
+            for (topic_position, topic) in log.topics.iter().enumerate() {
+                self.by_topic
+                    .entry((topic_position, *topic))
+                    .or_default()
+                    .push_back(position);
+            }
+            self.logs.push_back(log);
+        }
+    }
+
+    /// Forgets the logs of the blocks before `block_number`.
+    fn forget_before(&mut self, block_number: U64) {
+        let expired = self
+            .logs
+            .partition_point(|log| log.block_number < Some(block_number));
+        if expired == 0 {
+            return;
+        }
+        self.logs.drain(..expired);
+        self.forgotten += expired;
+        let forgotten = self.forgotten;
+        let forget = |positions: &mut VecDeque<usize>| {
+            let expired = positions.partition_point(|position| *position < forgotten);
+            positions.drain(..expired);
+            !positions.is_empty()
+        };
+        self.by_address.retain(|_, positions| forget(positions));
+        self.by_topic.retain(|_, positions| forget(positions));
+    }
+
+    /// The logs from the blocks `from` to `to`, both included, that match the
+    /// address and topics of the `filter`. Only the logs listed under the
+    /// most selective address or topic index of the `filter` are checked. A
+    /// range that ends before it starts holds no logs.
+    pub(crate) fn query(&self, filter: &Filter, from: U64, to: U64) -> Vec<ethers::types::Log> {
+        if from > to {
+            return vec![];
+        }
+        let start = self.forgotten
+            + self
+                .logs
+                .partition_point(|log| log.block_number < Some(from));
+        let end = self.forgotten
+            + self
+                .logs
+                .partition_point(|log| log.block_number <= Some(to));
+
+        // Every value of an address or topic of the filter is allowed, so each
+        // of them gives the union of what is indexed under its values
+        let mut keys: Vec<Vec<&VecDeque<usize>>> = vec![];
+        if let Some(addresses) = &filter.address {
+            let addresses = match addresses {
+                ValueOrArray::Value(address) => vec![*address],
+                ValueOrArray::Array(addresses) => addresses.clone(),
+            };
+            keys.push(
+                addresses
+                    .iter()
+                    .filter_map(|address| self.by_address.get(address))
+                    .collect(),
+            );
+        }
+        for (topic_position, topic) in filter.topics.iter().enumerate() {
+            let topics = match topic {
+                Some(ValueOrArray::Value(Some(topic))) => vec![Some(*topic)],
+                Some(ValueOrArray::Array(topics)) => topics.clone(),
+                _ => continue,
+            };
+            // Any topic matches a wildcard, so there is nothing to narrow down
+            if topics.contains(&None) {
+                continue;
+            }
+            keys.push(
+                topics
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|topic| self.by_topic.get(&(topic_position, topic)))
+                    .collect(),
+            );
+        }
+        let candidates = keys
+            .into_iter()
+            .map(|lists| {
+                let mut positions: Vec<usize> = lists
+                    .into_iter()
+                    .flat_map(|list| {
+                        let list_start = list.partition_point(|position| *position < start);
+                        let list_end = list.partition_point(|position| *position < end);
+                        list.range(list_start..list_end).copied()
+                    })
+                    .collect();
+                positions.sort_unstable();
+                positions.dedup();
+                positions
+            })
+            .min_by_key(|positions| positions.len())
+            .unwrap_or_else(|| (start..end).collect());
+
+        let filtered_params = FilteredParams::new(Some(filter.clone()));
+        candidates
+            .into_iter()
+            .map(|position| &self.logs[position - self.forgotten])
+            .filter(|log| filtered_params.filter_address(log) && filtered_params.filter_topics(log))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Responsible for broadcasting Ethereum logs and [`BlockHeader`]s to
+/// subscribers.
 ///
-/// Maintains a list of senders to which logs are sent whenever they are
-/// produced by the EVM.
+/// Maintains lists of senders to which logs are sent whenever they are
+/// produced by the EVM and to which headers are sent whenever a block is
+/// finished.
 #[derive(Clone, Debug)]
-pub(crate) struct EventBroadcaster(Vec<EventSender>);
+pub(crate) struct EventBroadcaster {
+    senders: Vec<EventSender>,
+    header_senders: Vec<HeaderSender>,
+    last_header: Option<BlockHeader>,
+}
 
 impl EventBroadcaster {
     /// Called only when creating a new [`Environment`]
     fn new() -> Self {
-        Self(vec![])
+        Self {
+            senders: vec![],
+            header_senders: vec![],
+            last_header: None,
+        }
     }
 
     /// Called from [`RevmMiddleware`] implementation when setting up a new
     /// `FilterWatcher` as each watcher will need their own sender
     pub(crate) fn add_sender(&mut self, sender: EventSender) {
-        self.0.push(sender);
+        self.senders.push(sender);
     }
 
-    /// Loop through each sender and send  `Vec<Log>` emitted from a transaction
-    /// downstream to any and all receivers
-    fn broadcast(&self, logs: Vec<Log>) -> Result<(), EnvironmentError> {
-        for sender in &self.0 {
-            sender
-                .send(logs.clone())
-                .map_err(|e| EnvironmentError::Communication(format!("{:?}", e)))?;
+    /// Called from [`RevmMiddleware`] implementation when setting up a new
+    /// block filter, which needs its own sender
+    pub(crate) fn add_header_sender(&mut self, sender: HeaderSender) {
+        self.header_senders.push(sender);
+    }
+
+    /// The [`BlockHeader`] of the most recently finished block, if any.
+    pub(crate) fn last_header(&self) -> Option<&BlockHeader> {
+        self.last_header.as_ref()
+    }
+
+    /// Loop through each sender and send the [`EmittedLogs`] of a transaction
+    /// downstream to any and all receivers. Senders whose receiver has been
+    /// dropped, e.g., by a finished agent or a stopped bridge, are removed
+    /// rather than failing the broadcasting [`Environment`]
+    fn broadcast(&mut self, emitted_logs: EmittedLogs) {
+        self.senders
+            .retain(|sender| sender.send(emitted_logs.clone()).is_ok());
+    }
+
+    /// Sends the [`BlockHeader`] of a finished block to every header receiver,
+    /// removing the senders whose receiver has been dropped
+    fn broadcast_header(&mut self, header: BlockHeader) {
+        self.header_senders
+            .retain(|sender| sender.send(header.clone()).is_ok());
+        self.last_header = Some(header);
+    }
+}
+
+/// Keeps the state of the [`EVM`] at the end of the most recent past blocks,
+/// forgetting the oldest one once more than `retention` blocks are kept.
+struct Checkpoints {
+    retention: usize,
+    blocks: VecDeque<EVM<CacheDB<EmptyDB>>>,
+}
+
+impl Checkpoints {
+    fn new(retention: usize) -> Self {
+        Self {
+            retention,
+            blocks: VecDeque::with_capacity(retention),
+        }
+    }
+
+    /// Keeps the state of the block the [`EVM`] is at, which is called once
+    /// that block has ended.
+    fn record(&mut self, evm: &EVM<CacheDB<EmptyDB>>) {
+        if self.retention == 0 {
+            return;
+        }
+        if self.blocks.len() == self.retention {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(evm.clone());
+    }
+
+    /// The [`EVM`] holding the state as of the end of `block_number`, which is
+    /// the live one if that is the current block. Blocks that have not begun
+    /// yet have no state.
+    fn at<'a>(
+        &'a mut self,
+        evm: &'a mut EVM<CacheDB<EmptyDB>>,
+        block_number: u64,
+    ) -> Result<&'a mut EVM<CacheDB<EmptyDB>>, EnvironmentError> {
+        let block = U256::from(block_number);
+        if block > evm.env.block.number {
+            return Err(EnvironmentError::Query(format!(
+                "block {} has not begun yet",
+                block_number
+            )));
         }
-        Ok(())
+        if block == evm.env.block.number {
+            return Ok(evm);
+        }
+        // Blocks that were skipped, e.g., by a `Clock`, have the state of the
+        // last block before them
+        self.blocks
+            .iter_mut()
+            .rev()
+            .find(|checkpoint| checkpoint.env.block.number <= block)
+            .ok_or_else(|| {
+                EnvironmentError::Query(format!(
+                    "the state of block {} is not retained",
+                    block_number
+                ))
+            })
     }
 }
 
+/// Answers an [`EnvironmentQuery`] from the current state of the [`EVM`].
+/// Accounts that were never touched are read as empty.
+fn answer(
+    evm: &mut EVM<CacheDB<EmptyDB>>,
+    query: EnvironmentQuery,
+) -> Result<QueryOutcome, EnvironmentError> {
+    let block_number = evm.env.block.number;
+    let chain_id = evm.env.cfg.chain_id;
+    let timestamp = evm.env.block.timestamp;
+    let base_fee = evm.env.block.basefee;
+    let db = evm
+        .db()
+        .ok_or_else(|| EnvironmentError::Query("the EVM has no database".into()))?;
+    let outcome = match query {
+        EnvironmentQuery::Balance(address) => {
+            let info = db.basic(address).unwrap_or_else(|e| match e {});
+            QueryOutcome::Uint(info.map(|info| info.balance).unwrap_or_default())
+        }
+        EnvironmentQuery::Code(address) => {
+            let code = match db.basic(address).unwrap_or_else(|e| match e {}) {
+                Some(info) => match info.code {
+                    Some(code) => code,
+                    None => db
+                        .code_by_hash(info.code_hash)
+                        .unwrap_or_else(|e| match e {}),
+                },
+                None => return Ok(QueryOutcome::Bytes(Bytes::new())),
+            };
+            QueryOutcome::Bytes(code.original_bytes())
+        }
+        EnvironmentQuery::Storage(address, slot) => {
+            QueryOutcome::Uint(db.storage(address, slot).unwrap_or_else(|e| match e {}))
+        }
+        EnvironmentQuery::TransactionCount(address) => {
+            let info = db.basic(address).unwrap_or_else(|e| match e {});
+            QueryOutcome::Uint(U256::from(info.map(|info| info.nonce).unwrap_or_default()))
+        }
+        EnvironmentQuery::BlockNumber => QueryOutcome::Uint(block_number),
+        EnvironmentQuery::ChainId => QueryOutcome::Uint(chain_id),
+        EnvironmentQuery::Timestamp => QueryOutcome::Uint(timestamp),
+        EnvironmentQuery::BaseFee => QueryOutcome::Uint(base_fee),
+    };
+    Ok(outcome)
+}
+
+/// Applies a [`Cheat`] to the state of the [`EVM`], creating the account it
+/// changes if need be, and answers with the new value.
+fn cheat(evm: &mut EVM<CacheDB<EmptyDB>>, change: Cheat) -> Result<QueryOutcome, EnvironmentError> {
+    let db = evm
+        .db()
+        .ok_or_else(|| EnvironmentError::Query("the EVM has no database".into()))?;
+    let address = match change {
+        Cheat::Deal(address, _)
+        | Cheat::Etch(address, _)
+        | Cheat::Store(address, ..)
+        | Cheat::SetNonce(address, _) => address,
+    };
+    let outcome = match change {
+        Cheat::Deal(address, amount) => {
+            db.load_account(address)
+                .unwrap_or_else(|e| match e {})
+                .info
+                .balance = amount;
+            QueryOutcome::Uint(amount)
+        }
+        Cheat::Etch(address, code) => {
+            let info = db.basic(address).unwrap_or_else(|e| match e {});
+            let bytecode = Bytecode::new_raw(code.clone());
+            let info = AccountInfo {
+                code_hash: bytecode.hash_slow(),
+                code: Some(bytecode),
+                ..info.unwrap_or_default()
+            };
+            db.insert_account_info(address, info);
+            QueryOutcome::Bytes(code)
+        }
+        Cheat::Store(address, slot, value) => {
+            db.insert_account_storage(address, slot, value)
+                .unwrap_or_else(|e| match e {});
+            QueryOutcome::Uint(value)
+        }
+        Cheat::SetNonce(address, nonce) => {
+            db.load_account(address)
+                .unwrap_or_else(|e| match e {})
+                .info
+                .nonce = nonce;
+            QueryOutcome::Uint(U256::from(nonce))
+        }
+    };
+    // An account that was read before it existed is marked as not existing,
+    // which would hide the change
+    db.load_account(address)
+        .unwrap_or_else(|e| match e {})
+        .account_state = AccountState::Touched;
+    Ok(outcome)
+}
+
+/// Sends back the rejection of an invalid transaction, e.g., one whose caller
+/// cannot afford its value. Unlike other execution errors, this does not
+/// pause the [`Environment`].
+fn reject(
+    sender: &ResultSender,
+    invalid: InvalidTransaction,
+    block_number: U256,
+) -> Result<(), EnvironmentError> {
+    let revm_result = RevmResult {
+        outcome: TransactionOutcome::Error(EnvironmentError::Execution(EVMError::Transaction(
+            invalid,
+        ))),
+        block_number: convert_uint_to_u64(block_number)
+            .map_err(|e| EnvironmentError::Conversion(format!("{:?}", e)))?,
+    };
+    sender
+        .send(revm_result)
+        .map_err(|e| EnvironmentError::Communication(format!("{:?}", e)))
+}
+
+/// Makes a call against the state of the [`EVM`] without committing it. Any
+/// failure to execute the call is sent back rather than pausing the
+/// [`Environment`], since the live state is not involved.
+fn call(evm: &mut EVM<CacheDB<EmptyDB>>, tx: TxEnv) -> Result<RevmResult, EnvironmentError> {
+    evm.env.tx = tx;
+    let outcome = match evm.transact() {
+        Ok(result_and_state) => TransactionOutcome::Success(result_and_state.result),
+        Err(e) => TransactionOutcome::Error(EnvironmentError::Execution(e)),
+    };
+    Ok(RevmResult {
+        outcome,
+        block_number: convert_uint_to_u64(evm.env.block.number)
+            .map_err(|e| EnvironmentError::Conversion(format!("{:?}", e)))?,
+    })
+}
+
+/// Locks the [`EnvironmentMetrics`] shared with the thread of an
+/// [`Environment`].
+#[inline]
+fn lock_metrics(
+    metrics: &Mutex<EnvironmentMetrics>,
+) -> Result<std::sync::MutexGuard<'_, EnvironmentMetrics>, EnvironmentError> {
+    metrics
+        .lock()
+        .map_err(|e| EnvironmentError::Communication(format!("{:?}", e)))
+}
+
 /// Convert a U256 to a U64, discarding the higher bits if the number is larger
-/// than 2^64 # Arguments
+/// than 2^64. Used for block number which is a U64.
+/// # Arguments
 /// * `input` - The U256 to convert.
 /// # Returns
 /// * `Ok(U64)` - The converted U64.
-/// Used for block number which is a U64.
 #[inline]
-fn convert_uint_to_u64(input: U256) -> Result<U64, &'static str> {
+pub(crate) fn convert_uint_to_u64(input: U256) -> Result<U64, &'static str> {
     let as_str = input.to_string();
     match as_str.parse::<u64>() {
         Ok(val) => Ok(val.into()),
@@ -553,7 +1433,7 @@ pub(crate) mod tests {
             block_rate: 1.0,
             seed: 1,
         };
-        let environment = Environment::new(TEST_ENV_LABEL.to_string(), params);
+        let environment = Environment::new(TEST_ENV_LABEL.to_string(), params).unwrap();
         assert_eq!(environment.label, TEST_ENV_LABEL);
         let state = environment.state.load(std::sync::atomic::Ordering::SeqCst);
         assert_eq!(state, State::Initialization);
@@ -565,7 +1445,7 @@ pub(crate) mod tests {
             block_rate: 1.0,
             seed: 1,
         };
-        let mut environment = Environment::new(TEST_ENV_LABEL.to_string(), params);
+        let mut environment = Environment::new(TEST_ENV_LABEL.to_string(), params).unwrap();
         environment.run();
         let state = environment.state.load(std::sync::atomic::Ordering::SeqCst);
         assert_eq!(state, State::Running);
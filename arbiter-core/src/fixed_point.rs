@@ -0,0 +1,631 @@
+//! The `fixed_point` module provides checked fixed-point numbers backed by the
+//! integers contracts use, so that values can be moved between Rust and the
+//! EVM without silently losing precision.
+//!
+//! A [`FixedPoint`] is an unsigned `U256` with `DECIMALS` decimal places and a
+//! [`SignedFixedPoint`] is its `I256` counterpart. The usual precisions are
+//! aliased as [`Wad`], [`Ray`] and [`SignedWad`], but any amount of decimals
+//! can be used, e.g., to match the `decimals` of an `ArbiterToken`.
+//!
+//! Conversions from floats go through the shortest decimal representation of
+//! the float, so `1.23` becomes exactly `1.23` rather than the nearest binary
+//! fraction, and digits beyond the precision are truncated. Every conversion
+//! and operation that could overflow, divide by zero or lose the sign returns
+//! a [`FixedPointError`] instead. Multiplication and division round in the
+//! same direction as solmate's `mulWadDown`, `mulWadUp`, `divWadDown` and
+//! `divWadUp`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use arbiter_core::fixed_point::{SignedWad, Wad};
+//! # use ethers::types::U256;
+//! let price = Wad::from_f64(1.23)?;
+//! assert_eq!(price.raw(), U256::from(1_230_000_000_000_000_000_u128));
+//!
+//! let third = Wad::from_integer(1u64)?.div_down(Wad::from_integer(3u64)?)?;
+//! assert_eq!(third.to_string(), "0.333333333333333333");
+//! let third = Wad::from_integer(1u64)?.div_up(Wad::from_integer(3u64)?)?;
+//! assert_eq!(third.to_string(), "0.333333333333333334");
+//!
+//! assert!(Wad::from_f64(-1.0).is_err());
+//! assert_eq!(SignedWad::from_f64(-1.5)?.to_f64(), -1.5);
+//! # Ok::<(), arbiter_core::fixed_point::FixedPointError>(())
+//! ```
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{fmt, str::FromStr};
+
+use ethers::types::{Sign, I256, U256};
+use thiserror::Error;
+
+/// A fixed-point number with 18 decimals, the precision of most ERC-20 tokens
+/// and of solmate's `FixedPointMathLib`.
+pub type Wad = FixedPoint<18>;
+
+/// A fixed-point number with 27 decimals.
+pub type Ray = FixedPoint<27>;
+
+/// A signed fixed-point number with 18 decimals.
+pub type SignedWad = SignedFixedPoint<18>;
+
+/// The largest amount of decimals whose scale fits in a `U256`.
+pub const MAX_DECIMALS: u8 = 77;
+
+/// Errors that can occur when converting to or computing with fixed-point
+/// numbers.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FixedPointError {
+    /// [`FixedPointError::NotFinite`] is thrown when converting a NaN or an
+    /// infinite float.
+    #[error("cannot convert {0} to a fixed-point number")]
+    NotFinite(f64),
+
+    /// [`FixedPointError::Negative`] is thrown when a negative value is given
+    /// to or produced for an unsigned [`FixedPoint`].
+    #[error("unsigned fixed-point numbers cannot be negative")]
+    Negative,
+
+    /// [`FixedPointError::Overflow`] is thrown when a value or an
+    /// intermediate result does not fit in 256 bits.
+    #[error("fixed-point overflow")]
+    Overflow,
+
+    /// [`FixedPointError::DivisionByZero`] is thrown when dividing by zero.
+    #[error("fixed-point division by zero")]
+    DivisionByZero,
+
+    /// [`FixedPointError::InvalidDecimals`] is thrown when a precision has
+    /// more than [`MAX_DECIMALS`] decimals.
+    #[error("{0} decimals exceed the maximum of {MAX_DECIMALS}")]
+    InvalidDecimals(u8),
+
+    /// [`FixedPointError::Parse`] is thrown when a string is not a decimal
+    /// number.
+    #[error("cannot parse {0:?} as a fixed-point number")]
+    Parse(String),
+}
+
+/// An unsigned fixed-point number with `DECIMALS` decimals, stored as the raw
+/// `U256` a contract would see, i.e., the value times `10^DECIMALS`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedPoint<const DECIMALS: u8>(U256);
+
+/// A signed fixed-point number with `DECIMALS` decimals, stored as the raw
+/// `I256` a contract would see, i.e., the value times `10^DECIMALS`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignedFixedPoint<const DECIMALS: u8>(I256);
+
+impl<const DECIMALS: u8> FixedPoint<DECIMALS> {
+    /// Fails to compile for precisions whose scale does not fit in a `U256`.
+    const VALID: () = assert!(DECIMALS <= MAX_DECIMALS, "too many decimals");
+
+    /// The raw value of one, i.e., `10^DECIMALS`.
+    pub fn scale() -> U256 {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID;
+        U256::exp10(DECIMALS as usize)
+    }
+
+    /// Zero.
+    pub fn zero() -> Self {
+        Self(U256::zero())
+    }
+
+    /// One.
+    pub fn one() -> Self {
+        Self(Self::scale())
+    }
+
+    /// Wraps a raw value that already has `DECIMALS` decimals, e.g., a token
+    /// balance or a price read from a contract.
+    pub fn from_raw(raw: U256) -> Self {
+        Self(raw)
+    }
+
+    /// The raw value to hand to a contract.
+    pub fn raw(self) -> U256 {
+        self.0
+    }
+
+    /// Converts a whole number.
+    pub fn from_integer(integer: impl Into<U256>) -> Result<Self, FixedPointError> {
+        integer
+            .into()
+            .checked_mul(Self::scale())
+            .map(Self)
+            .ok_or(FixedPointError::Overflow)
+    }
+
+    /// Converts a float through its shortest decimal representation,
+    /// truncating the digits beyond `DECIMALS`.
+    ///
+    /// # Errors
+    ///
+    /// Fails for NaN, infinite, negative and overflowing values. Negative zero
+    /// is zero.
+    pub fn from_f64(x: f64) -> Result<Self, FixedPointError> {
+        if !x.is_finite() {
+            return Err(FixedPointError::NotFinite(x));
+        }
+        if x < 0.0 {
+            return Err(FixedPointError::Negative);
+        }
+        // `Display` never uses an exponent, so this is always a plain decimal.
+        format!("{}", x.abs()).parse()
+    }
+
+    /// Converts to the nearest float. Never fails, though very large values
+    /// lose precision.
+    pub fn to_f64(self) -> f64 {
+        u256_to_f64(self.0) / scale_f64(DECIMALS)
+    }
+
+    /// Converts a raw amount with `decimals` decimals, e.g., a balance of a
+    /// token with that many decimals, truncating the digits beyond
+    /// `DECIMALS`.
+    pub fn from_decimals(raw: U256, decimals: u8) -> Result<Self, FixedPointError> {
+        rescale(raw, decimals, DECIMALS).map(Self)
+    }
+
+    /// Converts to a raw amount with `decimals` decimals, truncating the
+    /// digits beyond `decimals`.
+    pub fn to_decimals(self, decimals: u8) -> Result<U256, FixedPointError> {
+        rescale(self.0, DECIMALS, decimals)
+    }
+
+    /// Converts to another precision, truncating the digits beyond `TO`.
+    pub fn rescale<const TO: u8>(self) -> Result<FixedPoint<TO>, FixedPointError> {
+        FixedPoint::<TO>::from_decimals(self.0, DECIMALS)
+    }
+
+    /// Adds `other`, failing on overflow.
+    pub fn checked_add(self, other: Self) -> Result<Self, FixedPointError> {
+        self.0
+            .checked_add(other.0)
+            .map(Self)
+            .ok_or(FixedPointError::Overflow)
+    }
+
+    /// Subtracts `other`, failing if the result would be negative.
+    pub fn checked_sub(self, other: Self) -> Result<Self, FixedPointError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Self)
+            .ok_or(FixedPointError::Negative)
+    }
+
+    /// Multiplies by `other` rounding down, like solmate's `mulWadDown`.
+    pub fn mul_down(self, other: Self) -> Result<Self, FixedPointError> {
+        mul_div(self.0, other.0, Self::scale(), false).map(Self)
+    }
+
+    /// Multiplies by `other` rounding up, like solmate's `mulWadUp`.
+    pub fn mul_up(self, other: Self) -> Result<Self, FixedPointError> {
+        mul_div(self.0, other.0, Self::scale(), true).map(Self)
+    }
+
+    /// Divides by `other` rounding down, like solmate's `divWadDown`.
+    pub fn div_down(self, other: Self) -> Result<Self, FixedPointError> {
+        mul_div(self.0, Self::scale(), other.0, false).map(Self)
+    }
+
+    /// Divides by `other` rounding up, like solmate's `divWadUp`.
+    pub fn div_up(self, other: Self) -> Result<Self, FixedPointError> {
+        mul_div(self.0, Self::scale(), other.0, true).map(Self)
+    }
+}
+
+impl<const DECIMALS: u8> SignedFixedPoint<DECIMALS> {
+    /// The raw value of one, i.e., `10^DECIMALS`.
+    pub fn scale() -> U256 {
+        FixedPoint::<DECIMALS>::scale()
+    }
+
+    /// Zero.
+    pub fn zero() -> Self {
+        Self(I256::zero())
+    }
+
+    /// One.
+    pub fn one() -> Self {
+        Self(I256::from_raw(Self::scale()))
+    }
+
+    /// Wraps a raw value that already has `DECIMALS` decimals.
+    pub fn from_raw(raw: I256) -> Self {
+        Self(raw)
+    }
+
+    /// The raw value to hand to a contract.
+    pub fn raw(self) -> I256 {
+        self.0
+    }
+
+    /// Whether the value is below zero.
+    pub fn is_negative(self) -> bool {
+        self.0.is_negative()
+    }
+
+    /// Converts a whole number.
+    pub fn from_integer(integer: impl Into<I256>) -> Result<Self, FixedPointError> {
+        let (sign, abs) = integer.into().into_sign_and_abs();
+        let abs = abs
+            .checked_mul(Self::scale())
+            .ok_or(FixedPointError::Overflow)?;
+        from_sign_and_abs(sign, abs).map(Self)
+    }
+
+    /// Converts a float through its shortest decimal representation,
+    /// truncating the digits beyond `DECIMALS` toward zero.
+    ///
+    /// # Errors
+    ///
+    /// Fails for NaN, infinite and overflowing values.
+    pub fn from_f64(x: f64) -> Result<Self, FixedPointError> {
+        if !x.is_finite() {
+            return Err(FixedPointError::NotFinite(x));
+        }
+        let abs = FixedPoint::<DECIMALS>::from_f64(x.abs())?;
+        let sign = if x < 0.0 {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        };
+        from_sign_and_abs(sign, abs.0).map(Self)
+    }
+
+    /// Converts to the nearest float. Never fails, though very large values
+    /// lose precision.
+    pub fn to_f64(self) -> f64 {
+        let magnitude = FixedPoint::<DECIMALS>(self.0.unsigned_abs()).to_f64();
+        if self.is_negative() {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Adds `other`, failing on overflow.
+    pub fn checked_add(self, other: Self) -> Result<Self, FixedPointError> {
+        self.0
+            .checked_add(other.0)
+            .map(Self)
+            .ok_or(FixedPointError::Overflow)
+    }
+
+    /// Subtracts `other`, failing on overflow.
+    pub fn checked_sub(self, other: Self) -> Result<Self, FixedPointError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Self)
+            .ok_or(FixedPointError::Overflow)
+    }
+
+    /// Multiplies by `other` rounding toward negative infinity.
+    pub fn mul_down(self, other: Self) -> Result<Self, FixedPointError> {
+        signed_mul_div(self.0, other.0, I256::from_raw(Self::scale()), false).map(Self)
+    }
+
+    /// Multiplies by `other` rounding toward positive infinity.
+    pub fn mul_up(self, other: Self) -> Result<Self, FixedPointError> {
+        signed_mul_div(self.0, other.0, I256::from_raw(Self::scale()), true).map(Self)
+    }
+
+    /// Divides by `other` rounding toward negative infinity.
+    pub fn div_down(self, other: Self) -> Result<Self, FixedPointError> {
+        signed_mul_div(self.0, I256::from_raw(Self::scale()), other.0, false).map(Self)
+    }
+
+    /// Divides by `other` rounding toward positive infinity.
+    pub fn div_up(self, other: Self) -> Result<Self, FixedPointError> {
+        signed_mul_div(self.0, I256::from_raw(Self::scale()), other.0, true).map(Self)
+    }
+}
+
+impl<const DECIMALS: u8> From<FixedPoint<DECIMALS>> for U256 {
+    fn from(value: FixedPoint<DECIMALS>) -> Self {
+        value.0
+    }
+}
+
+impl<const DECIMALS: u8> From<SignedFixedPoint<DECIMALS>> for I256 {
+    fn from(value: SignedFixedPoint<DECIMALS>) -> Self {
+        value.0
+    }
+}
+
+impl<const DECIMALS: u8> TryFrom<FixedPoint<DECIMALS>> for SignedFixedPoint<DECIMALS> {
+    type Error = FixedPointError;
+
+    fn try_from(value: FixedPoint<DECIMALS>) -> Result<Self, Self::Error> {
+        from_sign_and_abs(Sign::Positive, value.0).map(Self)
+    }
+}
+
+impl<const DECIMALS: u8> TryFrom<SignedFixedPoint<DECIMALS>> for FixedPoint<DECIMALS> {
+    type Error = FixedPointError;
+
+    fn try_from(value: SignedFixedPoint<DECIMALS>) -> Result<Self, Self::Error> {
+        if value.is_negative() {
+            return Err(FixedPointError::Negative);
+        }
+        Ok(Self(value.0.into_raw()))
+    }
+}
+
+impl<const DECIMALS: u8> FromStr for FixedPoint<DECIMALS> {
+    type Err = FixedPointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_decimal(s, DECIMALS)? {
+            (Sign::Negative, abs) if !abs.is_zero() => Err(FixedPointError::Negative),
+            (_, abs) => Ok(Self(abs)),
+        }
+    }
+}
+
+impl<const DECIMALS: u8> FromStr for SignedFixedPoint<DECIMALS> {
+    type Err = FixedPointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sign, abs) = parse_decimal(s, DECIMALS)?;
+        from_sign_and_abs(sign, abs).map(Self)
+    }
+}
+
+impl<const DECIMALS: u8> fmt::Display for FixedPoint<DECIMALS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_decimal(f, self.0, DECIMALS)
+    }
+}
+
+impl<const DECIMALS: u8> fmt::Display for SignedFixedPoint<DECIMALS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+        write_decimal(f, self.0.unsigned_abs(), DECIMALS)
+    }
+}
+
+/// Computes `x * y / denominator` rounding down or up, failing if `x * y`
+/// overflows just as solmate's `mulDivDown` and `mulDivUp` revert.
+fn mul_div(x: U256, y: U256, denominator: U256, round_up: bool) -> Result<U256, FixedPointError> {
+    if denominator.is_zero() {
+        return Err(FixedPointError::DivisionByZero);
+    }
+    let product = x.checked_mul(y).ok_or(FixedPointError::Overflow)?;
+    let (quotient, remainder) = product.div_mod(denominator);
+    if round_up && !remainder.is_zero() {
+        Ok(quotient + 1)
+    } else {
+        Ok(quotient)
+    }
+}
+
+/// Computes `x * y / denominator` rounding toward negative or positive
+/// infinity.
+fn signed_mul_div(
+    x: I256,
+    y: I256,
+    denominator: I256,
+    round_up: bool,
+) -> Result<I256, FixedPointError> {
+    let negative = x.is_negative() ^ y.is_negative() ^ denominator.is_negative();
+    // Rounding the magnitude up moves a negative result down
+    let abs = mul_div(
+        x.unsigned_abs(),
+        y.unsigned_abs(),
+        denominator.unsigned_abs(),
+        round_up != negative,
+    )?;
+    let sign = if negative {
+        Sign::Negative
+    } else {
+        Sign::Positive
+    };
+    from_sign_and_abs(sign, abs)
+}
+
+/// Builds an `I256`, treating a negative zero as zero rather than as an
+/// overflow like `I256::checked_from_sign_and_abs` does.
+fn from_sign_and_abs(sign: Sign, abs: U256) -> Result<I256, FixedPointError> {
+    if abs.is_zero() {
+        return Ok(I256::zero());
+    }
+    I256::checked_from_sign_and_abs(sign, abs).ok_or(FixedPointError::Overflow)
+}
+
+/// Moves `raw` from `from` decimals to `to` decimals, truncating the digits
+/// that no longer fit.
+fn rescale(raw: U256, from: u8, to: u8) -> Result<U256, FixedPointError> {
+    for decimals in [from, to] {
+        if decimals > MAX_DECIMALS {
+            return Err(FixedPointError::InvalidDecimals(decimals));
+        }
+    }
+    if to >= from {
+        raw.checked_mul(U256::exp10((to - from) as usize))
+            .ok_or(FixedPointError::Overflow)
+    } else {
+        Ok(raw / U256::exp10((from - to) as usize))
+    }
+}
+
+/// Parses an optionally signed decimal number into its sign and its raw
+/// magnitude with `decimals` decimals, truncating the digits beyond them.
+fn parse_decimal(s: &str, decimals: u8) -> Result<(Sign, U256), FixedPointError> {
+    let invalid = || FixedPointError::Parse(s.to_string());
+    let (sign, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (Sign::Negative, rest),
+        None => (Sign::Positive, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let fraction: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(decimals as usize)
+        .collect();
+    let digits = format!("{integer}{fraction}");
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok((sign, U256::zero()));
+    }
+    // Anything with more than 78 digits cannot fit, which `from_dec_str` would
+    // report as an overflow too
+    U256::from_dec_str(digits)
+        .map(|abs| (sign, abs))
+        .map_err(|_| FixedPointError::Overflow)
+}
+
+/// Writes a raw magnitude with `decimals` decimals without trailing zeros.
+fn write_decimal(f: &mut fmt::Formatter<'_>, abs: U256, decimals: u8) -> fmt::Result {
+    let scale = U256::exp10(decimals as usize);
+    let (integer, fraction) = abs.div_mod(scale);
+    write!(f, "{integer}")?;
+    if fraction.is_zero() {
+        return Ok(());
+    }
+    let fraction = format!("{:0>width$}", fraction, width = decimals as usize);
+    write!(f, ".{}", fraction.trim_end_matches('0'))
+}
+
+/// The nearest float to `x`, exact for anything that fits in a `u128`.
+pub(crate) fn u256_to_f64(x: U256) -> f64 {
+    let shift = x.bits().saturating_sub(128);
+    (x >> shift).as_u128() as f64 * 2f64.powi(shift as i32)
+}
+
+fn scale_f64(decimals: u8) -> f64 {
+    10f64.powi(decimals as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_conversions() {
+        assert_eq!(
+            Wad::from_f64(1.23).unwrap().raw(),
+            U256::from(1_230_000_000_000_000_000_u128)
+        );
+        assert_eq!(Wad::from_f64(-0.0).unwrap(), Wad::zero());
+        assert_eq!(Wad::from_f64(-1.0), Err(FixedPointError::Negative));
+        assert!(matches!(
+            Wad::from_f64(f64::NAN),
+            Err(FixedPointError::NotFinite(_))
+        ));
+        assert_eq!(
+            Wad::from_f64(f64::INFINITY),
+            Err(FixedPointError::NotFinite(f64::INFINITY))
+        );
+        // Far beyond what `float_to_wad` can handle, but still in range
+        let large = Wad::from_f64(1e40).unwrap();
+        assert_eq!(large.raw(), U256::exp10(58));
+        assert!((large.to_f64() / 1e40 - 1.0).abs() < 1e-15);
+        assert_eq!(Wad::from_f64(1e60), Err(FixedPointError::Overflow));
+        // Digits beyond the precision are truncated
+        assert_eq!(FixedPoint::<2>::from_f64(1.239).unwrap().raw(), 123.into());
+        assert!((Wad::from_raw(U256::MAX).to_f64() / 1.157920892373162e59 - 1.0).abs() < 1e-15);
+
+        let signed = SignedWad::from_f64(-2.5).unwrap();
+        assert_eq!(signed.raw(), I256::from(-2_500_000_000_000_000_000_i128));
+        assert_eq!(signed.to_f64(), -2.5);
+        assert_eq!(signed.to_string(), "-2.5");
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let x: FixedPoint<6> = "12.345678".parse().unwrap();
+        assert_eq!(x.raw(), 12_345_678.into());
+        assert_eq!(x.to_string(), "12.345678");
+        assert_eq!("0.5".parse::<Wad>().unwrap().to_string(), "0.5");
+        assert_eq!(".5".parse::<Wad>().unwrap().to_string(), "0.5");
+        assert_eq!("7".parse::<Ray>().unwrap().raw(), U256::exp10(27) * 7);
+        assert_eq!("-0".parse::<Wad>().unwrap(), Wad::zero());
+        assert_eq!("-1".parse::<Wad>(), Err(FixedPointError::Negative));
+        for invalid in ["", ".", "1.2.3", "1e5", "abc", "--1"] {
+            assert!(matches!(
+                invalid.parse::<Wad>(),
+                Err(FixedPointError::Parse(_))
+            ));
+        }
+        assert_eq!("-0".parse::<SignedWad>().unwrap(), SignedWad::zero());
+        assert_eq!(
+            "-0.000001".parse::<SignedFixedPoint<6>>().unwrap().raw(),
+            I256::from(-1)
+        );
+    }
+
+    #[test]
+    fn rounding_matches_solmate() {
+        let wad = |raw: u64| Wad::from_raw(raw.into());
+        // mulWadDown(3, 0.5e18) = 1 and mulWadUp(3, 0.5e18) = 2
+        let half = Wad::from_f64(0.5).unwrap();
+        assert_eq!(wad(3).mul_down(half).unwrap(), wad(1));
+        assert_eq!(wad(3).mul_up(half).unwrap(), wad(2));
+        assert_eq!(wad(0).mul_up(half).unwrap(), wad(0));
+        // divWadDown(1, 3e18) = 0 and divWadUp(1, 3e18) = 1
+        let three = Wad::from_integer(3u64).unwrap();
+        assert_eq!(wad(1).div_down(three).unwrap(), wad(0));
+        assert_eq!(wad(1).div_up(three).unwrap(), wad(1));
+        assert_eq!(
+            three.div_down(Wad::zero()),
+            Err(FixedPointError::DivisionByZero)
+        );
+        assert_eq!(
+            Wad::from_raw(U256::MAX).mul_down(three),
+            Err(FixedPointError::Overflow)
+        );
+
+        let signed = |raw: i64| SignedWad::from_raw(raw.into());
+        let half = SignedWad::from_f64(0.5).unwrap();
+        assert_eq!(signed(-3).mul_down(half).unwrap(), signed(-2));
+        assert_eq!(signed(-3).mul_up(half).unwrap(), signed(-1));
+        assert_eq!(signed(3).mul_down(half).unwrap(), signed(1));
+        let minus_three = SignedWad::from_integer(-3).unwrap();
+        assert_eq!(signed(1).div_down(minus_three).unwrap(), signed(-1));
+        assert_eq!(signed(1).div_up(minus_three).unwrap(), signed(0));
+        assert_eq!(signed(-1).div_down(minus_three).unwrap(), signed(0));
+    }
+
+    #[test]
+    fn decimals() {
+        // 1.5 of a token with 6 decimals
+        let usdc = U256::from(1_500_000);
+        let amount = Wad::from_decimals(usdc, 6).unwrap();
+        assert_eq!(amount.to_string(), "1.5");
+        assert_eq!(amount.to_decimals(6).unwrap(), usdc);
+        assert_eq!(
+            Wad::from_raw(1.into()).rescale::<6>().unwrap(),
+            FixedPoint::<6>::zero()
+        );
+        assert_eq!(
+            amount.to_decimals(78),
+            Err(FixedPointError::InvalidDecimals(78))
+        );
+        assert_eq!(
+            Wad::from_raw(U256::MAX).rescale::<27>(),
+            Err(FixedPointError::Overflow)
+        );
+        assert_eq!(
+            SignedWad::try_from(Wad::from_raw(U256::MAX)),
+            Err(FixedPointError::Overflow)
+        );
+        assert_eq!(
+            Wad::try_from(SignedWad::from_integer(-1).unwrap()),
+            Err(FixedPointError::Negative)
+        );
+    }
+}
@@ -18,8 +18,30 @@
 //! Key Features:
 //! - **Manager Interface**: The main user entry-point that offers management of
 //!   different environments and agents.
+//! - **Agents**: Attach agents to environments and let them react to new blocks
+//!   and events.
 //! - **Environment Handling**: Detailed setup and control mechanisms for
 //!   running the Ethereum-like blockchain environment.
+//! - **Clock Synchronization**: Keep the blocks of multiple environments in
+//!   lockstep with a shared simulation time.
+//! - **Bridging**: Relay messages between environments to model rollups and
+//!   their L1.
+//! - **Recording**: Stream the decoded events of an environment to CSV or
+//!   JSON-lines files.
+//! - **Metrics**: Keep track of what each environment has processed and serve
+//!   it to dashboards.
+//! - **Observers**: Sample contract views at the end of every block into a time
+//!   series.
+//! - **Price Driving**: Walk the price of a `LiquidExchange` along a seeded
+//!   stochastic price path.
+//! - **Arbitrage**: A reference arbitrageur that keeps a constant product pool
+//!   in line with a `LiquidExchange`.
+//! - **Fixed-Point Numbers**: Checked WAD, RAY and arbitrary-decimal numbers
+//!   that round like solmate's `FixedPointMathLib`.
+//! - **Differential Testing**: Check Rust models against contract methods over
+//!   seeded inputs and report their errors.
+//! - **JSON-RPC Server**: Serve an environment over HTTP and WebSocket so that
+//!   tools like `cast`, web3.py or frontends can talk to it like a local node.
 //! - **Middleware Implementation**: Customized middleware to reduce overhead
 //!   and provide optimal performance.
 //!
@@ -31,10 +53,21 @@
 
 #![warn(missing_docs, unsafe_code)]
 
+pub mod agent;
+pub mod arbitrageur;
 pub mod bindings; // TODO: Add better documentation here and some kind of overwrite protection.
+pub mod bridge;
+pub mod clock;
+pub mod differential;
 pub mod environment;
+pub mod fixed_point;
 pub mod manager;
 pub mod math;
+pub mod metrics;
 pub mod middleware;
+pub mod observer;
+pub mod price_driver;
+pub mod recorder;
+pub mod server;
 #[cfg(test)]
 mod tests;
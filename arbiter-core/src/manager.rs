@@ -2,20 +2,48 @@
 //! environments that house simulations.
 //!
 //! It centralizes operations such as creating/adding environments as well as
-//! starting, pausing, and stopping a chosen environment.
+//! starting, pausing, and stopping a chosen environment. It can also keep a
+//! chosen group of environments in lockstep via a shared [`Clock`], relay
+//! messages between two environments via a [`Bridge`] and record the events of
+//! an environment via a [`Recorder`]. The [`EnvironmentMetrics`] of every
+//! environment can be inspected or served to dashboards, and contract views
+//! can be sampled every block via an [`Observer`], and an environment can be
+//! served over JSON-RPC to tools outside of Rust. Finally, it attaches
+//! [`Agent`]s to environments and stops them along with their environment.
 
 #![warn(missing_docs, unsafe_code)]
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    net::{SocketAddr, TcpListener, ToSocketAddrs},
+    path::Path,
+    sync::Arc,
+};
 
+use ethers::{
+    abi::{Abi, Detokenize},
+    contract::ContractCall,
+    types::{Filter, Log},
+};
 use log::{info, warn};
 use thiserror::Error;
 
-use crate::environment::{Environment, EnvironmentParameters, State};
 #[cfg_attr(doc, doc(hidden))]
 #[cfg_attr(doc, allow(unused_imports))]
 #[cfg(doc)]
 use crate::math::SeededPoisson;
+use crate::{
+    agent::{Agent, AgentError, AgentRuntime},
+    bridge::{Bridge, BridgeError, BridgeMessage, BridgeParameters},
+    clock::{Clock, ClockError, ClockParameters},
+    environment::{Environment, EnvironmentParameters, State},
+    math::{BlockSizeDistribution, MathError},
+    metrics::{self, EnvironmentMetrics, MetricsRegistry, MetricsServer},
+    middleware::RevmMiddleware,
+    observer::{self, Observation, Observer},
+    recorder::{Recorder, RecorderError, Sink},
+    server::{self, RpcServer},
+};
 
 /// The primary manager structure for maintaining a collection of environments.
 ///
@@ -26,6 +54,34 @@ pub struct Manager {
     /// A map of environment labels to their corresponding environment
     /// structures.
     pub environments: HashMap<String, Environment>,
+
+    /// A map of clock labels to the [`Clock`]s that synchronize groups of
+    /// environments.
+    pub clocks: HashMap<String, Clock>,
+
+    /// A map of bridge labels to the [`Bridge`]s that relay messages between
+    /// environments.
+    pub bridges: HashMap<String, Bridge>,
+
+    /// A map of recorder labels to the [`Recorder`]s that stream the events of
+    /// an environment to their sinks.
+    pub recorders: HashMap<String, Recorder>,
+
+    /// A map of agent labels to the [`Agent`]s running in the environments.
+    pub agents: HashMap<String, AgentRuntime>,
+
+    /// The metrics of every environment added to the manager, shared with the
+    /// metrics endpoint started by [`Manager::serve_metrics`].
+    metrics_registry: MetricsRegistry,
+
+    /// The metrics endpoint started by [`Manager::serve_metrics`], if any. It
+    /// is stopped by [`Manager::stop_metrics`] or when the manager is dropped.
+    metrics_server: Option<MetricsServer>,
+
+    /// The JSON-RPC servers started by [`Manager::serve_rpc`] by the label of
+    /// the environment they serve. They are stopped by [`Manager::stop_rpc`]
+    /// or when the manager is dropped.
+    rpc_servers: HashMap<String, RpcServer>,
 }
 
 /// Errors that can occur while operating on or with the [`Manager`].
@@ -64,6 +120,86 @@ pub enum ManagerError {
     #[error("environment labeled {0} is already paused!")]
     EnvironmentAlreadyPaused(String),
 
+    /// Indicates that the [`Environment`] with the given label is already
+    /// synchronized by a [`Clock`].
+    #[error("environment labeled {0} is already synchronized by a clock!")]
+    EnvironmentAlreadySynchronized(String),
+
+    /// Indicates that a [`Clock`] with the given label already exists.
+    #[error("clock labeled {0} already exists!")]
+    ClockAlreadyExists(String),
+
+    /// Indicates that no [`Clock`] exists with the provided label.
+    #[error("clock labeled {0} does not exist!")]
+    ClockDoesNotExist(String),
+
+    /// Indicates that a [`Clock`] could not be operated on.
+    #[error("clock failed: {0}")]
+    Clock(ClockError),
+
+    /// Indicates that a [`Bridge`] with the given label already exists.
+    #[error("bridge labeled {0} already exists!")]
+    BridgeAlreadyExists(String),
+
+    /// Indicates that a [`Bridge`] could not subscribe to its source
+    /// [`Environment`] or failed while relaying to the stopped one.
+    #[error("bridge failed: {0}")]
+    Bridge(BridgeError),
+
+    /// Indicates that a [`Recorder`] with the given label already exists.
+    #[error("recorder labeled {0} already exists!")]
+    RecorderAlreadyExists(String),
+
+    /// Indicates that a [`Recorder`] of the stopped [`Environment`] failed to
+    /// write or flush its records.
+    #[error("recorder failed to write its records: {0}")]
+    Recorder(RecorderError),
+
+    /// Indicates that an [`Agent`] with the given label already exists.
+    #[error("agent labeled {0} already exists!")]
+    AgentAlreadyExists(String),
+
+    /// Indicates that an [`Agent`] of the stopped [`Environment`] failed.
+    #[error("agent failed: {0}")]
+    Agent(AgentError),
+
+    /// Indicates that an [`Observer`] with the given label already exists in
+    /// the [`Environment`].
+    #[error("observer labeled {0} already exists!")]
+    ObserverAlreadyExists(String),
+
+    /// Indicates that no [`Observer`] exists with the provided label in the
+    /// [`Environment`].
+    #[error("observer labeled {0} does not exist!")]
+    ObserverDoesNotExist(String),
+
+    /// Indicates that an [`Observer`] cannot be created from the given call,
+    /// e.g., because the call has no recipient.
+    #[error("invalid observer: {0}")]
+    InvalidObserver(String),
+
+    /// Indicates that the [`Observation`]s could not be exported.
+    #[error("failed to export observations: {0}")]
+    Export(String),
+
+    /// Indicates that the metrics endpoint could not be started.
+    #[error("failed to serve metrics: {0}")]
+    MetricsServer(String),
+
+    /// Indicates that the JSON-RPC server of an [`Environment`] could not be
+    /// started.
+    #[error("failed to serve JSON-RPC: {0}")]
+    RpcServer(String),
+
+    /// Indicates that the block sizes of an [`Environment`] cannot be drawn
+    /// from the given distribution, e.g., because its parameters are invalid.
+    #[error("invalid block sizes: {0}")]
+    InvalidBlockSizes(MathError),
+
+    /// Indicates that the logs an [`Environment`] keeps could not be locked.
+    #[error("failed to lock the logs of the environment: {0}")]
+    LogStore(String),
+
     /// Indicates that the [`Environment`]'s thread handle could not be found.
     #[error("no handle available to join the environment")]
     NoHandleAvailable,
@@ -84,6 +220,13 @@ impl Manager {
     pub fn new() -> Self {
         Self {
             environments: HashMap::new(),
+            clocks: HashMap::new(),
+            bridges: HashMap::new(),
+            recorders: HashMap::new(),
+            agents: HashMap::new(),
+            metrics_registry: MetricsRegistry::default(),
+            metrics_server: None,
+            rpc_servers: HashMap::new(),
         }
     }
 
@@ -103,6 +246,8 @@ impl Manager {
     /// - `Ok(())`: The environment was successfully added.
     /// - `Err(ManagerError::EnvironmentAlreadyExists)`: An environment with the
     ///   specified label already exists.
+    /// - `Err(ManagerError::InvalidBlockSizes)`: The block rate is not
+    ///   positive.
     ///
     /// # Examples
     ///
@@ -127,15 +272,1012 @@ impl Manager {
             return Err(ManagerError::EnvironmentAlreadyExists(label_str));
         }
 
-        self.environments.insert(
-            label_str.clone(),
-            Environment::new(environment_label, params),
-        );
+        let environment =
+            Environment::new(environment_label, params).map_err(ManagerError::InvalidBlockSizes)?;
+        if let Ok(mut registry) = self.metrics_registry.lock() {
+            registry.insert(label_str.clone(), Arc::clone(&environment.metrics));
+        }
+        self.environments.insert(label_str.clone(), environment);
 
         info!("Added environment labeled {}", label_str);
         Ok(())
     }
 
+    /// Sets the distribution that the block sizes of an environment are drawn
+    /// from in place of the Poisson distribution given by its block rate. The
+    /// distribution is seeded with the seed of the environment.
+    ///
+    /// # Parameters
+    ///
+    /// - `environment_label`: The label of the environment.
+    /// - `distribution`: The distribution to draw block sizes from.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The distribution was successfully set.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::EnvironmentAlreadyRunning)`: The environment has
+    ///   already been started.
+    /// - `Err(ManagerError::InvalidBlockSizes)`: The parameters of the
+    ///   distribution are invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{
+    ///     environment::EnvironmentParameters, manager::Manager, math::BlockSizeDistribution,
+    /// };
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// let bursty = BlockSizeDistribution::NegativeBinomial {
+    ///     mean: 10.0,
+    ///     dispersion: 0.5,
+    /// };
+    /// manager.set_block_sizes("example_env", bursty).unwrap();
+    /// ```
+    pub fn set_block_sizes<S: Into<String> + Clone>(
+        &mut self,
+        environment_label: S,
+        distribution: BlockSizeDistribution,
+    ) -> Result<(), ManagerError> {
+        let label_str: String = environment_label.into();
+        let environment = match self.environments.get_mut(&label_str) {
+            Some(environment) => environment,
+            None => return Err(ManagerError::EnvironmentDoesNotExist(label_str)),
+        };
+        if environment.state.load(std::sync::atomic::Ordering::SeqCst) != State::Initialization {
+            return Err(ManagerError::EnvironmentAlreadyRunning(label_str));
+        }
+        environment.block_sizes = distribution
+            .seeded(environment.block_sizes.seed)
+            .map_err(ManagerError::InvalidBlockSizes)?;
+        info!("Set the block sizes of environment labeled {}", label_str);
+        Ok(())
+    }
+
+    /// Stops an environment from checking the nonces of transactions so that
+    /// transactions with any nonce are executed. By default, a transaction
+    /// that comes with a nonce is rejected unless it is the current nonce of
+    /// its caller, while transactions without one use the current nonce.
+    ///
+    /// # Parameters
+    ///
+    /// - `environment_label`: The label of the environment.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The nonce checks were successfully disabled.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::EnvironmentAlreadyRunning)`: The environment has
+    ///   already been started.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// manager.disable_nonce_checks("example_env").unwrap();
+    /// ```
+    pub fn disable_nonce_checks<S: Into<String> + Clone>(
+        &mut self,
+        environment_label: S,
+    ) -> Result<(), ManagerError> {
+        let label_str: String = environment_label.into();
+        let environment = match self.environments.get_mut(&label_str) {
+            Some(environment) => environment,
+            None => return Err(ManagerError::EnvironmentDoesNotExist(label_str)),
+        };
+        if environment.state.load(std::sync::atomic::Ordering::SeqCst) != State::Initialization {
+            return Err(ManagerError::EnvironmentAlreadyRunning(label_str));
+        }
+        environment.nonce_checks = false;
+        info!(
+            "Disabled the nonce checks of environment labeled {}",
+            label_str
+        );
+        Ok(())
+    }
+
+    /// Keeps the state of an environment at the end of each of its last
+    /// `blocks` blocks so that calls and state reads can be made as of those
+    /// blocks, e.g., by passing a past block number to
+    /// [`Middleware::call`](ethers::providers::Middleware::call). By default,
+    /// no past state is kept. Each kept block holds a copy of the whole
+    /// state, so keep `blocks` small for large states.
+    ///
+    /// # Parameters
+    ///
+    /// - `environment_label`: The label of the environment.
+    /// - `blocks`: The amount of past blocks to keep the state of.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The retention was successfully set.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::EnvironmentAlreadyRunning)`: The environment has
+    ///   already been started.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// manager.set_state_retention("example_env", 100).unwrap();
+    /// ```
+    pub fn set_state_retention<S: Into<String> + Clone>(
+        &mut self,
+        environment_label: S,
+        blocks: usize,
+    ) -> Result<(), ManagerError> {
+        let label_str: String = environment_label.into();
+        let environment = match self.environments.get_mut(&label_str) {
+            Some(environment) => environment,
+            None => return Err(ManagerError::EnvironmentDoesNotExist(label_str)),
+        };
+        if environment.state.load(std::sync::atomic::Ordering::SeqCst) != State::Initialization {
+            return Err(ManagerError::EnvironmentAlreadyRunning(label_str));
+        }
+        environment.state_retention = blocks;
+        info!(
+            "Set the state retention of environment labeled {} to {} blocks",
+            label_str, blocks
+        );
+        Ok(())
+    }
+
+    /// Keeps the logs an environment emitted in its last `blocks` blocks,
+    /// including the current one, so that they can be queried, e.g., via
+    /// [`Middleware::get_logs`](ethers::providers::Middleware::get_logs). The
+    /// logs of older blocks are forgotten as the environment moves on. By
+    /// default, the logs of the last
+    /// [`DEFAULT_LOG_RETENTION`](crate::environment::DEFAULT_LOG_RETENTION)
+    /// blocks are kept. The retention can be changed while the environment
+    /// runs and takes effect with the next logs it emits.
+    ///
+    /// # Parameters
+    ///
+    /// - `environment_label`: The label of the environment.
+    /// - `blocks`: The amount of blocks to keep the logs of.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The retention was successfully set.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::LogStore)`: The logs of the environment could not
+    ///   be locked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// manager.set_log_retention("example_env", 100).unwrap();
+    /// ```
+    pub fn set_log_retention<S: Into<String> + Clone>(
+        &mut self,
+        environment_label: S,
+        blocks: usize,
+    ) -> Result<(), ManagerError> {
+        let label_str: String = environment_label.into();
+        let environment = match self.environments.get(&label_str) {
+            Some(environment) => environment,
+            None => return Err(ManagerError::EnvironmentDoesNotExist(label_str)),
+        };
+        environment
+            .log_store
+            .lock()
+            .map_err(|e| ManagerError::LogStore(e.to_string()))?
+            .set_retention(blocks);
+        info!(
+            "Set the log retention of environment labeled {} to {} blocks",
+            label_str, blocks
+        );
+        Ok(())
+    }
+
+    /// Adds a new [`Clock`] to the manager that keeps the given environments
+    /// in lockstep.
+    ///
+    /// None of the synchronized environments will move on to block `N + 1`
+    /// until all of them have finished block `N`. Their block timestamps are
+    /// given by the [`Clock`] so that agents can read a shared simulation
+    /// time via [`Clock::now`]. An environment without transactions of its
+    /// own has to be moved on via [`RevmMiddleware::mine`] or stopped, as it
+    /// otherwise holds back the rest.
+    ///
+    /// # Parameters
+    ///
+    /// - `clock_label`: The label (identifier) to be used for the clock.
+    /// - `environment_labels`: The labels of the environments to synchronize.
+    /// - `params`: Parameters required to initialize the clock.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The clock was successfully added.
+    /// - `Err(ManagerError::ClockAlreadyExists)`: A clock with the specified
+    ///   label already exists.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with one
+    ///   of the specified labels exists.
+    /// - `Err(ManagerError::EnvironmentAlreadyRunning)`: One of the
+    ///   environments has already been started.
+    /// - `Err(ManagerError::EnvironmentAlreadySynchronized)`: One of the
+    ///   environments is already synchronized by another clock.
+    /// - `Err(ManagerError::Clock)`: The clock could not be joined.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{
+    ///     clock::ClockParameters, environment::EnvironmentParameters, manager::Manager,
+    /// };
+    ///
+    /// let mut manager = Manager::new();
+    /// for label in ["l1", "l2"] {
+    ///     let params = EnvironmentParameters {
+    ///         block_rate: 1.0,
+    ///         seed: 1,
+    ///     };
+    ///     manager.add_environment(label, params).unwrap();
+    /// }
+    /// let params = ClockParameters {
+    ///     genesis_timestamp: 0,
+    ///     block_time: 12,
+    /// };
+    /// manager
+    ///     .add_clock("example_clock", &["l1", "l2"], params)
+    ///     .unwrap();
+    /// ```
+    pub fn add_clock<S: Into<String> + Clone>(
+        &mut self,
+        clock_label: S,
+        environment_labels: &[S],
+        params: ClockParameters,
+    ) -> Result<(), ManagerError> {
+        let clock_label: String = clock_label.into();
+        if self.clocks.contains_key(&clock_label) {
+            return Err(ManagerError::ClockAlreadyExists(clock_label));
+        }
+
+        // Check every environment before attaching any so that a failure leaves
+        // the manager untouched
+        for environment_label in environment_labels {
+            let label_str: String = environment_label.clone().into();
+            match self.environments.get(&label_str) {
+                Some(environment) => {
+                    if environment.state.load(std::sync::atomic::Ordering::SeqCst)
+                        != State::Initialization
+                    {
+                        return Err(ManagerError::EnvironmentAlreadyRunning(label_str));
+                    }
+                    if environment.clock.is_some() {
+                        return Err(ManagerError::EnvironmentAlreadySynchronized(label_str));
+                    }
+                }
+                None => return Err(ManagerError::EnvironmentDoesNotExist(label_str)),
+            }
+        }
+
+        let clock = Clock::new(params);
+        for environment_label in environment_labels {
+            if let Some(environment) = self.environments.get_mut(&environment_label.clone().into())
+            {
+                clock
+                    .join(&environment.label)
+                    .map_err(ManagerError::Clock)?;
+                environment.clock = Some(clock.clone());
+            }
+        }
+        info!("Added clock labeled {}", clock_label);
+        self.clocks.insert(clock_label, clock);
+        Ok(())
+    }
+
+    /// Suspends the [`Clock`] with the given label so that its environments
+    /// move on to new blocks without waiting for each other, e.g., while
+    /// contracts are deployed into only some of them.
+    ///
+    /// # Parameters
+    ///
+    /// - `clock_label`: The label of the clock to suspend.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The clock was successfully suspended.
+    /// - `Err(ManagerError::ClockDoesNotExist)`: No clock with the specified
+    ///   label exists.
+    /// - `Err(ManagerError::Clock)`: The state of the clock could not be
+    ///   locked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{
+    ///     clock::ClockParameters, environment::EnvironmentParameters, manager::Manager,
+    /// };
+    ///
+    /// let mut manager = Manager::new();
+    /// for label in ["l1", "l2"] {
+    ///     let params = EnvironmentParameters {
+    ///         block_rate: 1.0,
+    ///         seed: 1,
+    ///     };
+    ///     manager.add_environment(label, params).unwrap();
+    /// }
+    /// let params = ClockParameters {
+    ///     genesis_timestamp: 0,
+    ///     block_time: 12,
+    /// };
+    /// manager
+    ///     .add_clock("example_clock", &["l1", "l2"], params)
+    ///     .unwrap();
+    /// manager.suspend_clock("example_clock").unwrap();
+    /// // ... set up the contracts of each environment ...
+    /// manager.resume_clock("example_clock").unwrap();
+    /// ```
+    pub fn suspend_clock<S: Into<String> + Clone>(
+        &mut self,
+        clock_label: S,
+    ) -> Result<(), ManagerError> {
+        let clock_label: String = clock_label.into();
+        match self.clocks.get(&clock_label) {
+            Some(clock) => {
+                clock.suspend().map_err(ManagerError::Clock)?;
+                info!("Suspended clock labeled {}", clock_label);
+                Ok(())
+            }
+            None => Err(ManagerError::ClockDoesNotExist(clock_label)),
+        }
+    }
+
+    /// Resumes the [`Clock`] with the given label so that its environments
+    /// are kept in lockstep again from their next block on. See
+    /// [`Manager::suspend_clock`].
+    ///
+    /// # Parameters
+    ///
+    /// - `clock_label`: The label of the clock to resume.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The clock was successfully resumed.
+    /// - `Err(ManagerError::ClockDoesNotExist)`: No clock with the specified
+    ///   label exists.
+    /// - `Err(ManagerError::Clock)`: The state of the clock could not be
+    ///   locked.
+    pub fn resume_clock<S: Into<String> + Clone>(
+        &mut self,
+        clock_label: S,
+    ) -> Result<(), ManagerError> {
+        let clock_label: String = clock_label.into();
+        match self.clocks.get(&clock_label) {
+            Some(clock) => {
+                clock.resume().map_err(ManagerError::Clock)?;
+                info!("Resumed clock labeled {}", clock_label);
+                Ok(())
+            }
+            None => Err(ManagerError::ClockDoesNotExist(clock_label)),
+        }
+    }
+
+    /// Adds a new [`Bridge`] to the manager that relays messages from one
+    /// environment to another.
+    ///
+    /// Every log emitted in the source environment that matches the
+    /// [`BridgeParameters::filter`] is passed to `relay`. If `relay` returns a
+    /// [`BridgeMessage`], it is delivered as a transaction from the
+    /// [`BridgeParameters::relayer`] into the destination environment once
+    /// [`BridgeParameters::block_delay`] blocks have passed in the source
+    /// environment. The bridge stops relaying once either environment is
+    /// stopped.
+    ///
+    /// # Parameters
+    ///
+    /// - `bridge_label`: The label (identifier) to be used for the bridge.
+    /// - `source_label`: The label of the environment whose events are watched.
+    /// - `destination_label`: The label of the environment messages are
+    ///   delivered to.
+    /// - `params`: Parameters required to initialize the bridge.
+    /// - `relay`: Turns a matching log into the message to deliver.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The bridge was successfully added.
+    /// - `Err(ManagerError::BridgeAlreadyExists)`: A bridge with the specified
+    ///   label already exists.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with one
+    ///   of the specified labels exists.
+    /// - `Err(ManagerError::EnvironmentStopped)`: One of the environments has
+    ///   already been stopped.
+    /// - `Err(ManagerError::Bridge)`: The bridge could not subscribe to the
+    ///   events of the source environment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{
+    ///     bridge::{BridgeMessage, BridgeParameters},
+    ///     environment::EnvironmentParameters,
+    ///     manager::Manager,
+    /// };
+    /// use ethers::types::{Address, Filter};
+    ///
+    /// let mut manager = Manager::new();
+    /// for label in ["l1", "l2"] {
+    ///     let params = EnvironmentParameters {
+    ///         block_rate: 1.0,
+    ///         seed: 1,
+    ///     };
+    ///     manager.add_environment(label, params).unwrap();
+    /// }
+    /// let params = BridgeParameters {
+    ///     filter: Filter::new(),
+    ///     block_delay: 10,
+    ///     relayer: Address::zero(),
+    /// };
+    /// manager
+    ///     .add_bridge("l1_to_l2", "l1", "l2", params, |log| {
+    ///         Some(BridgeMessage {
+    ///             to: log.address,
+    ///             data: log.data.clone(),
+    ///         })
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn add_bridge<S: Into<String> + Clone, F>(
+        &mut self,
+        bridge_label: S,
+        source_label: S,
+        destination_label: S,
+        params: BridgeParameters,
+        relay: F,
+    ) -> Result<(), ManagerError>
+    where
+        F: Fn(&Log) -> Option<BridgeMessage> + Send + 'static,
+    {
+        let bridge_label: String = bridge_label.into();
+        if self.bridges.contains_key(&bridge_label) {
+            return Err(ManagerError::BridgeAlreadyExists(bridge_label));
+        }
+        let mut environments = vec![];
+        for label in [source_label.into(), destination_label.into()] {
+            match self.environments.get(&label) {
+                Some(environment) => {
+                    if environment.state.load(std::sync::atomic::Ordering::SeqCst) == State::Stopped
+                    {
+                        return Err(ManagerError::EnvironmentStopped(label));
+                    }
+                    environments.push(environment);
+                }
+                None => return Err(ManagerError::EnvironmentDoesNotExist(label)),
+            }
+        }
+        let bridge = Bridge::new(environments[0], environments[1], params, Box::new(relay))
+            .map_err(ManagerError::Bridge)?;
+        info!("Added bridge labeled {}", bridge_label);
+        self.bridges.insert(bridge_label, bridge);
+        Ok(())
+    }
+
+    /// Adds a new recorder that streams the events emitted in an environment
+    /// to a set of sinks.
+    ///
+    /// Every log emitted in the environment that matches the `filter` is
+    /// decoded with the events of the `abi`, typically the ABI of a binding
+    /// such as `LIQUIDEXCHANGE_ABI`, and written to each sink. Logs that are
+    /// not an event of the `abi` are skipped. The sinks are flushed when the
+    /// environment is stopped via [`Manager::stop_environment`].
+    ///
+    /// # Parameters
+    ///
+    /// - `recorder_label`: The label (identifier) to be used for the recorder.
+    /// - `environment_label`: The label of the environment whose events are
+    ///   recorded.
+    /// - `filter`: The filter that logs must match in order to be recorded.
+    /// - `abi`: The ABI whose events the logs are decoded with.
+    /// - `sinks`: The destinations of the records.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The recorder was successfully added.
+    /// - `Err(ManagerError::RecorderAlreadyExists)`: A recorder with the
+    ///   specified label already exists.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::EnvironmentStopped)`: The environment has already
+    ///   been stopped.
+    /// - `Err(ManagerError::Recorder)`: The recorder could not subscribe to the
+    ///   events of the environment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{
+    ///     bindings::liquid_exchange::LIQUIDEXCHANGE_ABI, environment::EnvironmentParameters,
+    ///     manager::Manager, recorder::JsonLinesSink,
+    /// };
+    /// use ethers::types::Filter;
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// let path = std::env::temp_dir().join("example_env_events.jsonl");
+    /// let sink = JsonLinesSink::new(path).unwrap();
+    /// manager
+    ///     .add_recorder(
+    ///         "exchange_events",
+    ///         "example_env",
+    ///         Filter::new(),
+    ///         LIQUIDEXCHANGE_ABI.clone(),
+    ///         vec![Box::new(sink)],
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn add_recorder<S: Into<String> + Clone>(
+        &mut self,
+        recorder_label: S,
+        environment_label: S,
+        filter: Filter,
+        abi: Abi,
+        sinks: Vec<Box<dyn Sink>>,
+    ) -> Result<(), ManagerError> {
+        let recorder_label: String = recorder_label.into();
+        if self.recorders.contains_key(&recorder_label) {
+            return Err(ManagerError::RecorderAlreadyExists(recorder_label));
+        }
+        let environment_label: String = environment_label.into();
+        let environment = match self.environments.get(&environment_label) {
+            Some(environment) => environment,
+            None => return Err(ManagerError::EnvironmentDoesNotExist(environment_label)),
+        };
+        if environment.state.load(std::sync::atomic::Ordering::SeqCst) == State::Stopped {
+            return Err(ManagerError::EnvironmentStopped(environment_label));
+        }
+        let recorder =
+            Recorder::new(environment, filter, abi, sinks).map_err(ManagerError::Recorder)?;
+        info!("Added recorder labeled {}", recorder_label);
+        self.recorders.insert(recorder_label, recorder);
+        Ok(())
+    }
+
+    /// Attaches an [`Agent`] to an environment and starts running it on a
+    /// separate thread.
+    ///
+    /// The agent gets its own
+    /// [`RevmMiddleware`] whose wallet
+    /// is seeded by the agent's label, so the same label always
+    /// transacts from the same address. The agent is shut down when the
+    /// environment is stopped via [`Manager::stop_environment`].
+    ///
+    /// # Parameters
+    ///
+    /// - `agent_label`: The label (identifier) to be used for the agent.
+    /// - `environment_label`: The label of the environment to attach the agent
+    ///   to.
+    /// - `agent`: The agent to run.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The agent was successfully attached.
+    /// - `Err(ManagerError::AgentAlreadyExists)`: An agent with the specified
+    ///   label already exists.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::EnvironmentStopped)`: The environment has already
+    ///   been stopped.
+    /// - `Err(ManagerError::Agent)`: The agent could not subscribe to the
+    ///   events of the environment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{agent::Agent, environment::EnvironmentParameters, manager::Manager};
+    ///
+    /// struct Idle;
+    /// impl Agent for Idle {}
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// manager.start_environment("example_env").unwrap();
+    /// manager.add_agent("idle", "example_env", Idle).unwrap();
+    /// manager.stop_environment("example_env").unwrap();
+    /// ```
+    pub fn add_agent<S: Into<String> + Clone, A: Agent + 'static>(
+        &mut self,
+        agent_label: S,
+        environment_label: S,
+        agent: A,
+    ) -> Result<(), ManagerError> {
+        let agent_label: String = agent_label.into();
+        if self.agents.contains_key(&agent_label) {
+            return Err(ManagerError::AgentAlreadyExists(agent_label));
+        }
+        let environment_label: String = environment_label.into();
+        let environment = match self.environments.get(&environment_label) {
+            Some(environment) => environment,
+            None => return Err(ManagerError::EnvironmentDoesNotExist(environment_label)),
+        };
+        if environment.state.load(std::sync::atomic::Ordering::SeqCst) == State::Stopped {
+            return Err(ManagerError::EnvironmentStopped(environment_label));
+        }
+        let runtime = AgentRuntime::new(agent_label.clone(), environment, agent)
+            .map_err(ManagerError::Agent)?;
+        info!(
+            "Added agent labeled {} to environment labeled {}",
+            agent_label, environment_label
+        );
+        self.agents.insert(agent_label, runtime);
+        Ok(())
+    }
+
+    /// Adds a new observer that makes a read-only call at the end of every
+    /// `every` blocks of an environment.
+    ///
+    /// The call is made by the environment itself against the committed state
+    /// of each block whose number is a multiple of `every`, so it does not
+    /// count towards the size of the block. Its decoded return values are kept
+    /// as a time series that can be retrieved via [`Manager::observations`]
+    /// or written to a file via [`Manager::export_observations`].
+    ///
+    /// # Parameters
+    ///
+    /// - `observer_label`: The label (identifier) to be used for the observer.
+    /// - `environment_label`: The label of the environment to observe.
+    /// - `call`: The call to make, e.g., `liquid_exchange.price()`.
+    /// - `every`: The amount of blocks between two calls.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The observer was successfully added.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::ObserverAlreadyExists)`: An observer with the
+    ///   specified label already exists in the environment.
+    /// - `Err(ManagerError::InvalidObserver)`: The call has no recipient or
+    ///   `every` is zero.
+    pub fn add_observer<S: Into<String> + Clone, M, D: Detokenize>(
+        &mut self,
+        observer_label: S,
+        environment_label: S,
+        call: ContractCall<M, D>,
+        every: u64,
+    ) -> Result<(), ManagerError> {
+        let observer_label: String = observer_label.into();
+        let environment_label: String = environment_label.into();
+        let environment = self
+            .environments
+            .get(&environment_label)
+            .ok_or_else(|| ManagerError::EnvironmentDoesNotExist(environment_label.clone()))?;
+        if every == 0 {
+            return Err(ManagerError::InvalidObserver(
+                "observations must be made at least every block".to_string(),
+            ));
+        }
+        let to = call.tx.to_addr().copied().ok_or_else(|| {
+            ManagerError::InvalidObserver("the call has no recipient".to_string())
+        })?;
+        let observer = Observer {
+            to,
+            function: call.function.clone(),
+            data: call.tx.data().cloned().unwrap_or_default(),
+            every,
+            observations: vec![],
+        };
+        let mut observers = environment
+            .observers
+            .lock()
+            .map_err(|e| ManagerError::InvalidObserver(e.to_string()))?;
+        if observers.contains_key(&observer_label) {
+            return Err(ManagerError::ObserverAlreadyExists(observer_label));
+        }
+        info!(
+            "Added observer labeled {} to environment labeled {}",
+            observer_label, environment_label
+        );
+        observers.insert(observer_label, observer);
+        Ok(())
+    }
+
+    /// Returns the [`Observation`]s made so far by an observer of the
+    /// specified environment.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Vec<Observation>)`: The observations in order of their block.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::ObserverDoesNotExist)`: No observer with the
+    ///   specified label exists in the environment.
+    pub fn observations<S: Into<String>>(
+        &self,
+        environment_label: S,
+        observer_label: S,
+    ) -> Result<Vec<Observation>, ManagerError> {
+        let environment_label: String = environment_label.into();
+        let observer_label: String = observer_label.into();
+        let environment = self
+            .environments
+            .get(&environment_label)
+            .ok_or(ManagerError::EnvironmentDoesNotExist(environment_label))?;
+        let observers = environment
+            .observers
+            .lock()
+            .map_err(|e| ManagerError::Export(e.to_string()))?;
+        observers
+            .get(&observer_label)
+            .map(|observer| observer.observations.clone())
+            .ok_or(ManagerError::ObserverDoesNotExist(observer_label))
+    }
+
+    /// Writes the [`Observation`]s of every observer of the specified
+    /// environment to a CSV file with the columns
+    /// `observer,block_number,block_timestamp,values`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The observations were written.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::Export)`: The file could not be written.
+    pub fn export_observations<S: Into<String>, P: AsRef<Path>>(
+        &self,
+        environment_label: S,
+        path: P,
+    ) -> Result<(), ManagerError> {
+        let environment_label: String = environment_label.into();
+        let environment = self
+            .environments
+            .get(&environment_label)
+            .ok_or(ManagerError::EnvironmentDoesNotExist(environment_label))?;
+        let observers = environment
+            .observers
+            .lock()
+            .map_err(|e| ManagerError::Export(e.to_string()))?;
+        observer::write_csv(path, &observers).map_err(|e| ManagerError::Export(e.to_string()))
+    }
+
+    /// Takes a snapshot of the [`EnvironmentMetrics`] of the specified
+    /// environment.
+    ///
+    /// # Parameters
+    ///
+    /// - `environment_label`: The label (identifier) of the environment whose
+    ///   metrics are returned.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(EnvironmentMetrics)`: The metrics of the environment at the time
+    ///   of the call.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// let metrics = manager.metrics("example_env").unwrap();
+    /// assert_eq!(metrics.transactions, 0);
+    /// ```
+    pub fn metrics<S: Into<String>>(
+        &self,
+        environment_label: S,
+    ) -> Result<EnvironmentMetrics, ManagerError> {
+        let environment_label = environment_label.into();
+        match self.environments.get(&environment_label) {
+            Some(environment) => Ok(environment
+                .metrics
+                .lock()
+                .map(|metrics| metrics.clone())
+                .unwrap_or_default()),
+            None => Err(ManagerError::EnvironmentDoesNotExist(environment_label)),
+        }
+    }
+
+    /// Serves the metrics of every environment of the manager, including the
+    /// ones added later on, in the Prometheus text format.
+    ///
+    /// Every HTTP request made to the given address is answered with the
+    /// current metrics on a separate thread that runs until
+    /// [`Manager::stop_metrics`] is called or the manager is dropped.
+    ///
+    /// # Parameters
+    ///
+    /// - `address`: The local address to listen on. Use port `0` to let the
+    ///   operating system pick a free port.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(SocketAddr)`: The address the metrics are served on.
+    /// - `Err(ManagerError::MetricsServer)`: The metrics are already being
+    ///   served or the address could not be bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::manager::Manager;
+    ///
+    /// let mut manager = Manager::new();
+    /// let address = manager.serve_metrics("127.0.0.1:0").unwrap();
+    /// println!("Metrics are served on http://{}/metrics", address);
+    /// manager.stop_metrics().unwrap();
+    /// ```
+    pub fn serve_metrics<A: ToSocketAddrs>(
+        &mut self,
+        address: A,
+    ) -> Result<SocketAddr, ManagerError> {
+        if self.metrics_server.is_some() {
+            return Err(ManagerError::MetricsServer(
+                "the metrics are already being served".to_string(),
+            ));
+        }
+        let listener =
+            TcpListener::bind(address).map_err(|e| ManagerError::MetricsServer(e.to_string()))?;
+        let local_address = listener
+            .local_addr()
+            .map_err(|e| ManagerError::MetricsServer(e.to_string()))?;
+        let server = metrics::serve(listener, Arc::clone(&self.metrics_registry))
+            .map_err(|e| ManagerError::MetricsServer(e.to_string()))?;
+        self.metrics_server = Some(server);
+        info!("Serving metrics on {}", local_address);
+        Ok(local_address)
+    }
+
+    /// Stops serving the metrics started by [`Manager::serve_metrics`] and
+    /// waits for the thread that served them to finish. Does nothing if the
+    /// metrics are not being served.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The metrics are no longer served.
+    /// - `Err(ManagerError::ThreadPanic)`: The thread serving the metrics has
+    ///   panicked.
+    pub fn stop_metrics(&mut self) -> Result<(), ManagerError> {
+        if let Some(mut server) = self.metrics_server.take() {
+            server.stop().map_err(|_| ManagerError::ThreadPanic)?;
+            info!("Stopped serving metrics");
+        }
+        Ok(())
+    }
+
+    /// Serves an environment over JSON-RPC so that tools such as `cast`,
+    /// web3.py or frontends can talk to it like a local node. See the
+    /// [`server`](crate::server) module for the supported methods.
+    ///
+    /// Requests are taken both as HTTP `POST`s and as messages over a
+    /// WebSocket on the given address and are answered on separate threads
+    /// that run until [`Manager::stop_rpc`] is called or the manager is
+    /// dropped. They are answered once the environment is running.
+    ///
+    /// # Parameters
+    ///
+    /// - `environment_label`: The label of the environment to serve.
+    /// - `address`: The local address to listen on. Use port `0` to let the
+    ///   operating system pick a free port.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(SocketAddr)`: The address the environment is served on.
+    /// - `Err(ManagerError::EnvironmentDoesNotExist)`: No environment with the
+    ///   specified label exists.
+    /// - `Err(ManagerError::RpcServer)`: The environment is already being
+    ///   served or the address could not be bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+    ///
+    /// let mut manager = Manager::new();
+    /// let params = EnvironmentParameters {
+    ///     block_rate: 1.0,
+    ///     seed: 1,
+    /// };
+    /// manager.add_environment("example_env", params).unwrap();
+    /// manager.start_environment("example_env").unwrap();
+    /// let address = manager.serve_rpc("example_env", "127.0.0.1:0").unwrap();
+    /// println!("Serving JSON-RPC on http://{}", address);
+    /// manager.stop_rpc("example_env").unwrap();
+    /// ```
+    pub fn serve_rpc<S: Into<String>, A: ToSocketAddrs>(
+        &mut self,
+        environment_label: S,
+        address: A,
+    ) -> Result<SocketAddr, ManagerError> {
+        let environment_label = environment_label.into();
+        let environment = self
+            .environments
+            .get(&environment_label)
+            .ok_or_else(|| ManagerError::EnvironmentDoesNotExist(environment_label.clone()))?;
+        if self.rpc_servers.contains_key(&environment_label) {
+            return Err(ManagerError::RpcServer(format!(
+                "the environment labeled {} is already being served",
+                environment_label
+            )));
+        }
+        let listener =
+            TcpListener::bind(address).map_err(|e| ManagerError::RpcServer(e.to_string()))?;
+        let local_address = listener
+            .local_addr()
+            .map_err(|e| ManagerError::RpcServer(e.to_string()))?;
+        // Accept without blocking so that the server notices when it is stopped
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| ManagerError::RpcServer(e.to_string()))?;
+        let server = server::serve(listener, RevmMiddleware::new(environment, None))
+            .map_err(|e| ManagerError::RpcServer(e.to_string()))?;
+        self.rpc_servers.insert(environment_label.clone(), server);
+        info!(
+            "Serving the environment labeled {} over JSON-RPC on {}",
+            environment_label, local_address
+        );
+        Ok(local_address)
+    }
+
+    /// Stops serving an environment over JSON-RPC as started by
+    /// [`Manager::serve_rpc`], closing the open connections. Does nothing if
+    /// the environment is not being served.
+    ///
+    /// # Parameters
+    ///
+    /// - `environment_label`: The label of the environment to stop serving.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: The environment is no longer served.
+    /// - `Err(ManagerError::ThreadPanic)`: The thread accepting connections has
+    ///   panicked.
+    pub fn stop_rpc<S: Into<String>>(&mut self, environment_label: S) -> Result<(), ManagerError> {
+        let environment_label = environment_label.into();
+        if let Some(mut server) = self.rpc_servers.remove(&environment_label) {
+            server.stop().map_err(|_| ManagerError::ThreadPanic)?;
+            info!(
+                "Stopped serving the environment labeled {} over JSON-RPC",
+                environment_label
+            );
+        }
+        Ok(())
+    }
+
     /// Starts the specified environment.
     ///
     /// Attempts to transition the state of the given environment to `Running`.
@@ -282,7 +1424,8 @@ impl Manager {
     /// Stops the specified environment.
     ///
     /// This method attempts to transition the state of the given environment to
-    /// `Stopped`. Once stopped, an environment cannot be restarted.
+    /// `Stopped`. Once stopped, an environment cannot be restarted. Every
+    /// [`Recorder`] of the environment is flushed before this method returns.
     ///
     /// # Parameters
     ///
@@ -302,6 +1445,18 @@ impl Manager {
     ///   could not be found.
     /// - `Err(ManagerError::ThreadPanic)`: The [`Environment`]'s thead has
     ///   panicked!
+    /// - `Err(ManagerError::Recorder)`: A [`Recorder`] of the [`Environment`]
+    ///   failed to write its records.
+    /// - `Err(ManagerError::Bridge)`: A [`Bridge`] from or to the
+    ///   [`Environment`] failed to relay its events.
+    /// - `Err(ManagerError::Agent)`: An [`Agent`] of the [`Environment`]
+    ///   failed.
+    /// - `Err(ManagerError::Clock)`: The [`Environment`] could not leave its
+    ///   [`Clock`].
+    ///
+    /// The [`Environment`] is stopped and its agents, bridges and recorders are
+    /// wound down even if one of these steps fails, in which case the first
+    /// failure is returned.
     ///
     /// # Examples
     ///
@@ -330,40 +1485,70 @@ impl Manager {
                         environment_label.into(),
                     )),
                     State::Running => {
+                        // The environment leaves its clock first so that it no longer waits
+                        // on the others, which would hold up an agent whose transaction
+                        // finishes a block. Agents are then shut down while the environment
+                        // is still running so that they may still transact
+                        // Every step is run even if an earlier one fails so that the
+                        // recorders are still flushed, and the first failure is returned
+                        let mut result = match &environment.clock {
+                            Some(clock) => {
+                                clock.leave(&environment.label).map_err(ManagerError::Clock)
+                            }
+                            None => Ok(()),
+                        };
+                        keep_first_error(
+                            &mut result,
+                            stop_agents(&mut self.agents, &environment.label),
+                        );
                         environment
                             .state
                             .store(State::Stopped, std::sync::atomic::Ordering::SeqCst);
-                        match environment.handle.take() {
-                            Some(handle) => {
-                                if handle.join().is_err() {
-                                    return Err(ManagerError::ThreadPanic);
-                                }
-                            }
-                            None => return Err(ManagerError::NoHandleAvailable),
-                        }
+                        keep_first_error(&mut result, join_environment(environment));
+                        keep_first_error(
+                            &mut result,
+                            join_bridges(&mut self.bridges, &environment.label),
+                        );
+                        keep_first_error(
+                            &mut result,
+                            finish_recorders(&mut self.recorders, &environment.label),
+                        );
                         warn!(
                             "Stopped running environment labeled {}",
                             environment_label.into()
                         );
-                        Ok(())
+                        result
                     }
                     State::Paused => {
                         environment
                             .state
                             .store(State::Stopped, std::sync::atomic::Ordering::SeqCst);
-                        match environment.handle.take() {
-                            Some(handle) => {
-                                if handle.join().is_err() {
-                                    return Err(ManagerError::ThreadPanic);
-                                }
-                            }
-                            None => return Err(ManagerError::NoHandleAvailable),
+                        if let Some(clock) = &environment.clock {
+                            clock.release();
+                        }
+                        let mut result = join_environment(environment);
+                        if let Some(clock) = &environment.clock {
+                            keep_first_error(
+                                &mut result,
+                                clock.leave(&environment.label).map_err(ManagerError::Clock),
+                            );
                         }
+                        keep_first_error(
+                            &mut result,
+                            join_bridges(&mut self.bridges, &environment.label),
+                        );
+                        keep_first_error(
+                            &mut result,
+                            finish_recorders(&mut self.recorders, &environment.label),
+                        );
+                        // A paused environment does not answer its agents, so they are
+                        // left to wind down on their own
+                        detach_agents(&mut self.agents, &environment.label);
                         warn!(
                             "Stopped paused environment labeled {}",
                             environment_label.into()
                         );
-                        Ok(())
+                        result
                     }
                     State::Stopped => {
                         Err(ManagerError::EnvironmentStopped(environment_label.into()))
@@ -377,6 +1562,98 @@ impl Manager {
     }
 }
 
+/// Stores `next` in `result` if it is the first failure.
+fn keep_first_error(result: &mut Result<(), ManagerError>, next: Result<(), ManagerError>) {
+    if let Err(e) = next {
+        if result.is_ok() {
+            *result = Err(e);
+        }
+    }
+}
+
+/// Waits for the thread of a stopped [`Environment`] to finish.
+fn join_environment(environment: &mut Environment) -> Result<(), ManagerError> {
+    match environment.handle.take() {
+        Some(handle) => match handle.join() {
+            Ok(_) => Ok(()),
+            Err(_) => Err(ManagerError::ThreadPanic),
+        },
+        None => Err(ManagerError::NoHandleAvailable),
+    }
+}
+
+/// Shuts down every [`Agent`] of the environment with the given label and
+/// waits for them to finish. Every agent is stopped even if one of them fails,
+/// in which case the first failure is returned.
+fn stop_agents(
+    agents: &mut HashMap<String, AgentRuntime>,
+    environment_label: &str,
+) -> Result<(), ManagerError> {
+    let mut result = Ok(());
+    for (label, agent) in agents.iter_mut() {
+        if agent.environment != environment_label {
+            continue;
+        }
+        if let Err(e) = agent.stop() {
+            warn!("Agent labeled {} failed: {}", label, e);
+            keep_first_error(&mut result, Err(ManagerError::Agent(e)));
+        }
+    }
+    result
+}
+
+/// Tells every [`Agent`] of the environment with the given label to shut down
+/// without waiting for them.
+fn detach_agents(agents: &mut HashMap<String, AgentRuntime>, environment_label: &str) {
+    for agent in agents.values_mut() {
+        if agent.environment == environment_label {
+            agent.detach();
+        }
+    }
+}
+
+/// Waits for every [`Bridge`] from or to the environment with the given label
+/// to stop relaying, which they do once either of their environments is
+/// stopped. Every bridge is joined even if one of them fails, in which case the
+/// first failure is returned.
+fn join_bridges(
+    bridges: &mut HashMap<String, Bridge>,
+    environment_label: &str,
+) -> Result<(), ManagerError> {
+    let mut result = Ok(());
+    for (label, bridge) in bridges.iter_mut() {
+        if bridge.source != environment_label && bridge.destination != environment_label {
+            continue;
+        }
+        if let Err(e) = bridge.join() {
+            warn!("Bridge labeled {} failed: {}", label, e);
+            keep_first_error(&mut result, Err(ManagerError::Bridge(e)));
+        }
+    }
+    result
+}
+
+/// Waits for every [`Recorder`] of the environment with the given label to
+/// record the last of its events and flush its sinks. Every recorder is
+/// finished even if one of them fails, in which case the first failure is
+/// returned.
+fn finish_recorders(
+    recorders: &mut HashMap<String, Recorder>,
+    environment_label: &str,
+) -> Result<(), ManagerError> {
+    let mut result = Ok(());
+    for (label, recorder) in recorders.iter_mut() {
+        if recorder.environment != environment_label {
+            continue;
+        }
+        if let Err(e) = recorder.finish() {
+            warn!("Recorder labeled {} failed: {}", label, e);
+            keep_first_error(&mut result, Err(ManagerError::Recorder(e)));
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
 
@@ -386,5 +1663,9 @@ pub(crate) mod tests {
     fn new_manager() {
         let manager = Manager::new();
         assert!(manager.environments.is_empty());
+        assert!(manager.clocks.is_empty());
+        assert!(manager.bridges.is_empty());
+        assert!(manager.recorders.is_empty());
+        assert!(manager.agents.is_empty());
     }
 }
@@ -3,17 +3,30 @@
 //! and blockchain operations. This includes fixed-point conversions (WAD) and
 //! seeded random number generation with a Poisson distribution.
 //!
-//! The main feature is the [`SeededPoisson`] struct which provides seeded
-//! randomness for determining block sizes in a simulation. We also re-export
-//! the [`RustQuant::stochastics`] module so that the end user may retrieve
-//! stochastic processes of their choosing in a simulation they build.
+//! The main feature is the [`SeededBlockSizes`] struct which provides seeded
+//! randomness for determining block sizes in a simulation, drawn from a
+//! Poisson or any other [`BlockSizeDistribution`]. Seeded price paths can be
+//! sampled on a [`TimeGrid`] from a [`PriceProcess`], or from a
+//! [`CorrelatedGeometricBrownianMotion`] for several assets at once. We also
+//! re-export the [`RustQuant::stochastics`] module so that the end user may
+//! retrieve stochastic processes of their choosing in a simulation they build.
+//! Note that their own paths are not seeded, so a [`PriceProcess`] steps along
+//! their drift and diffusion with seeded randomness instead.
+//! Checked fixed-point numbers live in the [`fixed_point`](crate::fixed_point)
+//! module.
+//!
+//! The functions of the `ArbiterMath` contract are mirrored by [`cdf`],
+//! [`pdf`], [`ppf`], [`log`], [`sqrt`] and [`invariant`], which reproduce the
+//! integer arithmetic of solstat and solmate exactly, so that agents can
+//! evaluate them without calling the contract. Inputs for which the contract
+//! reverts return a [`MathError`] instead.
 //!
 //! # Examples
 //!
 //! ```
 //! # use arbiter_core::math::{SeededPoisson, float_to_wad, wad_to_float};
 //! // Using SeededPoisson
-//! let mut poisson = SeededPoisson::new(10.0, 12345);
+//! let mut poisson = SeededPoisson::new(10.0, 12345).unwrap();
 //! let random_value = poisson.sample();
 //! // Converting floating-point numbers to WAD representation and back
 //! let wad_val = float_to_wad(10.5);
@@ -23,12 +36,20 @@
 
 #![warn(missing_docs, unsafe_code)]
 
-use ethers::types::U256;
-use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+use ethers::types::{I256, U256};
+use rand::{
+    distributions::{Distribution, Uniform},
+    rngs::StdRng,
+    SeedableRng,
+};
+use rand_distr::{Gamma, StandardNormal};
 use statrs::distribution::Poisson;
+use thiserror::Error;
 /// Re-export [`RustQuant`](https://crates.io/crates/RustQuant) stochastics package module.
 pub use RustQuant::stochastics::*;
 
+use crate::fixed_point::u256_to_f64;
+
 /// Represents a Poisson distribution with a seeded random number generator.
 ///
 /// This is useful for generating deterministic random values from a Poisson
@@ -66,18 +87,29 @@ impl SeededPoisson {
     ///
     /// # Returns
     ///
-    /// A new [`SeededPoisson`] instance.
+    /// * `Ok(SeededPoisson)` if `rate_parameter` is positive and finite.
+    /// * `Err(MathError::InvalidDistribution)` otherwise.
     ///
     /// # Examples
     ///
     /// ```
     /// # use arbiter_core::math::SeededPoisson;
-    /// let poisson = SeededPoisson::new(10.0, 12345);
+    /// let poisson = SeededPoisson::new(10.0, 12345).unwrap();
+    /// assert!(SeededPoisson::new(-1.0, 12345).is_err());
     /// ```
-    pub fn new(rate_parameter: f64, seed: u64) -> Self {
-        let distribution = Poisson::new(rate_parameter).unwrap();
+    pub fn new(rate_parameter: f64, seed: u64) -> Result<Self, MathError> {
+        let invalid = || {
+            MathError::InvalidDistribution(format!(
+                "the rate {} must be positive and finite",
+                rate_parameter
+            ))
+        };
+        if !rate_parameter.is_finite() {
+            return Err(invalid());
+        }
+        let distribution = Poisson::new(rate_parameter).map_err(|_| invalid())?;
         let rng = StdRng::seed_from_u64(seed);
-        Self { distribution, rng }
+        Ok(Self { distribution, rng })
     }
 
     /// Samples a single value from the Poisson distribution using the seeded
@@ -91,7 +123,7 @@ impl SeededPoisson {
     ///
     /// ```
     /// # use arbiter_core::math::SeededPoisson;
-    /// let mut poisson = SeededPoisson::new(10.0, 12345);
+    /// let mut poisson = SeededPoisson::new(10.0, 12345).unwrap();
     /// let random_value = poisson.sample();
     /// ```
     pub fn sample(&mut self) -> usize {
@@ -106,9 +138,9 @@ mod tests {
 
     #[test]
     fn seeded_poisson() {
-        let mut test_dist_1 = SeededPoisson::new(10.0, 321);
-        let mut test_dist_2 = SeededPoisson::new(10000.0, 123);
-        let mut test_dist_3 = SeededPoisson::new(10000.0, 123);
+        let mut test_dist_1 = SeededPoisson::new(10.0, 321).unwrap();
+        let mut test_dist_2 = SeededPoisson::new(10000.0, 123).unwrap();
+        let mut test_dist_3 = SeededPoisson::new(10000.0, 123).unwrap();
 
         let result_1 = test_dist_1.sample();
         let result_2 = test_dist_1.sample();
@@ -124,6 +156,1426 @@ mod tests {
         assert_eq!(result_5, result_3);
         assert_eq!(result_6, result_4);
     }
+
+    #[test]
+    fn seeded_price_paths() {
+        let processes = [
+            PriceProcess::GeometricBrownianMotion {
+                drift: 0.1,
+                volatility: 0.5,
+            },
+            PriceProcess::OrnsteinUhlenbeck {
+                mean: 1000.0,
+                mean_reversion: 2.0,
+                volatility: 100.0,
+            },
+            PriceProcess::CoxIngersollRoss {
+                mean: 1000.0,
+                mean_reversion: 2.0,
+                volatility: 10.0,
+            },
+            PriceProcess::JumpDiffusion {
+                drift: 0.1,
+                volatility: 0.5,
+                jump_intensity: 50.0,
+                jump_mean: -0.05,
+                jump_volatility: 0.1,
+            },
+        ];
+        let grid = TimeGrid::new(0.0, 1.0, 100).unwrap();
+        for process in processes {
+            let path_1 = process.sample(1000.0, grid, 321).unwrap();
+            let path_2 = process.sample(1000.0, grid, 321).unwrap();
+            let path_3 = process.sample(1000.0, grid, 123).unwrap();
+            assert_eq!(path_1.prices.len(), 101);
+            assert_eq!(path_1.times.len(), 101);
+            assert_eq!(path_1.prices[0], 1000.0);
+            assert_eq!(path_1, path_2);
+            assert_ne!(path_1, path_3);
+            assert!(path_1.prices.iter().all(|price| price.is_finite()));
+            assert_eq!(path_1.wad_prices().unwrap()[0], float_to_wad(1000.0));
+        }
+
+        // Without any noise the processes follow their drift
+        let grid = TimeGrid::new(0.0, 1.0, 2).unwrap();
+        let path = PriceProcess::GeometricBrownianMotion {
+            drift: 1.0,
+            volatility: 0.0,
+        }
+        .sample(1.0, grid, 1)
+        .unwrap();
+        assert_eq!(path.times, vec![0.0, 0.5, 1.0]);
+        assert!((path.prices[2] - 1f64.exp()).abs() < 1e-12);
+        let grid = TimeGrid::new(0.0, 1.0, 10_000).unwrap();
+        let path = PriceProcess::OrnsteinUhlenbeck {
+            mean: 10.0,
+            mean_reversion: 1.0,
+            volatility: 0.0,
+        }
+        .sample(0.0, grid, 1)
+        .unwrap();
+        assert!((path.prices[10_000] - 10.0 * (1.0 - (-1f64).exp())).abs() < 1e-3);
+    }
+
+    #[test]
+    fn invalid_price_processes() {
+        let grid = TimeGrid::new(0.0, 1.0, 10).unwrap();
+        let jump_diffusion = |jump_intensity| PriceProcess::JumpDiffusion {
+            drift: 0.1,
+            volatility: 0.5,
+            jump_intensity,
+            jump_mean: -0.05,
+            jump_volatility: 0.1,
+        };
+        for jump_intensity in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                jump_diffusion(jump_intensity).sample(1000.0, grid, 1),
+                Err(MathError::InvalidProcess(_))
+            ));
+        }
+        assert!(jump_diffusion(0.0).sample(1000.0, grid, 1).is_ok());
+        let gbm = PriceProcess::GeometricBrownianMotion {
+            drift: 0.1,
+            volatility: -0.5,
+        };
+        assert!(gbm.sample(1000.0, grid, 1).is_err());
+        let cir = PriceProcess::CoxIngersollRoss {
+            mean: 1000.0,
+            mean_reversion: 2.0,
+            volatility: 10.0,
+        };
+        assert!(cir.sample(0.0, grid, 1).is_ok());
+        assert!(cir.sample(-1.0, grid, 1).is_err());
+        assert!(jump_diffusion(1.0).sample(0.0, grid, 1).is_err());
+
+        // Prices are not silently clamped when converted to WADs
+        let path = PricePath {
+            times: vec![0.0, 1.0],
+            prices: vec![1.0, -1.0],
+        };
+        assert_eq!(path.wad_prices(), Err(MathError::InvalidPrice(-1.0)));
+        assert!(prices_to_wad(&[f64::NAN]).is_err());
+
+        let correlation = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        assert!(matches!(
+            CorrelatedGeometricBrownianMotion::new(
+                vec![0.0, 0.0],
+                vec![0.2, -0.2],
+                correlation.clone()
+            ),
+            Err(MathError::InvalidProcess(_))
+        ));
+        let process =
+            CorrelatedGeometricBrownianMotion::new(vec![0.0, 0.0], vec![0.2, 0.2], correlation)
+                .unwrap();
+        assert!(matches!(
+            process.sample(&[1.0, 0.0], grid, 1),
+            Err(MathError::InvalidProcess(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_time_grid() {
+        assert!(TimeGrid::new(0.0, 0.0, 1).is_err());
+        assert!(TimeGrid::new(0.0, 1.0, 0).is_err());
+        assert!(TimeGrid::new(0.0, f64::NAN, 1).is_err());
+    }
+
+    #[test]
+    fn correlated_price_paths() {
+        let grid = TimeGrid::new(0.0, 1.0, 10_000).unwrap();
+        let process = CorrelatedGeometricBrownianMotion::new(
+            vec![0.0, 0.0],
+            vec![0.2, 0.2],
+            vec![vec![1.0, 0.9], vec![0.9, 1.0]],
+        )
+        .unwrap();
+        let paths = process.sample(&[1.0, 1.0], grid, 321).unwrap();
+        assert_eq!(paths, process.sample(&[1.0, 1.0], grid, 321).unwrap());
+
+        // The sample correlation of the log returns is close to the given one
+        let returns: Vec<Vec<f64>> = paths
+            .iter()
+            .map(|path| {
+                path.prices
+                    .windows(2)
+                    .map(|pair| (pair[1] / pair[0]).ln())
+                    .collect()
+            })
+            .collect();
+        let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len() as f64;
+        let (mean_0, mean_1) = (mean(&returns[0]), mean(&returns[1]));
+        let covariance = |xs: &[f64], mean_x: f64, ys: &[f64], mean_y: f64| {
+            xs.iter()
+                .zip(ys)
+                .map(|(x, y)| (x - mean_x) * (y - mean_y))
+                .sum::<f64>()
+        };
+        let correlation = covariance(&returns[0], mean_0, &returns[1], mean_1)
+            / (covariance(&returns[0], mean_0, &returns[0], mean_0)
+                * covariance(&returns[1], mean_1, &returns[1], mean_1))
+            .sqrt();
+        assert!((correlation - 0.9).abs() < 0.02);
+
+        assert!(matches!(
+            process.sample(&[1.0], grid, 321),
+            Err(MathError::DimensionMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert!(matches!(
+            CorrelatedGeometricBrownianMotion::new(
+                vec![0.0, 0.0],
+                vec![0.2, 0.2],
+                vec![vec![1.0, 1.5], vec![1.5, 1.0]],
+            ),
+            Err(MathError::InvalidCorrelation(_))
+        ));
+        assert!(matches!(
+            CorrelatedGeometricBrownianMotion::new(
+                vec![0.0, 0.0],
+                vec![0.2, 0.2],
+                vec![vec![1.0, 0.5], vec![0.4, 1.0]],
+            ),
+            Err(MathError::InvalidCorrelation(_))
+        ));
+    }
+
+    #[test]
+    fn seeded_block_sizes() {
+        let distributions = [
+            BlockSizeDistribution::Poisson { rate: 10.0 },
+            BlockSizeDistribution::Constant { size: 3 },
+            BlockSizeDistribution::Uniform { min: 2, max: 5 },
+            BlockSizeDistribution::NegativeBinomial {
+                mean: 10.0,
+                dispersion: 0.5,
+            },
+            BlockSizeDistribution::Empirical {
+                sizes: vec![1, 7, 7, 30],
+            },
+        ];
+        for distribution in distributions {
+            let mut block_sizes_1 = distribution.clone().seeded(321).unwrap();
+            let mut block_sizes_2 = distribution.clone().seeded(321).unwrap();
+            let sizes: Vec<usize> = (0..1000).map(|_| block_sizes_1.sample()).collect();
+            assert_eq!(
+                sizes,
+                (0..1000)
+                    .map(|_| block_sizes_2.sample())
+                    .collect::<Vec<usize>>()
+            );
+            match distribution {
+                BlockSizeDistribution::Constant { size } => {
+                    assert!(sizes.iter().all(|sample| *sample == size))
+                }
+                BlockSizeDistribution::Uniform { min, max } => {
+                    assert!(sizes.iter().all(|sample| (min..=max).contains(sample)));
+                    assert!(sizes.contains(&min) && sizes.contains(&max));
+                }
+                BlockSizeDistribution::NegativeBinomial { mean, dispersion } => {
+                    // The traffic is overdispersed compared to a Poisson distribution
+                    let sample_mean = sizes.iter().sum::<usize>() as f64 / sizes.len() as f64;
+                    let sample_variance = sizes
+                        .iter()
+                        .map(|sample| (*sample as f64 - sample_mean).powi(2))
+                        .sum::<f64>()
+                        / sizes.len() as f64;
+                    assert!((sample_mean - mean).abs() < 2.0);
+                    assert!(sample_variance > mean + mean.powi(2) / dispersion / 2.0);
+                }
+                BlockSizeDistribution::Empirical { sizes: observed } => {
+                    assert!(sizes.iter().all(|sample| observed.contains(sample)))
+                }
+                BlockSizeDistribution::Poisson { .. } => {}
+            }
+        }
+
+        // The Poisson distribution gives the same sizes as a `SeededPoisson`
+        let mut block_sizes = BlockSizeDistribution::Poisson { rate: 10.0 }
+            .seeded(321)
+            .unwrap();
+        let mut poisson = SeededPoisson::new(10.0, 321).unwrap();
+        assert_eq!(block_sizes.sample(), poisson.sample());
+        assert_eq!(block_sizes.sample(), poisson.sample());
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                SeededPoisson::new(rate, 321),
+                Err(MathError::InvalidDistribution(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_block_size_distributions() {
+        let distributions = [
+            BlockSizeDistribution::Poisson { rate: 0.0 },
+            BlockSizeDistribution::Poisson { rate: f64::NAN },
+            BlockSizeDistribution::Poisson {
+                rate: f64::INFINITY,
+            },
+            BlockSizeDistribution::Uniform { min: 5, max: 2 },
+            BlockSizeDistribution::NegativeBinomial {
+                mean: -1.0,
+                dispersion: 1.0,
+            },
+            BlockSizeDistribution::NegativeBinomial {
+                mean: 1.0,
+                dispersion: 0.0,
+            },
+            BlockSizeDistribution::NegativeBinomial {
+                mean: 1.0,
+                dispersion: f64::INFINITY,
+            },
+            BlockSizeDistribution::Empirical { sizes: vec![] },
+        ];
+        for distribution in distributions {
+            assert!(matches!(
+                distribution.seeded(1),
+                Err(MathError::InvalidDistribution(_))
+            ));
+        }
+    }
+}
+
+/// Errors that can occur when setting up the seeded processes and
+/// distributions of the [`math` module](self).
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum MathError {
+    /// [`MathError::InvalidTimeGrid`] is thrown when a [`TimeGrid`] does not
+    /// move forward in time.
+    #[error("invalid time grid: {0}")]
+    InvalidTimeGrid(String),
+
+    /// [`MathError::DimensionMismatch`] is thrown when the parameters of a
+    /// multi-asset process do not all describe the same amount of assets.
+    #[error("expected parameters for {expected} assets but got {actual}")]
+    DimensionMismatch {
+        /// The amount of assets of the process.
+        expected: usize,
+        /// The amount of assets the offending parameter describes.
+        actual: usize,
+    },
+
+    /// [`MathError::InvalidCorrelation`] is thrown when a correlation matrix
+    /// is not symmetric, does not have a unit diagonal or is not positive
+    /// definite.
+    #[error("invalid correlation matrix: {0}")]
+    InvalidCorrelation(String),
+
+    /// [`MathError::InvalidDistribution`] is thrown when the parameters of a
+    /// [`BlockSizeDistribution`] are invalid.
+    #[error("invalid distribution: {0}")]
+    InvalidDistribution(String),
+
+    /// [`MathError::InvalidProcess`] is thrown when the parameters of a
+    /// [`PriceProcess`] or [`CorrelatedGeometricBrownianMotion`] are invalid.
+    #[error("invalid process: {0}")]
+    InvalidProcess(String),
+
+    /// [`MathError::InvalidPrice`] is thrown when a price that is not positive
+    /// and finite is converted to a WAD.
+    #[error("invalid price: {0}")]
+    InvalidPrice(f64),
+
+    /// [`MathError::OutOfDomain`] is thrown when a mirror of `ArbiterMath`
+    /// is evaluated outside of its domain, where the contract reverts.
+    #[error("out of domain: {0}")]
+    OutOfDomain(String),
+
+    /// [`MathError::Overflow`] is thrown when an intermediate result of a
+    /// mirror of `ArbiterMath` overflows or divides by zero, where the
+    /// contract reverts.
+    #[error("arithmetic overflow or division by zero")]
+    Overflow,
+}
+
+/// A distribution of the amount of transactions in a block.
+///
+/// The distribution is only a description. Blocks sizes are drawn from the
+/// [`SeededBlockSizes`] given by [`BlockSizeDistribution::seeded`], which
+/// checks the parameters of the distribution.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockSizeDistribution {
+    /// Poisson distributed block sizes with the given mean, the default of an
+    /// [`Environment`](crate::environment::Environment).
+    Poisson {
+        /// The mean block size.
+        rate: f64,
+    },
+
+    /// Every block has the same size.
+    Constant {
+        /// The size of every block.
+        size: usize,
+    },
+
+    /// Block sizes drawn uniformly from `min` to `max`, both included.
+    Uniform {
+        /// The smallest block size.
+        min: usize,
+        /// The largest block size.
+        max: usize,
+    },
+
+    /// Negative binomially distributed block sizes, i.e., Poisson distributed
+    /// with a gamma distributed rate. This models bursty traffic whose
+    /// variance, `mean + mean^2 / dispersion`, exceeds its mean. The smaller
+    /// the `dispersion`, the burstier the traffic.
+    NegativeBinomial {
+        /// The mean block size.
+        mean: f64,
+        /// The dispersion of the block sizes.
+        dispersion: f64,
+    },
+
+    /// Block sizes resampled with replacement from a list of observed block
+    /// sizes, e.g., the transaction counts of historical blocks.
+    Empirical {
+        /// The observed block sizes.
+        sizes: Vec<usize>,
+    },
+}
+
+impl BlockSizeDistribution {
+    /// Checks the parameters of the distribution and seeds it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SeededBlockSizes)` if the parameters are valid.
+    /// * `Err(MathError::InvalidDistribution)` otherwise, e.g., for a negative
+    ///   rate or an empty list of sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arbiter_core::math::BlockSizeDistribution;
+    /// let mut block_sizes = BlockSizeDistribution::Uniform { min: 1, max: 10 }
+    ///     .seeded(12345)
+    ///     .unwrap();
+    /// let size = block_sizes.sample();
+    /// assert!((1..=10).contains(&size));
+    ///
+    /// assert!(BlockSizeDistribution::Poisson { rate: -1.0 }
+    ///     .seeded(12345)
+    ///     .is_err());
+    /// ```
+    pub fn seeded(self, seed: u64) -> Result<SeededBlockSizes, MathError> {
+        let invalid = |reason: String| Err(MathError::InvalidDistribution(reason));
+        let sampler = match &self {
+            BlockSizeDistribution::Poisson { rate } => {
+                Sampler::Poisson(SeededPoisson::new(*rate, seed)?.distribution)
+            }
+            BlockSizeDistribution::Constant { size } => Sampler::Constant(*size),
+            BlockSizeDistribution::Uniform { min, max } => {
+                if min > max {
+                    return invalid(format!("the minimum {} exceeds the maximum {}", min, max));
+                }
+                Sampler::Uniform(Uniform::new_inclusive(*min, *max))
+            }
+            BlockSizeDistribution::NegativeBinomial { mean, dispersion } => {
+                if !(mean.is_finite() && *mean > 0.0) {
+                    return invalid(format!("the mean {} must be positive", mean));
+                }
+                match Gamma::new(*dispersion, mean / dispersion) {
+                    Ok(gamma) if dispersion.is_finite() => Sampler::NegativeBinomial(gamma),
+                    _ => return invalid(format!("the dispersion {} must be positive", dispersion)),
+                }
+            }
+            BlockSizeDistribution::Empirical { sizes } => {
+                if sizes.is_empty() {
+                    return invalid("there must be at least one observed size".to_string());
+                }
+                Sampler::Empirical(sizes.clone(), Uniform::new(0, sizes.len()))
+            }
+        };
+        Ok(SeededBlockSizes {
+            distribution: self,
+            seed,
+            sampler,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+}
+
+/// The prepared distribution a [`SeededBlockSizes`] samples from.
+#[derive(Debug, Clone)]
+enum Sampler {
+    Poisson(Poisson),
+    Constant(usize),
+    Uniform(Uniform<usize>),
+    NegativeBinomial(Gamma<f64>),
+    Empirical(Vec<usize>, Uniform<usize>),
+}
+
+/// Block sizes drawn from a [`BlockSizeDistribution`] with a seeded random
+/// number generator, so that the same distribution and seed always give the
+/// same block sizes.
+#[derive(Debug, Clone)]
+pub struct SeededBlockSizes {
+    /// The distribution the block sizes are drawn from.
+    pub distribution: BlockSizeDistribution,
+
+    /// The seed of the random number generator.
+    pub seed: u64,
+
+    /// The prepared distribution.
+    sampler: Sampler,
+
+    /// Random number generator.
+    rng: StdRng,
+}
+
+impl SeededBlockSizes {
+    /// Draws the size of the next block.
+    pub fn sample(&mut self) -> usize {
+        match &self.sampler {
+            Sampler::Poisson(poisson) => poisson.sample(&mut self.rng) as usize,
+            Sampler::Constant(size) => *size,
+            Sampler::Uniform(uniform) => uniform.sample(&mut self.rng),
+            Sampler::NegativeBinomial(gamma) => {
+                let rate = gamma.sample(&mut self.rng);
+                match Poisson::new(rate) {
+                    Ok(poisson) => poisson.sample(&mut self.rng) as usize,
+                    // A rate that is not positive gives an empty block
+                    Err(_) => 0,
+                }
+            }
+            Sampler::Empirical(sizes, index) => sizes[index.sample(&mut self.rng)],
+        }
+    }
+}
+
+/// Evenly spaced points in time from `start` to `end` that a path is sampled
+/// at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeGrid {
+    /// The time of the first point of the grid.
+    pub start: f64,
+
+    /// The time of the last point of the grid.
+    pub end: f64,
+
+    /// The amount of steps between the first and the last point of the grid.
+    pub steps: usize,
+}
+
+impl TimeGrid {
+    /// Constructs a new [`TimeGrid`] of `steps` steps from `start` to `end`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(TimeGrid)` if `end` comes after `start` and there is at least one
+    ///   step.
+    /// * `Err(MathError::InvalidTimeGrid)` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arbiter_core::math::TimeGrid;
+    /// // Daily steps over a year
+    /// let grid = TimeGrid::new(0.0, 1.0, 365).unwrap();
+    /// assert_eq!(grid.times().len(), 366);
+    /// assert!(TimeGrid::new(1.0, 0.0, 365).is_err());
+    /// ```
+    pub fn new(start: f64, end: f64, steps: usize) -> Result<Self, MathError> {
+        if !(start.is_finite() && end.is_finite()) || end <= start {
+            return Err(MathError::InvalidTimeGrid(format!(
+                "the grid must move forward in time but goes from {} to {}",
+                start, end
+            )));
+        }
+        if steps == 0 {
+            return Err(MathError::InvalidTimeGrid(
+                "the grid must have at least one step".to_string(),
+            ));
+        }
+        Ok(Self { start, end, steps })
+    }
+
+    /// The amount of time between two points of the grid.
+    pub fn time_step(&self) -> f64 {
+        (self.end - self.start) / self.steps as f64
+    }
+
+    /// The `steps + 1` points in time of the grid.
+    pub fn times(&self) -> Vec<f64> {
+        let time_step = self.time_step();
+        (0..=self.steps)
+            .map(|step| self.start + step as f64 * time_step)
+            .collect()
+    }
+}
+
+/// A price path sampled at the points of a [`TimeGrid`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricePath {
+    /// The points in time the prices were sampled at.
+    pub times: Vec<f64>,
+
+    /// The prices of the path, one for each point in time.
+    pub prices: Vec<f64>,
+}
+
+impl PricePath {
+    /// Converts the prices of the path to WADs via [`price_to_wad`], e.g., to
+    /// push them to a contract.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<U256>)` with the WAD representation of every price.
+    /// * `Err(MathError::InvalidPrice)` if a price is not positive and finite.
+    pub fn wad_prices(&self) -> Result<Vec<U256>, MathError> {
+        prices_to_wad(&self.prices)
+    }
+}
+
+/// Converts a path of prices to WADs via [`price_to_wad`].
+///
+/// # Returns
+///
+/// * `Ok(Vec<U256>)` with the WAD representation of every price.
+/// * `Err(MathError::InvalidPrice)` for the first price that is not positive
+///   and finite.
+///
+/// # Examples
+///
+/// ```
+/// # use arbiter_core::math::{float_to_wad, prices_to_wad};
+/// let wads = prices_to_wad(&[1.5, 2.0]).unwrap();
+/// assert_eq!(wads, vec![float_to_wad(1.5), float_to_wad(2.0)]);
+/// assert!(prices_to_wad(&[1.5, -2.0]).is_err());
+/// ```
+pub fn prices_to_wad(prices: &[f64]) -> Result<Vec<U256>, MathError> {
+    prices.iter().map(|price| price_to_wad(*price)).collect()
+}
+
+/// A stochastic process that a price path can be sampled from.
+///
+/// Each process is stepped along the drift and diffusion of the corresponding
+/// process of the re-exported [`RustQuant::stochastics`] module, but with its
+/// own seeded random number generator, so the same process, time grid and seed
+/// always give the same path. Processes whose drift and diffusion are
+/// proportional to the price, i.e., [`PriceProcess::GeometricBrownianMotion`]
+/// and [`PriceProcess::JumpDiffusion`], are stepped in log price, which is
+/// exact. The others are stepped with the Euler-Maruyama scheme, so fine time
+/// grids give more accurate paths.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceProcess {
+    /// Geometric Brownian motion, i.e., `dS = drift * S dt + volatility * S
+    /// dW`. Prices are log-normally distributed and stay positive.
+    GeometricBrownianMotion {
+        /// The drift of the price per unit of time.
+        drift: f64,
+        /// The volatility of the price per square root unit of time.
+        volatility: f64,
+    },
+
+    /// Ornstein-Uhlenbeck process, i.e.,
+    /// `dS = mean_reversion * (mean - S) dt + volatility dW`. Prices revert to
+    /// `mean` and may become negative.
+    OrnsteinUhlenbeck {
+        /// The level prices revert to.
+        mean: f64,
+        /// The speed at which prices revert to `mean`.
+        mean_reversion: f64,
+        /// The volatility of the price per square root unit of time.
+        volatility: f64,
+    },
+
+    /// Cox-Ingersoll-Ross process, i.e.,
+    /// `dS = mean_reversion * (mean - S) dt + volatility * sqrt(S) dW`. Prices
+    /// revert to `mean` and stay non-negative, which is ensured by truncating
+    /// negative prices to zero.
+    CoxIngersollRoss {
+        /// The level prices revert to.
+        mean: f64,
+        /// The speed at which prices revert to `mean`.
+        mean_reversion: f64,
+        /// The volatility of the price per square root unit of time and price.
+        volatility: f64,
+    },
+
+    /// Merton jump diffusion, i.e., a [`PriceProcess::GeometricBrownianMotion`]
+    /// whose log price also jumps by normally distributed amounts at the times
+    /// of a Poisson process. The drift is compensated for the jumps so that
+    /// the expected return matches that of the diffusion alone.
+    JumpDiffusion {
+        /// The drift of the price per unit of time.
+        drift: f64,
+        /// The volatility of the price per square root unit of time.
+        volatility: f64,
+        /// The expected amount of jumps per unit of time.
+        jump_intensity: f64,
+        /// The mean of the jumps of the log price.
+        jump_mean: f64,
+        /// The standard deviation of the jumps of the log price.
+        jump_volatility: f64,
+    },
+}
+
+impl PriceProcess {
+    /// Samples a path starting at `initial_price` at the points of `grid`.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_price` - The price the path starts at.
+    /// * `grid` - The points in time to sample the path at.
+    /// * `seed` - The seed value for the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PricePath)` with a price for each point of `grid`, the first of
+    ///   which is `initial_price`.
+    /// * `Err(MathError::InvalidProcess)` if a parameter of the process is not
+    ///   finite, a volatility, speed of mean reversion or jump intensity is
+    ///   negative, or `initial_price` is not a valid starting point of the
+    ///   process, e.g., a non-positive price of a geometric Brownian motion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arbiter_core::math::{PriceProcess, TimeGrid};
+    /// let process = PriceProcess::GeometricBrownianMotion {
+    ///     drift: 0.05,
+    ///     volatility: 0.2,
+    /// };
+    /// let grid = TimeGrid::new(0.0, 10.0 / 365.0, 10).unwrap();
+    /// let path = process.sample(1000.0, grid, 12345).unwrap();
+    /// assert_eq!(path.prices.len(), 11);
+    /// assert_eq!(path, process.sample(1000.0, grid, 12345).unwrap());
+    /// assert!(process.sample(-1000.0, grid, 12345).is_err());
+    /// ```
+    pub fn sample(
+        &self,
+        initial_price: f64,
+        grid: TimeGrid,
+        seed: u64,
+    ) -> Result<PricePath, MathError> {
+        self.validate(initial_price)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let time_step = grid.time_step();
+        // The amount of jumps in a time step is only set up once
+        let jumps = match *self {
+            PriceProcess::JumpDiffusion { jump_intensity, .. }
+                if jump_intensity * time_step > 0.0 =>
+            {
+                Some(Poisson::new(jump_intensity * time_step).map_err(|e| {
+                    MathError::InvalidProcess(format!(
+                        "the jump intensity {} gives no jump distribution: {}",
+                        jump_intensity, e
+                    ))
+                })?)
+            }
+            _ => None,
+        };
+        let times = grid.times();
+        let mut prices = Vec::with_capacity(grid.steps + 1);
+        prices.push(initial_price);
+        let mut price = initial_price;
+        for time in &times[..grid.steps] {
+            price = self.step(price, *time, time_step, jumps.as_ref(), &mut rng);
+            prices.push(price);
+        }
+        Ok(PricePath { times, prices })
+    }
+
+    /// Checks the parameters of the process and that it can start at
+    /// `initial_price`.
+    fn validate(&self, initial_price: f64) -> Result<(), MathError> {
+        let (finite, non_negative, valid_start) = match *self {
+            PriceProcess::GeometricBrownianMotion { drift, volatility } => {
+                (vec![drift], vec![volatility], initial_price > 0.0)
+            }
+            PriceProcess::OrnsteinUhlenbeck {
+                mean,
+                mean_reversion,
+                volatility,
+            } => (vec![mean], vec![mean_reversion, volatility], true),
+            PriceProcess::CoxIngersollRoss {
+                mean,
+                mean_reversion,
+                volatility,
+            } => (
+                vec![mean],
+                vec![mean_reversion, volatility],
+                initial_price >= 0.0,
+            ),
+            PriceProcess::JumpDiffusion {
+                drift,
+                volatility,
+                jump_intensity,
+                jump_mean,
+                jump_volatility,
+            } => (
+                vec![drift, jump_mean],
+                vec![volatility, jump_intensity, jump_volatility],
+                initial_price > 0.0,
+            ),
+        };
+        let invalid = |reason: String| Err(MathError::InvalidProcess(reason));
+        if let Some(parameter) = finite.iter().find(|parameter| !parameter.is_finite()) {
+            return invalid(format!("the parameter {} must be finite", parameter));
+        }
+        if let Some(parameter) = non_negative
+            .iter()
+            .find(|parameter| !(parameter.is_finite() && **parameter >= 0.0))
+        {
+            return invalid(format!(
+                "the parameter {} must be finite and non-negative",
+                parameter
+            ));
+        }
+        if !(initial_price.is_finite() && valid_start) {
+            return invalid(format!(
+                "the process cannot start at the price {}",
+                initial_price
+            ));
+        }
+        Ok(())
+    }
+
+    /// Moves `price` forward from `time` by `time_step`, where `jumps` gives
+    /// the amount of jumps of a [`PriceProcess::JumpDiffusion`] in a time step.
+    fn step(
+        &self,
+        price: f64,
+        time: f64,
+        time_step: f64,
+        jumps: Option<&Poisson>,
+        rng: &mut StdRng,
+    ) -> f64 {
+        let shock: f64 = StandardNormal.sample(rng);
+        match *self {
+            PriceProcess::GeometricBrownianMotion { drift, volatility } => {
+                let process = GeometricBrownianMotion::new(drift, volatility);
+                log_euler_step(&process, price, time, time_step, shock)
+            }
+            PriceProcess::OrnsteinUhlenbeck {
+                mean,
+                mean_reversion,
+                volatility,
+            } => {
+                // RustQuant takes the mean, the volatility and then the speed of mean reversion
+                let process = OrnsteinUhlenbeck::new(mean, volatility, mean_reversion);
+                euler_step(&process, price, time, time_step, shock)
+            }
+            PriceProcess::CoxIngersollRoss {
+                mean,
+                mean_reversion,
+                volatility,
+            } => {
+                let process = CoxIngersollRoss::new(mean, volatility, mean_reversion);
+                euler_step(&process, price.max(0.0), time, time_step, shock).max(0.0)
+            }
+            PriceProcess::JumpDiffusion {
+                drift,
+                volatility,
+                jump_intensity,
+                jump_mean,
+                jump_volatility,
+            } => {
+                let compensation =
+                    jump_intensity * ((jump_mean + jump_volatility.powi(2) / 2.0).exp() - 1.0);
+                let jumps = jumps.map_or(0, |jumps| jumps.sample(rng) as u64);
+                let process = GeometricBrownianMotion::new(drift - compensation, volatility);
+                let mut log_jump = 0.0;
+                for _ in 0..jumps {
+                    let jump_shock: f64 = StandardNormal.sample(rng);
+                    log_jump += jump_mean + jump_volatility * jump_shock;
+                }
+                log_euler_step(&process, price, time, time_step, shock) * log_jump.exp()
+            }
+        }
+    }
+}
+
+/// Steps a [`StochasticProcess`] from `time` by `time_step` with the
+/// Euler-Maruyama scheme given a standard normal `shock`.
+fn euler_step<P: StochasticProcess>(
+    process: &P,
+    value: f64,
+    time: f64,
+    time_step: f64,
+    shock: f64,
+) -> f64 {
+    value
+        + process.drift(value, time) * time_step
+        + process.diffusion(value, time) * time_step.sqrt() * shock
+}
+
+/// Steps a [`StochasticProcess`] whose drift and diffusion are proportional to
+/// its value from `time` by `time_step` in log space given a standard normal
+/// `shock`, which is exact for a geometric Brownian motion.
+fn log_euler_step<P: StochasticProcess>(
+    process: &P,
+    value: f64,
+    time: f64,
+    time_step: f64,
+    shock: f64,
+) -> f64 {
+    if value == 0.0 {
+        return 0.0;
+    }
+    let drift = process.drift(value, time) / value;
+    let volatility = process.diffusion(value, time) / value;
+    value
+        * ((drift - volatility.powi(2) / 2.0) * time_step + volatility * time_step.sqrt() * shock)
+            .exp()
+}
+
+/// Geometric Brownian motions of several assets whose shocks are correlated.
+///
+/// Each asset is stepped along a [`GeometricBrownianMotion`] of the re-exported
+/// [`RustQuant::stochastics`] module. Independent standard normal shocks are
+/// correlated through the Cholesky factor of the correlation matrix, which is
+/// computed once when the process is constructed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelatedGeometricBrownianMotion {
+    /// The drift of each asset per unit of time.
+    pub drifts: Vec<f64>,
+
+    /// The volatility of each asset per square root unit of time.
+    pub volatilities: Vec<f64>,
+
+    /// The lower triangular Cholesky factor of the correlation matrix.
+    cholesky: Vec<Vec<f64>>,
+}
+
+impl CorrelatedGeometricBrownianMotion {
+    /// Constructs a new [`CorrelatedGeometricBrownianMotion`] of
+    /// `drifts.len()` assets.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CorrelatedGeometricBrownianMotion)` if every parameter describes
+    ///   the same amount of assets and `correlation` is a valid correlation
+    ///   matrix.
+    /// * `Err(MathError::DimensionMismatch)` if the parameters describe
+    ///   different amounts of assets.
+    /// * `Err(MathError::InvalidProcess)` if a drift is not finite or a
+    ///   volatility is negative or not finite.
+    /// * `Err(MathError::InvalidCorrelation)` if `correlation` is not
+    ///   symmetric, does not have a unit diagonal or is not positive definite.
+    pub fn new(
+        drifts: Vec<f64>,
+        volatilities: Vec<f64>,
+        correlation: Vec<Vec<f64>>,
+    ) -> Result<Self, MathError> {
+        let assets = drifts.len();
+        for actual in std::iter::once(volatilities.len())
+            .chain(std::iter::once(correlation.len()))
+            .chain(correlation.iter().map(Vec::len))
+        {
+            if actual != assets {
+                return Err(MathError::DimensionMismatch {
+                    expected: assets,
+                    actual,
+                });
+            }
+        }
+        if let Some(drift) = drifts.iter().find(|drift| !drift.is_finite()) {
+            return Err(MathError::InvalidProcess(format!(
+                "the drift {} must be finite",
+                drift
+            )));
+        }
+        if let Some(volatility) = volatilities
+            .iter()
+            .find(|volatility| !(volatility.is_finite() && **volatility >= 0.0))
+        {
+            return Err(MathError::InvalidProcess(format!(
+                "the volatility {} must be non-negative and finite",
+                volatility
+            )));
+        }
+        let cholesky = cholesky(&correlation)?;
+        Ok(Self {
+            drifts,
+            volatilities,
+            cholesky,
+        })
+    }
+
+    /// Samples a path for each asset starting at its initial price at the
+    /// points of `grid`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PricePath>)` with the path of each asset in order.
+    /// * `Err(MathError::DimensionMismatch)` if there is not exactly one
+    ///   initial price per asset.
+    /// * `Err(MathError::InvalidProcess)` if an initial price is not positive
+    ///   and finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arbiter_core::math::{CorrelatedGeometricBrownianMotion, TimeGrid};
+    /// let process = CorrelatedGeometricBrownianMotion::new(
+    ///     vec![0.05, 0.1],
+    ///     vec![0.2, 0.5],
+    ///     vec![vec![1.0, 0.8], vec![0.8, 1.0]],
+    /// )
+    /// .unwrap();
+    /// let grid = TimeGrid::new(0.0, 1.0, 365).unwrap();
+    /// let paths = process.sample(&[1.0, 1000.0], grid, 12345).unwrap();
+    /// assert_eq!(paths.len(), 2);
+    /// ```
+    pub fn sample(
+        &self,
+        initial_prices: &[f64],
+        grid: TimeGrid,
+        seed: u64,
+    ) -> Result<Vec<PricePath>, MathError> {
+        let assets = self.drifts.len();
+        if initial_prices.len() != assets {
+            return Err(MathError::DimensionMismatch {
+                expected: assets,
+                actual: initial_prices.len(),
+            });
+        }
+        if let Some(price) = initial_prices
+            .iter()
+            .find(|price| !(price.is_finite() && **price > 0.0))
+        {
+            return Err(MathError::InvalidProcess(format!(
+                "the initial price {} must be positive and finite",
+                price
+            )));
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let time_step = grid.time_step();
+        let times = grid.times();
+        let processes: Vec<GeometricBrownianMotion> = self
+            .drifts
+            .iter()
+            .zip(&self.volatilities)
+            .map(|(drift, volatility)| GeometricBrownianMotion::new(*drift, *volatility))
+            .collect();
+        let mut prices: Vec<Vec<f64>> = initial_prices.iter().map(|price| vec![*price]).collect();
+        let mut current = initial_prices.to_vec();
+        for time in &times[..grid.steps] {
+            let independent: Vec<f64> = (0..assets)
+                .map(|_| StandardNormal.sample(&mut rng))
+                .collect();
+            for (asset, price) in current.iter_mut().enumerate() {
+                let shock: f64 = self.cholesky[asset]
+                    .iter()
+                    .zip(&independent)
+                    .map(|(factor, shock)| factor * shock)
+                    .sum();
+                *price = log_euler_step(&processes[asset], *price, *time, time_step, shock);
+                prices[asset].push(*price);
+            }
+        }
+        Ok(prices
+            .into_iter()
+            .map(|prices| PricePath {
+                times: times.clone(),
+                prices,
+            })
+            .collect())
+    }
+}
+
+/// Computes the lower triangular Cholesky factor of a correlation matrix.
+fn cholesky(correlation: &[Vec<f64>]) -> Result<Vec<Vec<f64>>, MathError> {
+    let size = correlation.len();
+    for (i, row) in correlation.iter().enumerate() {
+        if (row[i] - 1.0).abs() > 1e-12 {
+            return Err(MathError::InvalidCorrelation(format!(
+                "the diagonal entry {} is {} instead of 1",
+                i, row[i]
+            )));
+        }
+        for (j, entry) in row.iter().enumerate().take(i) {
+            if (entry - correlation[j][i]).abs() > 1e-12 {
+                return Err(MathError::InvalidCorrelation(format!(
+                    "the entries ({}, {}) and ({}, {}) differ",
+                    i, j, j, i
+                )));
+            }
+        }
+    }
+    let mut factor = vec![vec![0.0; size]; size];
+    for i in 0..size {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| factor[i][k] * factor[j][k]).sum();
+            if i == j {
+                let pivot = correlation[i][i] - sum;
+                if pivot <= 0.0 {
+                    return Err(MathError::InvalidCorrelation(
+                        "the matrix is not positive definite".to_string(),
+                    ));
+                }
+                factor[i][j] = pivot.sqrt();
+            } else {
+                factor[i][j] = (correlation[i][j] - sum) / factor[j][j];
+            }
+        }
+    }
+    Ok(factor)
+}
+
+/// One in WAD precision, `1e18`.
+const ONE: i128 = 1_000_000_000_000_000_000;
+
+/// `sqrt(2)` in WAD precision as used by solstat's `Gaussian`.
+const SQRT2: i128 = 1_414_213_562_373_095_048;
+
+/// `sqrt(2 * pi)` in WAD precision as used by solstat's `Gaussian`.
+const SQRT_2PI: i128 = 2_506_628_274_631_000_502;
+
+/// The length of a year in seconds used by solstat's `Invariant`.
+const YEAR: u64 = 31_556_952;
+
+/// The coefficients of the Chebyshev fit of solstat's `erfc`.
+const ERFC_A: i128 = 1_265_512_230_000_000_000;
+const ERFC_B: i128 = 1_000_023_680_000_000_000;
+const ERFC_C: i128 = 374_091_960_000_000_000;
+const ERFC_D: i128 = 96_784_180_000_000_000;
+const ERFC_E: i128 = -186_288_060_000_000_000;
+const ERFC_F: i128 = 278_868_070_000_000_000;
+const ERFC_G: i128 = -1_135_203_980_000_000_000;
+const ERFC_H: i128 = 1_488_515_870_000_000_000;
+const ERFC_I: i128 = -822_152_230_000_000_000;
+const ERFC_J: i128 = 170_872_770_000_000_000;
+
+/// The coefficients of the initial guess and Newton steps of solstat's
+/// `ierfc`.
+const IERFC_A: i128 = -707_110_000_000_000_000;
+const IERFC_B: i128 = 2_307_530_000_000_000_000;
+const IERFC_C: i128 = 270_610_000_000_000_000;
+const IERFC_D: i128 = 992_290_000_000_000_000;
+const IERFC_E: i128 = 44_810_000_000_000_000;
+const IERFC_F: i128 = 1_128_379_167_095_512_570;
+
+/// The standard normal cumulative distribution function of a WAD, mirroring
+/// solstat's `Gaussian.cdf` and `ArbiterMath.cdf` down to the last wei.
+///
+/// # Examples
+///
+/// ```
+/// # use arbiter_core::math::cdf;
+/// # use ethers::types::I256;
+/// let one = I256::exp10(18);
+/// assert_eq!(cdf(one)?, I256::from(841_344_738_604_325_374_u64));
+/// # Ok::<(), arbiter_core::math::MathError>(())
+/// ```
+pub fn cdf(x: I256) -> Result<I256, MathError> {
+    let input = div(mul(x, int(ONE))?, int(SQRT2))?;
+    mul_div(int(ONE), erfc(neg(input)?)?, int(2 * ONE))
+}
+
+/// The standard normal probability density function of a WAD, mirroring
+/// solstat's `Gaussian.pdf` and `ArbiterMath.pdf`.
+pub fn pdf(x: I256) -> Result<I256, MathError> {
+    let exponent = div(mul(x, neg(x)?)?, int(2 * ONE))?;
+    div(mul(int(ONE), exp_wad(exponent)?)?, int(SQRT_2PI))
+}
+
+/// The inverse of the standard normal cumulative distribution function of a
+/// WAD, mirroring solstat's `Gaussian.ppf` and `ArbiterMath.ppf`.
+///
+/// # Errors
+///
+/// Fails with [`MathError::OutOfDomain`] unless `0 < x < 1e18`.
+pub fn ppf(x: I256) -> Result<I256, MathError> {
+    if x == int(ONE / 2) {
+        return Ok(I256::zero());
+    }
+    if x >= int(ONE) || x.is_zero() {
+        return Err(MathError::OutOfDomain(format!(
+            "the ppf of {x} is infinite"
+        )));
+    }
+    neg(mul_wad(int(SQRT2), ierfc(mul(x, int(2))?)?)?)
+}
+
+/// The natural logarithm of a WAD, mirroring solmate's `lnWad` and
+/// `ArbiterMath.log`.
+///
+/// # Errors
+///
+/// Fails with [`MathError::OutOfDomain`] unless `x` is positive.
+pub fn log(x: I256) -> Result<I256, MathError> {
+    if x <= I256::zero() {
+        return Err(MathError::OutOfDomain(format!(
+            "the logarithm of {x} is undefined"
+        )));
+    }
+    // Reduce the range of x to (1, 2) * 2**96 since ln(2^k * x) = k * ln(2) +
+    // ln(x)
+    let k = x.into_raw().bits() as i64 - 1 - 96;
+    let x = I256::from_raw((x.into_raw() << (159 - k) as usize) >> 159);
+
+    // Evaluate the (8, 8)-term rational approximation, leaving p in 2**192
+    // basis
+    let mut p = x + int(3_273_285_459_638_523_848_632_254_066_296);
+    p = (p * x).asr(96) + int(24_828_157_081_833_163_892_658_089_445_524);
+    p = (p * x).asr(96) + int(43_456_485_725_739_037_958_740_375_743_393);
+    p = (p * x).asr(96) - int(11_111_509_109_440_967_052_023_855_526_967);
+    p = (p * x).asr(96) - int(45_023_709_667_254_063_763_336_534_515_857);
+    p = (p * x).asr(96) - int(14_706_773_417_378_608_786_704_636_184_526);
+    p = p * x - int(795_164_235_651_350_426_258_249_787_498).wrapping_shl(96);
+    let mut q = x + int(5_573_035_233_440_673_466_300_451_813_936);
+    q = (q * x).asr(96) + int(71_694_874_799_317_883_764_090_561_454_958);
+    q = (q * x).asr(96) + int(283_447_036_172_924_575_727_196_451_306_956);
+    q = (q * x).asr(96) + int(401_686_690_394_027_663_651_624_208_769_553);
+    q = (q * x).asr(96) + int(204_048_457_590_392_012_362_485_061_816_622);
+    q = (q * x).asr(96) + int(31_853_899_698_501_571_402_653_359_427_138);
+    q = (q * x).asr(96) + int(909_429_971_244_387_300_277_376_558_375);
+
+    // Scale, add k * ln(2) and ln(2**96 / 1e18) and convert back to a WAD
+    let mut r = p / q;
+    r *= big("1677202110996718588342820967067443963516166");
+    r += big("16597577552685614221487285958193947469193820559219878177908093499208371")
+        * I256::from(k);
+    r += big("600920179829731861736702779321621459595472258049074101567377883020018308");
+    Ok(r.asr(174))
+}
+
+/// The exponential of a WAD, mirroring solmate's `expWad`.
+///
+/// # Errors
+///
+/// Fails with [`MathError::OutOfDomain`] when the result does not fit in an
+/// `int256`, i.e., from about `135.3e18` up.
+pub fn exp_wad(x: I256) -> Result<I256, MathError> {
+    if x <= int(-42_139_678_854_452_767_551) {
+        return Ok(I256::zero());
+    }
+    if x >= int(135_305_999_368_893_231_589) {
+        return Err(MathError::OutOfDomain(format!(
+            "the exponential of {x} overflows"
+        )));
+    }
+    // Convert to a 2**96 basis, i.e., multiply by 1e18 / 2**96 = 5**18 / 2**78
+    let x = x.wrapping_shl(78) / int(3_814_697_265_625);
+
+    // Factor out powers of two so that exp(x) = exp(x') * 2**k
+    let ln2 = int(54_916_777_467_707_473_351_141_471_128);
+    let k = (x.wrapping_shl(96) / ln2 + I256::one().wrapping_shl(95)).asr(96);
+    let x = x - k * ln2;
+
+    // Evaluate the (6, 7)-term rational approximation, leaving p in 2**192
+    // basis
+    let mut y = x + int(1_346_386_616_545_796_478_920_950_773_328);
+    y = (y * x).asr(96) + int(57_155_421_227_552_351_082_224_309_758_442);
+    let mut p = y + x - int(94_201_549_194_550_492_254_356_042_504_812);
+    p = (p * y).asr(96) + int(28_719_021_644_029_726_153_956_944_680_412_240);
+    p = p * x + int(4_385_272_521_454_847_904_659_076_985_693_276).wrapping_shl(96);
+    let mut q = x - int(2_855_989_394_907_223_263_936_484_059_900);
+    q = (q * x).asr(96) + int(50_020_603_652_535_783_019_961_831_881_945);
+    q = (q * x).asr(96) - int(533_845_033_583_426_703_283_633_433_725_380);
+    q = (q * x).asr(96) + int(3_604_857_256_930_695_427_073_651_918_091_429);
+    q = (q * x).asr(96) - int(14_423_608_567_350_463_180_887_372_962_807_573);
+    q = (q * x).asr(96) + int(26_449_188_498_355_588_339_934_803_723_976_023);
+
+    // Multiply by the scale factor, 2**k and 1e18 / 2**96 all at once
+    let r = (p / q).into_raw();
+    let (r, _) =
+        r.overflowing_mul(big("3822833074963236453042738258902158003155416615667").into_raw());
+    Ok(I256::from_raw(r >> (195 - k.low_i64()) as usize))
+}
+
+/// The integer square root, rounded down, mirroring solmate's `sqrt` and
+/// `ArbiterMath.sqrt`. The square root of a WAD is a number with 9 decimals.
+pub fn sqrt(x: U256) -> U256 {
+    x.integer_sqrt()
+}
+
+/// The invariant of an RMM-01 pool with the given reserves per unit of
+/// liquidity, strike, volatility and time to maturity in seconds, all WADs but
+/// the last, mirroring solstat's `Invariant.invariant` and
+/// `ArbiterMath.invariant`. The invariant is `reserve_y` minus the reserve of
+/// token Y the trading function expects for `reserve_x`.
+///
+/// # Errors
+///
+/// Fails with [`MathError::OutOfDomain`] when `reserve_x` is more than `1e18`.
+pub fn invariant(
+    reserve_y: U256,
+    reserve_x: U256,
+    strike: U256,
+    volatility: U256,
+    tau: U256,
+) -> Result<I256, MathError> {
+    let expected_y = trading_function_y(reserve_x, strike, volatility, tau)?;
+    Ok(I256::from_raw(reserve_y).wrapping_sub(expected_y))
+}
+
+/// The reserve of token Y of an RMM-01 pool given its reserve of token X,
+/// mirroring solstat's `Invariant.getY` for an invariant of zero.
+fn trading_function_y(
+    reserve_x: U256,
+    strike: U256,
+    volatility: U256,
+    tau: U256,
+) -> Result<I256, MathError> {
+    let one = U256::exp10(18);
+    if reserve_x > one {
+        return Err(MathError::OutOfDomain(format!(
+            "the reserve of token X {reserve_x} exceeds one"
+        )));
+    }
+    // The casts of solstat are unchecked, so values above `int256` wrap
+    let strike = I256::from_raw(strike);
+    if reserve_x == one {
+        return Ok(I256::zero());
+    }
+    if reserve_x.is_zero() {
+        return Ok(strike);
+    }
+    let input = sub(int(ONE), I256::from_raw(reserve_x))?;
+    if tau.is_zero() {
+        return mul_wad(strike, input);
+    }
+    let years = mul_div_down(tau, one, U256::from(YEAR))?;
+    let sqrt_years = sqrt(years)
+        .checked_mul(U256::exp10(9))
+        .ok_or(MathError::Overflow)?;
+    let std_dev = mul_div_down(sqrt_years, volatility, one)?;
+    let input = sub(ppf(input)?, I256::from_raw(std_dev))?;
+    mul_wad(strike, cdf(input)?)
+}
+
+/// The complementary error function of a WAD, mirroring solstat's
+/// `Gaussian.erfc`.
+fn erfc(x: I256) -> Result<I256, MathError> {
+    if x.is_zero() {
+        return Ok(int(ONE));
+    }
+    let bound = int(6_240_000_000_000_000_000);
+    if x >= bound {
+        return Ok(I256::zero());
+    }
+    if x <= neg(bound)? {
+        return Ok(int(2 * ONE));
+    }
+    let z = x.unsigned_abs();
+    let quotient = I256::from_raw(mul_div_down(z, U256::exp10(18), U256::exp10(18) * 2)?);
+    let t = div_wad(int(ONE), add(quotient, int(ONE))?)?;
+    let horner = |coefficient: i128, acc: I256| add(int(coefficient), mul_wad(t, acc)?);
+    let mut step = horner(ERFC_I, int(ERFC_J))?;
+    step = horner(ERFC_H, step)?;
+    step = horner(ERFC_G, step)?;
+    step = horner(ERFC_F, step)?;
+    step = horner(ERFC_E, step)?;
+    step = horner(ERFC_D, step)?;
+    step = horner(ERFC_C, step)?;
+    step = horner(ERFC_B, step)?;
+    step = mul_wad(t, step)?;
+    let z = I256::from_raw(z);
+    let k = add(sub(neg(mul_wad(z, z)?)?, int(ERFC_A))?, step)?;
+    let r = mul_wad(exp_wad(k)?, t)?;
+    if x.is_negative() {
+        sub(int(2 * ONE), r)
+    } else {
+        Ok(r)
+    }
+}
+
+/// The inverse of the complementary error function of a WAD, mirroring
+/// solstat's `Gaussian.ierfc`.
+fn ierfc(x: I256) -> Result<I256, MathError> {
+    let two = int(2 * ONE);
+    if x.is_negative() || x > two {
+        return Err(MathError::OutOfDomain(format!(
+            "the inverse erfc of {x} is undefined"
+        )));
+    }
+    if x.is_zero() || x == two {
+        return Err(MathError::OutOfDomain(format!(
+            "the inverse erfc of {x} is infinite"
+        )));
+    }
+    let xx = if x < int(ONE) { x } else { sub(two, x)? };
+    let half = div_wad(xx, two)?;
+    if half.is_zero() {
+        return Err(MathError::OutOfDomain(format!(
+            "the inverse erfc of {x} is infinite"
+        )));
+    }
+    let ln = mul_wad(log(half)?, neg(two)?)?;
+    let t = sqrt(ln.into_raw())
+        .checked_mul(U256::exp10(9))
+        .ok_or(MathError::Overflow)?;
+    let t = I256::from_raw(t);
+
+    // Initial guess followed by two Newton steps
+    let numerator = add(int(IERFC_B), mul_wad(t, int(IERFC_C))?)?;
+    let denominator = add(
+        int(ONE),
+        mul_wad(t, add(int(IERFC_D), mul_wad(t, int(IERFC_E))?)?)?,
+    )?;
+    let mut r = mul_wad(int(IERFC_A), sub(div_wad(numerator, denominator)?, t)?)?;
+    for _ in 0..2 {
+        let err = sub(erfc(r)?, xx)?;
+        let slope = sub(
+            mul_wad(int(IERFC_F), exp_wad(neg(mul_wad(r, r)?)?)?)?,
+            mul_wad(r, err)?,
+        )?;
+        r = add(r, div_wad(err, slope)?)?;
+    }
+    if x < int(ONE) {
+        Ok(r)
+    } else {
+        neg(r)
+    }
+}
+
+/// Converts a constant that fits in an `i128`.
+fn int(x: i128) -> I256 {
+    I256::from(x)
+}
+
+/// Converts a constant that does not fit in an `i128`.
+fn big(x: &str) -> I256 {
+    I256::from_dec_str(x).unwrap()
+}
+
+// Checked signed arithmetic reverting like Solidity's, i.e., with a
+// [`MathError::Overflow`] on overflow and division by zero.
+
+fn add(x: I256, y: I256) -> Result<I256, MathError> {
+    x.checked_add(y).ok_or(MathError::Overflow)
+}
+
+fn sub(x: I256, y: I256) -> Result<I256, MathError> {
+    x.checked_sub(y).ok_or(MathError::Overflow)
+}
+
+fn mul(x: I256, y: I256) -> Result<I256, MathError> {
+    x.checked_mul(y).ok_or(MathError::Overflow)
+}
+
+fn div(x: I256, y: I256) -> Result<I256, MathError> {
+    x.checked_div(y).ok_or(MathError::Overflow)
+}
+
+fn neg(x: I256) -> Result<I256, MathError> {
+    x.checked_neg().ok_or(MathError::Overflow)
+}
+
+fn mul_div(x: I256, y: I256, denominator: I256) -> Result<I256, MathError> {
+    div(mul(x, y)?, denominator)
+}
+
+fn mul_wad(x: I256, y: I256) -> Result<I256, MathError> {
+    mul_div(x, y, int(ONE))
+}
+
+fn div_wad(x: I256, y: I256) -> Result<I256, MathError> {
+    mul_div(x, int(ONE), y)
+}
+
+fn mul_div_down(x: U256, y: U256, denominator: U256) -> Result<U256, MathError> {
+    x.checked_mul(y)
+        .and_then(|product| product.checked_div(denominator))
+        .ok_or(MathError::Overflow)
 }
 
 /// Converts a floating-point number to a WAD fixed-point representation using
@@ -138,7 +1590,10 @@ mod tests {
 ///
 /// # Returns
 ///
-/// Returns the `U256` representation of the WAD fixed-point number.
+/// Returns the `U256` representation of the WAD fixed-point number. Negative
+/// and NaN inputs become zero and inputs above about `3.4e20` saturate, so use
+/// [`Wad::from_f64`](crate::fixed_point::Wad::from_f64) where those have to be
+/// caught.
 ///
 /// # Examples
 ///
@@ -150,6 +1605,33 @@ pub fn float_to_wad(x: f64) -> U256 {
     U256::from((x * 1e18) as u128)
 }
 
+/// Converts a price to a WAD fixed-point representation using `U256`.
+///
+/// The price is converted via [`float_to_wad`], but where that would turn a
+/// negative price into zero or saturate a large one, the price is rejected
+/// instead, and so are NaN, infinite and zero prices.
+///
+/// # Returns
+///
+/// * `Ok(U256)` with the WAD representation of the price.
+/// * `Err(MathError::InvalidPrice)` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// # use arbiter_core::math::{float_to_wad, price_to_wad};
+/// assert_eq!(price_to_wad(1.5).unwrap(), float_to_wad(1.5));
+/// assert!(price_to_wad(-1.5).is_err());
+/// assert!(price_to_wad(f64::NAN).is_err());
+/// assert!(price_to_wad(1e21).is_err());
+/// ```
+pub fn price_to_wad(price: f64) -> Result<U256, MathError> {
+    if !(price.is_finite() && price > 0.0 && price * 1e18 < u128::MAX as f64) {
+        return Err(MathError::InvalidPrice(price));
+    }
+    Ok(float_to_wad(price))
+}
+
 /// Converts a WAD fixed-point number, represented as `U256`, back to a
 /// floating-point number.
 ///
@@ -161,7 +1643,9 @@ pub fn float_to_wad(x: f64) -> U256 {
 ///
 /// # Returns
 ///
-/// Returns the floating-point representation of the number.
+/// Returns the floating-point representation of the number, which loses
+/// precision for very large values. See [`Wad`](crate::fixed_point::Wad) for
+/// exact arithmetic.
 ///
 /// # Examples
 ///
@@ -172,5 +1656,5 @@ pub fn float_to_wad(x: f64) -> U256 {
 /// assert_eq!(float_val, 1.23);
 /// ```
 pub fn wad_to_float(x: U256) -> f64 {
-    x.as_u128() as f64 / 1e18
+    u256_to_f64(x) / 1e18
 }
@@ -0,0 +1,494 @@
+//! The `metrics` module keeps track of what an [`Environment`] has been doing
+//! while running, e.g., how many transactions it has processed, how many of
+//! them reverted and how much gas they used.
+//!
+//! Every [`Environment`] updates its own [`EnvironmentMetrics`] as it processes
+//! transactions. A snapshot can be taken at any time via the [`Manager`], which
+//! can also serve the metrics of all of its environments in the Prometheus
+//! text format for dashboards.
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use ethers::types::Address;
+use log::warn;
+use revm::primitives::{ExecutionResult, B160};
+
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::{environment::Environment, manager::Manager};
+
+/// How long the metrics server waits on an idle connection for its request or
+/// for its response to be sent.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The largest request, in bytes, that is read by the metrics server.
+const MAX_REQUEST_SIZE: u64 = 8 * 1024;
+
+/// The upper bounds of the buckets used for the gas histograms.
+pub const GAS_BUCKETS: [u64; 10] = [
+    21_000, 50_000, 100_000, 250_000, 500_000, 1_000_000, 2_500_000, 5_000_000, 10_000_000,
+    30_000_000,
+];
+
+/// Alias for the metrics of every [`Environment`] of a [`Manager`] keyed by
+/// their labels.
+pub(crate) type MetricsRegistry = Arc<Mutex<BTreeMap<String, Arc<Mutex<EnvironmentMetrics>>>>>;
+
+/// A histogram of observed values with fixed buckets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Histogram {
+    /// The inclusive upper bounds of the buckets in increasing order.
+    pub bounds: Vec<u64>,
+
+    /// The amount of observations in each bucket. Holds one more entry than
+    /// `bounds` for the observations above the last bound.
+    pub counts: Vec<u64>,
+
+    /// The sum of all observations.
+    pub sum: u64,
+
+    /// The amount of observations.
+    pub count: u64,
+}
+
+impl Histogram {
+    /// Creates an empty [`Histogram`] with the given bucket bounds.
+    pub fn new(bounds: &[u64]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            counts: vec![0; bounds.len() + 1],
+            sum: 0,
+            count: 0,
+        }
+    }
+
+    /// Adds an observation to the [`Histogram`].
+    pub fn observe(&mut self, value: u64) {
+        let bucket = self.bounds.partition_point(|bound| *bound < value);
+        self.counts[bucket] += 1;
+        self.sum = self.sum.saturating_add(value);
+        self.count += 1;
+    }
+
+    /// The mean of all observations, if there are any.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum as f64 / self.count as f64)
+    }
+}
+
+/// Counters and histograms describing the activity of an [`Environment`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvironmentMetrics {
+    /// The amount of state changing transactions processed.
+    pub transactions: u64,
+
+    /// The amount of read only calls processed.
+    pub calls: u64,
+
+    /// The amount of transactions that reverted.
+    pub reverts: u64,
+
+    /// The amount of transactions that halted.
+    pub halts: u64,
+
+    /// The amount of blocks produced since the [`Environment`] started.
+    pub blocks: u64,
+
+    /// The amount of transactions and calls waiting to be processed the last
+    /// time one was received.
+    pub queue_depth: u64,
+
+    /// The largest `queue_depth` seen so far.
+    pub max_queue_depth: u64,
+
+    /// The gas used by each transaction.
+    pub gas_used_per_transaction: Histogram,
+
+    /// The gas used by all transactions of each finished block.
+    pub gas_used_per_block: Histogram,
+
+    /// The amount of transactions sent by each address.
+    pub transactions_per_sender: HashMap<Address, u64>,
+
+    /// The gas used so far in the current block.
+    current_block_gas: u64,
+}
+
+impl Default for EnvironmentMetrics {
+    fn default() -> Self {
+        Self {
+            transactions: 0,
+            calls: 0,
+            reverts: 0,
+            halts: 0,
+            blocks: 0,
+            queue_depth: 0,
+            max_queue_depth: 0,
+            gas_used_per_transaction: Histogram::new(&GAS_BUCKETS),
+            gas_used_per_block: Histogram::new(&GAS_BUCKETS),
+            transactions_per_sender: HashMap::new(),
+            current_block_gas: 0,
+        }
+    }
+}
+
+impl EnvironmentMetrics {
+    /// Records a processed transaction and its result.
+    pub(crate) fn record_transaction(&mut self, sender: B160, result: &ExecutionResult) {
+        self.transactions += 1;
+        *self
+            .transactions_per_sender
+            .entry(Address::from(sender.to_fixed_bytes()))
+            .or_default() += 1;
+        let gas_used = match result {
+            ExecutionResult::Success { gas_used, .. } => *gas_used,
+            ExecutionResult::Revert { gas_used, .. } => {
+                self.reverts += 1;
+                *gas_used
+            }
+            ExecutionResult::Halt { gas_used, .. } => {
+                self.halts += 1;
+                *gas_used
+            }
+        };
+        self.gas_used_per_transaction.observe(gas_used);
+        self.current_block_gas = self.current_block_gas.saturating_add(gas_used);
+    }
+
+    /// Records a processed call.
+    pub(crate) fn record_call(&mut self) {
+        self.calls += 1;
+    }
+
+    /// Records that the current block is finished.
+    pub(crate) fn record_block(&mut self) {
+        self.blocks += 1;
+        self.gas_used_per_block.observe(self.current_block_gas);
+        self.current_block_gas = 0;
+    }
+
+    /// Records the amount of transactions and calls waiting to be processed.
+    pub(crate) fn record_queue_depth(&mut self, queue_depth: usize) {
+        self.queue_depth = queue_depth as u64;
+        self.max_queue_depth = self.max_queue_depth.max(self.queue_depth);
+    }
+}
+
+/// The name, help text and getter of a counter or gauge.
+type ValueMetric = (&'static str, &'static str, fn(&EnvironmentMetrics) -> u64);
+
+/// The name, help text and getter of a histogram.
+type HistogramMetric = (
+    &'static str,
+    &'static str,
+    fn(&EnvironmentMetrics) -> &Histogram,
+);
+
+/// Renders the metrics of a set of environments, keyed by their labels, in
+/// the Prometheus text exposition format.
+pub fn render_prometheus(metrics: &BTreeMap<String, EnvironmentMetrics>) -> String {
+    let mut output = String::new();
+    let metrics: Vec<_> = metrics
+        .iter()
+        .map(|(label, metrics)| (escape_label_value(label), metrics))
+        .collect();
+    let counters: [ValueMetric; 5] = [
+        ("transactions_total", "Transactions processed.", |m| {
+            m.transactions
+        }),
+        ("calls_total", "Read only calls processed.", |m| m.calls),
+        ("reverts_total", "Transactions that reverted.", |m| {
+            m.reverts
+        }),
+        ("halts_total", "Transactions that halted.", |m| m.halts),
+        ("blocks_total", "Blocks produced.", |m| m.blocks),
+    ];
+    for (name, help, value) in counters {
+        write_header(&mut output, name, help, "counter");
+        for (label, metrics) in &metrics {
+            writeln!(
+                output,
+                "arbiter_{}{{environment=\"{}\"}} {}",
+                name,
+                label,
+                value(metrics)
+            )
+            .ok();
+        }
+    }
+    let gauges: [ValueMetric; 2] = [
+        (
+            "queue_depth",
+            "Transactions and calls waiting to be processed.",
+            |m| m.queue_depth,
+        ),
+        ("max_queue_depth", "Largest queue depth seen.", |m| {
+            m.max_queue_depth
+        }),
+    ];
+    for (name, help, value) in gauges {
+        write_header(&mut output, name, help, "gauge");
+        for (label, metrics) in &metrics {
+            writeln!(
+                output,
+                "arbiter_{}{{environment=\"{}\"}} {}",
+                name,
+                label,
+                value(metrics)
+            )
+            .ok();
+        }
+    }
+    write_header(
+        &mut output,
+        "sender_transactions_total",
+        "Transactions sent by each address.",
+        "counter",
+    );
+    for (label, metrics) in &metrics {
+        let mut senders: Vec<_> = metrics.transactions_per_sender.iter().collect();
+        senders.sort();
+        for (sender, count) in senders {
+            writeln!(
+                output,
+                "arbiter_sender_transactions_total{{environment=\"{}\",sender=\"{:?}\"}} {}",
+                label, sender, count
+            )
+            .ok();
+        }
+    }
+    let histograms: [HistogramMetric; 2] = [
+        (
+            "gas_used_per_transaction",
+            "Gas used by each transaction.",
+            |m| &m.gas_used_per_transaction,
+        ),
+        (
+            "gas_used_per_block",
+            "Gas used by each finished block.",
+            |m| &m.gas_used_per_block,
+        ),
+    ];
+    for (name, help, histogram) in histograms {
+        write_header(&mut output, name, help, "histogram");
+        for (label, metrics) in &metrics {
+            let histogram = histogram(metrics);
+            let mut cumulative = 0;
+            for (bound, count) in histogram.bounds.iter().zip(&histogram.counts) {
+                cumulative += count;
+                writeln!(
+                    output,
+                    "arbiter_{}_bucket{{environment=\"{}\",le=\"{}\"}} {}",
+                    name, label, bound, cumulative
+                )
+                .ok();
+            }
+            writeln!(
+                output,
+                "arbiter_{}_bucket{{environment=\"{}\",le=\"+Inf\"}} {}",
+                name, label, histogram.count
+            )
+            .ok();
+            writeln!(
+                output,
+                "arbiter_{}_sum{{environment=\"{}\"}} {}",
+                name, label, histogram.sum
+            )
+            .ok();
+            writeln!(
+                output,
+                "arbiter_{}_count{{environment=\"{}\"}} {}",
+                name, label, histogram.count
+            )
+            .ok();
+        }
+    }
+    output
+}
+
+/// Escapes the backslashes, double quotes and line feeds of a label value,
+/// which would otherwise end the value or the line of its sample.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Writes the `HELP` and `TYPE` lines of a metric.
+fn write_header(output: &mut String, name: &str, help: &str, kind: &str) {
+    writeln!(output, "# HELP arbiter_{} {}", name, help).ok();
+    writeln!(output, "# TYPE arbiter_{} {}", name, kind).ok();
+}
+
+/// The thread that serves the metrics of a [`Manager`]. The thread is stopped
+/// and joined via [`MetricsServer::stop`] or when the server is dropped.
+#[derive(Debug)]
+pub(crate) struct MetricsServer {
+    /// Tells the thread of the server to stop accepting requests.
+    stopped: Arc<AtomicBool>,
+
+    /// [`JoinHandle`] for the thread in which the metrics are served.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Stops accepting requests and waits for the thread of the server to
+    /// finish. Returns an error if the thread has panicked.
+    pub(crate) fn stop(&mut self) -> thread::Result<()> {
+        self.stopped.store(true, Ordering::SeqCst);
+        match self.handle.take() {
+            Some(handle) => handle.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        if self.stop().is_err() {
+            warn!("The metrics server thread panicked");
+        }
+    }
+}
+
+/// Answers every HTTP request made to the `listener` with the current metrics
+/// of the environments in the `registry` on a new thread until the returned
+/// [`MetricsServer`] is stopped. Each connection is answered on its own thread
+/// so that a slow client cannot hold up the server or its shutdown.
+pub(crate) fn serve(
+    listener: TcpListener,
+    registry: MetricsRegistry,
+) -> std::io::Result<MetricsServer> {
+    // Accept without blocking so that the thread notices when it is stopped
+    listener.set_nonblocking(true)?;
+    let stopped = Arc::new(AtomicBool::new(false));
+    let thread_stopped = Arc::clone(&stopped);
+    let handle = thread::spawn(move || {
+        while !thread_stopped.load(Ordering::SeqCst) {
+            let result = match listener.accept() {
+                Ok((stream, _)) => {
+                    let registry = Arc::clone(&registry);
+                    thread::spawn(move || {
+                        if let Err(e) = respond(stream, &registry) {
+                            warn!("Failed to serve metrics: {}", e);
+                        }
+                    });
+                    Ok(())
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("Failed to serve metrics: {}", e);
+            }
+        }
+    });
+    Ok(MetricsServer {
+        stopped,
+        handle: Some(handle),
+    })
+}
+
+/// Reads a single HTTP request and responds with the rendered metrics. Gives
+/// up once the connection has been idle for [`CONNECTION_TIMEOUT`] or its
+/// request exceeds [`MAX_REQUEST_SIZE`].
+fn respond(mut stream: TcpStream, registry: &MetricsRegistry) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+    // The request itself does not matter, so just read up to the empty line that
+    // ends its headers
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_SIZE));
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" && line != "\n" {
+        line.clear();
+    }
+    let snapshot: BTreeMap<String, EnvironmentMetrics> = registry
+        .lock()
+        .map_err(|e| std::io::Error::other(e.to_string()))?
+        .iter()
+        .map(|(label, metrics)| {
+            let metrics = metrics.lock().map(|m| m.clone()).unwrap_or_default();
+            (label.clone(), metrics)
+        })
+        .collect();
+    let body = render_prometheus(&snapshot);
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::primitives::{Eval, Output};
+
+    use super::*;
+
+    #[test]
+    fn histogram_buckets() {
+        let mut histogram = Histogram::new(&[10, 100]);
+        for value in [5, 10, 11, 1000] {
+            histogram.observe(value);
+        }
+        assert_eq!(histogram.counts, vec![2, 1, 1]);
+        assert_eq!(histogram.sum, 1026);
+        assert_eq!(histogram.count, 4);
+        assert_eq!(histogram.mean(), Some(256.5));
+    }
+
+    #[test]
+    fn render_metrics() {
+        let mut metrics = EnvironmentMetrics::default();
+        metrics.record_transaction(
+            B160::zero(),
+            &ExecutionResult::Success {
+                reason: Eval::Stop,
+                gas_used: 21_000,
+                gas_refunded: 0,
+                logs: vec![],
+                output: Output::Call(bytes::Bytes::new()),
+            },
+        );
+        metrics.record_block();
+        let rendered = render_prometheus(&BTreeMap::from([("l1".to_string(), metrics)]));
+        assert!(rendered.contains("arbiter_transactions_total{environment=\"l1\"} 1\n"));
+        assert!(rendered.contains("arbiter_blocks_total{environment=\"l1\"} 1\n"));
+        assert!(rendered
+            .contains("arbiter_gas_used_per_block_bucket{environment=\"l1\",le=\"21000\"} 1\n"));
+        assert!(rendered.contains(&format!(
+            "arbiter_sender_transactions_total{{environment=\"l1\",sender=\"{:?}\"}} 1\n",
+            Address::zero()
+        )));
+    }
+
+    #[test]
+    fn render_escapes_labels() {
+        let rendered = render_prometheus(&BTreeMap::from([(
+            "a\\b\"c\nd".to_string(),
+            EnvironmentMetrics::default(),
+        )]));
+        assert!(rendered.contains("arbiter_blocks_total{environment=\"a\\\\b\\\"c\\nd\"} 0\n"));
+    }
+}
@@ -7,7 +7,10 @@
 //! - [`RevmMiddleware`]: The core middleware implementation.
 //! - [`RevmMiddlewareError`]: Error type for the middleware.
 //! - [`Connection`]: Handles communication with the Ethereum VM.
-//! - `FilterReceiver`: Facilitates event watching based on certain filters.
+//! - `FilterReceiver`: Facilitates watching events and new blocks based on
+//!   certain filters.
+//! - `Subscription`: Pushes events and new blocks to `ethers-rs` subscriptions
+//!   as they are broadcast.
 
 #![warn(missing_docs, unsafe_code)]
 
@@ -16,7 +19,8 @@
 use std::{
     collections::HashMap,
     fmt::Debug,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread,
     time::Duration,
 };
 
@@ -32,23 +36,42 @@ use ethers::{
     },
     providers::{
         FilterKind, FilterWatcher, JsonRpcClient, Middleware, MiddlewareError, PendingTransaction,
-        Provider,
+        Provider, PubsubClient, SubscriptionStream,
     },
     signers::{Signer, Wallet},
     types::{
-        transaction::eip2718::TypedTransaction, Address, BlockId, Bytes, Filter, FilteredParams,
-        Log,
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address, Block, BlockId, BlockNumber, Bytes, Filter, FilterBlockOption, FilteredParams,
+        Log, NameOrAddress, Signature, Transaction, H256, U64,
     },
+    utils::{get_contract_address, get_create2_address},
 };
+use futures::channel::mpsc;
 use rand::rngs;
-use revm::primitives::{CreateScheme, ExecutionResult, Output, TransactTo, TxEnv, B160, U256};
+use revm::primitives::{
+    CreateScheme, EVMError, ExecutionResult, Output, TransactTo, TxEnv, B160, U256,
+};
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::value::RawValue;
 use thiserror::Error;
 
 use crate::environment::{
-    Environment, EventBroadcaster, ResultReceiver, ResultSender, TransactionOutcome, TxSender,
+    convert_uint_to_u64, BlockHeader, Cheat, EmittedLogs, Environment, EnvironmentError,
+    EnvironmentQuery, EventBroadcaster, Instruction, InstructionSender, LogStore, QueryOutcome,
+    QuerySender, ResultReceiver, ResultSender, RevmResult, TransactionOutcome,
 };
 
+/// How often the [`FilterWatcher`]s of a [`RevmMiddleware`] poll for changes.
+/// Polling never waits for the [`Environment`], so a short interval keeps
+/// idle watchers from spinning while still handing out changes promptly.
+const FILTER_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How long a subscription made via `eth_subscribe` is kept before it is
+/// streamed. Subscriptions that are never streamed are dropped after this so
+/// that they stop receiving from the [`EventBroadcaster`], which then forgets
+/// their senders with its next broadcast.
+const PENDING_SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// A middleware structure that integrates with `revm`.
 ///
 /// [`RevmMiddleware`] serves as a bridge between the application and `revm`'s
@@ -92,6 +115,10 @@ use crate::environment::{
 pub struct RevmMiddleware {
     provider: Provider<Connection>,
     wallet: Wallet<SigningKey>,
+    /// Held from giving a transaction without a nonce the current one until
+    /// it is executed, so that transactions sent at once by this client do
+    /// not take the same nonce.
+    nonce_lock: tokio::sync::Mutex<()>,
 }
 
 /// Errors that can occur while using the [`RevmMiddleware`].
@@ -152,6 +179,21 @@ pub enum RevmMiddlewareError {
         /// Provides the amount of gas used by the transaction.
         gas_used: u64,
     },
+
+    /// The [`Environment`] rejected a transaction as invalid, e.g., because
+    /// the caller cannot afford its value.
+    #[error("invalid transaction! due to: {0}")]
+    InvalidTransaction(String),
+
+    /// The wallet of the client failed to sign a message or transaction, or
+    /// was asked to sign for another address.
+    #[error("failed to sign! due to: {0}")]
+    Signing(String),
+
+    /// The [`Connection`] failed to answer a request, e.g., because the
+    /// method is not supported.
+    #[error("failed to answer the request! due to: {0}")]
+    Provider(ProviderError),
 }
 
 impl MiddlewareError for RevmMiddlewareError {
@@ -192,14 +234,16 @@ impl RevmMiddleware {
     /// Use a seed if you want to have a constant address across simulations as
     /// well as a label for a client. This can be useful for debugging.
     pub fn new(environment: &Environment, seed_and_label: Option<String>) -> Self {
-        let tx_sender = environment.socket.tx_sender.clone();
+        let instruction_sender = environment.socket.instruction_sender.clone();
         let (result_sender, result_receiver) = crossbeam_channel::unbounded();
         let connection = Connection {
-            tx_sender,
+            instruction_sender,
             result_sender,
             result_receiver,
             event_broadcaster: Arc::clone(&environment.socket.event_broadcaster),
             filter_receivers: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            log_store: Arc::clone(&environment.log_store),
             environment_state: Arc::clone(&environment.state),
         };
         let provider = Provider::new(connection);
@@ -209,13 +253,345 @@ impl RevmMiddleware {
             let hashed = hasher.finalize();
             let mut rng: rngs::StdRng = SeedableRng::from_seed(hashed.into());
             let wallet = Wallet::new(&mut rng);
-            Self { provider, wallet }
+            Self {
+                provider,
+                wallet,
+                nonce_lock: tokio::sync::Mutex::new(()),
+            }
         } else {
             let mut rng = rand::thread_rng();
             let wallet = Wallet::new(&mut rng);
-            Self { provider, wallet }
+            Self {
+                provider,
+                wallet,
+                nonce_lock: tokio::sync::Mutex::new(()),
+            }
+        }
+    }
+
+    /// Sets the ETH balance of `address` in the [`Environment`] to `amount`
+    /// wei. Acts as a faucet so that client wallets can pay for the value of
+    /// their transactions, since every account starts out with no ETH.
+    ///
+    /// # Examples
+    /// ```
+    /// # use arbiter_core::{
+    /// #     environment::EnvironmentParameters, manager::Manager, middleware::RevmMiddleware,
+    /// # };
+    /// # use ethers::{providers::Middleware, types::{TransactionRequest, U256}};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut manager = Manager::new();
+    /// # let params = EnvironmentParameters { block_rate: 1.0, seed: 1 };
+    /// # manager.add_environment("example_env", params)?;
+    /// # manager.start_environment("example_env")?;
+    /// # let environment = manager.environments.get("example_env").unwrap();
+    /// let alice = RevmMiddleware::new(&environment, Some("alice".to_string()));
+    /// let bob = RevmMiddleware::new(&environment, Some("bob".to_string()));
+    /// let alice_address = alice.default_sender().unwrap();
+    /// let bob_address = bob.default_sender().unwrap();
+    ///
+    /// alice.deal(alice_address, U256::exp10(18))?;
+    /// let transfer = TransactionRequest::new().to(bob_address).value(1000);
+    /// alice.send_transaction(transfer, None).await?.await?;
+    /// assert_eq!(bob.get_balance(bob_address, None).await?, U256::from(1000));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deal(
+        &self,
+        address: Address,
+        amount: ethers::types::U256,
+    ) -> Result<(), RevmMiddlewareError> {
+        self.provider()
+            .as_ref()
+            .instruct(|sender| {
+                Instruction::Cheat(
+                    Cheat::Deal(B160::from(address), recast_ethers_uint(amount)),
+                    sender,
+                )
+            })
+            .map_err(RevmMiddlewareError::Provider)?;
+        Ok(())
+    }
+
+    /// Finishes the current block of the [`Environment`] right away, however
+    /// many transactions it has seen, and returns the number of the next
+    /// block. If the [`Environment`] is synchronized by a
+    /// [`Clock`](crate::clock::Clock), this waits for the rest of the
+    /// synchronized environments to finish the block too, which lets an
+    /// [`Environment`] without traffic of its own keep up with the others.
+    pub fn mine(&self) -> Result<U64, RevmMiddlewareError> {
+        let block_number = self
+            .provider()
+            .as_ref()
+            .instruct(Instruction::Mine)
+            .map_err(RevmMiddlewareError::Provider)?
+            .into_uint()
+            .map_err(RevmMiddlewareError::Provider)?;
+        convert_uint_to_u64(block_number)
+            .map_err(|e| RevmMiddlewareError::Conversion(e.to_string()))
+    }
+
+    /// Signs the EIP-712 typed `data` with the wallet of this client, e.g., a
+    /// `permit` or an off-chain order that a contract verifies with
+    /// `ecrecover`.
+    pub async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        data: &T,
+    ) -> Result<Signature, RevmMiddlewareError> {
+        self.wallet
+            .sign_typed_data(data)
+            .await
+            .map_err(|e| RevmMiddlewareError::Signing(e.to_string()))
+    }
+
+    /// Starts watching for new blocks like [`Middleware::watch_blocks`], but
+    /// yields the whole [`BlockHeader`] of every block the [`Environment`] has
+    /// moved on from rather than its hash.
+    pub async fn watch_headers(
+        &self,
+    ) -> Result<FilterWatcher<'_, Connection, BlockHeader>, RevmMiddlewareError> {
+        let id = self.new_block_filter(FilterReceiver::Headers).await?;
+        Ok(FilterWatcher::new(id, self.provider()).interval(FILTER_POLL_INTERVAL))
+    }
+
+    /// Installs a filter that receives the [`BlockHeader`] of every finished
+    /// block, where `kind` decides how the headers are handed out.
+    async fn new_block_filter(
+        &self,
+        kind: fn(crossbeam_channel::Receiver<BlockHeader>) -> FilterReceiver,
+    ) -> Result<ethers::types::U256, RevmMiddlewareError> {
+        let header_receiver = self.header_receiver()?;
+        // Block filters have no arguments to tell them apart, so they get a
+        // random ID like subscriptions do
+        let id = ethers::types::U256::from(rand::random::<u128>());
+        self.provider()
+            .as_ref()
+            .filter_receivers
+            .lock()
+            .await
+            .insert(id, kind(header_receiver));
+        Ok(id)
+    }
+
+    /// Subscribes to the [`BlockHeader`] of every block the [`Environment`]
+    /// finishes from now on.
+    pub(crate) fn header_receiver(
+        &self,
+    ) -> Result<crossbeam_channel::Receiver<BlockHeader>, RevmMiddlewareError> {
+        let (header_sender, header_receiver) = crossbeam_channel::unbounded::<BlockHeader>();
+        self.provider()
+            .as_ref()
+            .event_broadcaster
+            .lock()
+            .map_err(|e| {
+                RevmMiddlewareError::EventBroadcaster(format!(
+                    "Failed to gain lock on the `Connection`'s `event_broadcaster` due to {:?} ",
+                    e
+                ))
+            })?
+            .add_header_sender(header_sender);
+        Ok(header_receiver)
+    }
+
+    /// Checks that `from` is the address of the wallet of this client, which
+    /// is the only one it can sign for.
+    fn check_signer(&self, from: Address) -> Result<(), RevmMiddlewareError> {
+        if from != self.wallet.address() {
+            return Err(RevmMiddlewareError::Signing(format!(
+                "{:?} is not the signer of this client",
+                from
+            )));
+        }
+        Ok(())
+    }
+
+    /// Deploys a contract with `CREATE2` from the wallet of this client and
+    /// returns its address. The address only depends on the address of this
+    /// client, the `salt` and the init code of the deployment, so it can be
+    /// predicted with [`RevmMiddleware::create2_address`] before deploying.
+    ///
+    /// The deployment `tx` is typically taken from an abigen binding, e.g.,
+    /// `ArbiterToken::deploy(client.clone(), args)?.deployer.tx`.
+    pub async fn deploy_create2(
+        &self,
+        tx: impl Into<TypedTransaction>,
+        salt: H256,
+    ) -> Result<Address, RevmMiddlewareError> {
+        let tx: TypedTransaction = tx.into();
+        let tx_env = TxEnv {
+            transact_to: TransactTo::Create(CreateScheme::Create2 {
+                salt: U256::from_be_bytes(salt.to_fixed_bytes()),
+            }),
+            ..tx_env(self.wallet.address(), &tx)?
+        };
+        let revm_result =
+            self.execute(|sender| Instruction::Transaction(true, Box::new(tx_env), sender))?;
+        match revm_result.outcome {
+            TransactionOutcome::Success(execution_result) => {
+                match unpack_execution_result(execution_result)?.output {
+                    Output::Create(_, Some(address)) => Ok(recast_address(address)),
+                    _ => Err(RevmMiddlewareError::MissingData(
+                        "Address missing in transaction!".to_string(),
+                    )),
+                }
+            }
+            TransactionOutcome::Error(err) => Err(environment_error(err)),
         }
     }
+
+    /// Predicts the address that [`RevmMiddleware::deploy_create2`] deploys
+    /// the init code of the deployment `tx` to with the given `salt`.
+    pub fn create2_address(&self, tx: &TypedTransaction, salt: H256) -> Address {
+        let init_code = tx.data().cloned().unwrap_or_default();
+        get_create2_address(self.wallet.address(), salt, init_code)
+    }
+
+    /// Predicts the address that the next regular deployment of this client
+    /// is deployed to, i.e., the `CREATE` address for its current nonce.
+    pub async fn next_create_address(&self) -> Result<Address, RevmMiddlewareError> {
+        let nonce = self
+            .get_transaction_count(self.wallet.address(), None)
+            .await?;
+        Ok(get_contract_address(self.wallet.address(), nonce))
+    }
+
+    /// Sends the call or transaction [`Instruction`] built around the
+    /// [`ResultSender`] of this client to the [`Environment`] and waits for its
+    /// [`RevmResult`].
+    pub(crate) fn execute(
+        &self,
+        instruction: impl FnOnce(ResultSender) -> Instruction,
+    ) -> Result<RevmResult, RevmMiddlewareError> {
+        if self
+            .provider()
+            .as_ref()
+            .environment_state
+            .load(std::sync::atomic::Ordering::SeqCst)
+            == crate::environment::State::Paused
+        {
+            return Err(RevmMiddlewareError::Send("Environment Paused".to_string()));
+        }
+        let connection = self.provider().as_ref();
+        connection
+            .instruction_sender
+            .send(instruction(connection.result_sender.clone()))
+            .map_err(|e| RevmMiddlewareError::Send(e.to_string()))?;
+        connection
+            .result_receiver
+            .recv()
+            .map_err(|e| RevmMiddlewareError::Receive(e.to_string()))
+    }
+
+    /// Executes a call to the [`Environment`] without committing it and
+    /// unpacks its result. The call is made against the state as of the end of
+    /// `block_number` if given and against the current state otherwise.
+    fn dry_run(
+        &self,
+        tx_env: TxEnv,
+        block_number: Option<u64>,
+    ) -> Result<Success, RevmMiddlewareError> {
+        let tx_env = Box::new(tx_env);
+        let revm_result = self.execute(|sender| match block_number {
+            Some(block_number) => Instruction::HistoricalCall(block_number, tx_env, sender),
+            None => Instruction::Transaction(false, tx_env, sender),
+        })?;
+        match revm_result.outcome {
+            TransactionOutcome::Success(execution_result) => {
+                unpack_execution_result(execution_result)
+            }
+            TransactionOutcome::Error(err) => Err(environment_error(err)),
+        }
+    }
+
+    /// Executes a call like [`Middleware::call`] and returns its output along
+    /// with the gas it used.
+    pub async fn call_with_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(Bytes, u64), RevmMiddlewareError> {
+        let tx_env = tx_env(self.wallet.address(), tx)?;
+        self.call_tx_env(tx_env, block_number(block)?)
+    }
+
+    /// Executes the call given by `tx_env` like
+    /// [`RevmMiddleware::call_with_gas`], against the state as of the end
+    /// of `block_number` if given, and returns its output along with the
+    /// gas it used.
+    pub(crate) fn call_tx_env(
+        &self,
+        tx_env: TxEnv,
+        block_number: Option<u64>,
+    ) -> Result<(Bytes, u64), RevmMiddlewareError> {
+        // Calls do not check the nonce just like `eth_call`
+        let tx_env = TxEnv {
+            nonce: None,
+            ..tx_env
+        };
+        let Success {
+            gas_used, output, ..
+        } = self.dry_run(tx_env, block_number)?;
+        let output = match output {
+            Output::Create(bytes, ..) => bytes,
+            Output::Call(bytes) => bytes,
+        };
+        Ok((Bytes::from(output.to_vec()), gas_used))
+    }
+
+    /// Estimates the gas the transaction given by `tx_env` needs like
+    /// [`Middleware::estimate_gas`], against the state as of the end of
+    /// `block_number` if given.
+    pub(crate) fn estimate(
+        &self,
+        tx_env: TxEnv,
+        block_number: Option<u64>,
+    ) -> Result<u64, RevmMiddlewareError> {
+        let tx_env = TxEnv {
+            nonce: None,
+            ..tx_env
+        };
+        // A transaction that fails with all the gas it may use fails with any
+        let gas_used = self.dry_run(tx_env.clone(), block_number)?.gas_used;
+        let succeeds = |gas_limit: u64| match self.dry_run(
+            TxEnv {
+                gas_limit,
+                ..tx_env.clone()
+            },
+            block_number,
+        ) {
+            Ok(_) => Ok(true),
+            Err(RevmMiddlewareError::ExecutionRevert { .. })
+            | Err(RevmMiddlewareError::ExecutionHalt { .. })
+            | Err(RevmMiddlewareError::InvalidTransaction(_)) => Ok(false),
+            Err(err) => Err(err),
+        };
+
+        // Refunds and the 63/64 rule for nested calls mean that a transaction
+        // may need more gas than it used, but never less
+        let mut low = gas_used.saturating_sub(1);
+        let mut high = tx_env.gas_limit;
+        // Most transactions succeed with little more than the gas they used,
+        // which saves searching all the way down from the gas limit
+        let optimistic = (gas_used.saturating_add(2300)).saturating_mul(64) / 63;
+        if optimistic < high {
+            if succeeds(optimistic)? {
+                high = optimistic;
+            } else {
+                low = optimistic;
+            }
+        }
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if succeeds(mid)? {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        Ok(high)
+    }
 }
 
 #[async_trait::async_trait]
@@ -250,89 +626,59 @@ impl Middleware for RevmMiddleware {
     /// transaction environment used for `revm`-based transactions.
     /// It then sends this transaction for execution and returns the
     /// corresponding pending transaction.
+    ///
+    /// The transaction is signed by the wallet of this client beforehand, with
+    /// its `nonce` and `chain_id` filled in if missing, so the pending
+    /// transaction carries its real hash. Transactions without a `nonce` sent
+    /// at once by this client are executed one after another, each with the
+    /// nonce the previous one left.
     async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
         &self,
         tx: T,
         _block: Option<BlockId>,
     ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
-        if self
-            .provider()
-            .as_ref()
-            .environment_state
-            .load(std::sync::atomic::Ordering::SeqCst)
-            == crate::environment::State::Paused
-        {
-            return Err(RevmMiddlewareError::Send("Environment Paused".to_string()));
-        }
-
-        let tx: TypedTransaction = tx.into();
-
-        // Check the `to` field of the transaction to determine if it is a call or a
-        // deploy. If there is no `to` field, then it is a `Deploy` else it is a
-        // `Call`.
-        let transact_to = match tx.to_addr() {
-            Some(to) => TransactTo::Call(B160::from(*to)),
-            None => TransactTo::Create(CreateScheme::Create),
-        };
-        let tx_env = TxEnv {
-            caller: B160::from(self.wallet.address()),
-            gas_limit: u64::MAX,
-            gas_price: U256::ZERO,
-            gas_priority_fee: None,
-            transact_to,
-            value: U256::ZERO,
-            data: bytes::Bytes::from(
-                tx.data()
-                    .ok_or(RevmMiddlewareError::MissingData(
-                        "Data missing in transaction!".to_string(),
-                    ))?
-                    .to_vec(),
-            ),
-            chain_id: None,
-            nonce: None,
-            access_list: Vec::new(),
+        let mut tx: TypedTransaction = tx.into();
+        tx.set_from(self.wallet.address());
+        let _nonce_guard = match tx.nonce() {
+            Some(_) => None,
+            None => {
+                let nonce_guard = self.nonce_lock.lock().await;
+                let nonce = self
+                    .get_transaction_count(self.wallet.address(), None)
+                    .await?;
+                tx.set_nonce(nonce);
+                Some(nonce_guard)
+            }
         };
-        self.provider()
-            .as_ref()
-            .tx_sender
-            .send((
-                true,
-                tx_env.clone(),
-                self.provider().as_ref().result_sender.clone(),
-            ))
-            .map_err(|e| RevmMiddlewareError::Send(e.to_string()))?;
-
-        let revm_result = self
-            .provider()
-            .as_ref()
-            .result_receiver
-            .recv()
-            .map_err(|e| RevmMiddlewareError::Receive(e.to_string()))?;
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.wallet.chain_id());
+        }
+        let signature = self.sign_transaction(&tx, self.wallet.address()).await?;
+        let tx_hash = tx.hash(&signature);
+        let tx_env = Box::new(tx_env(self.wallet.address(), &tx)?);
+        let revm_result = self.execute(|sender| Instruction::Transaction(true, tx_env, sender))?;
 
         match revm_result.outcome {
             TransactionOutcome::Success(execution_result) => {
                 let Success {
-                    _reason: _,
-                    _gas_used: _,
-                    _gas_refunded: _,
-                    logs,
-                    output,
+                    mut logs, output, ..
                 } = unpack_execution_result(execution_result)?;
+                for log in logs.iter_mut() {
+                    log.transaction_hash = Some(tx_hash);
+                }
 
                 match output {
                     Output::Create(_, address) => {
                         let address = address.ok_or(RevmMiddlewareError::MissingData(
                             "Address missing in transaction!".to_string(),
                         ))?;
-                        let mut pending_tx =
-                            PendingTransaction::new(ethers::types::H256::zero(), self.provider());
+                        let mut pending_tx = PendingTransaction::new(tx_hash, self.provider());
                         pending_tx.state =
                             PendingTxState::RevmDeployOutput(recast_address(address));
                         return Ok(pending_tx);
                     }
                     Output::Call(_) => {
-                        let mut pending_tx =
-                            PendingTransaction::new(ethers::types::H256::zero(), self.provider());
+                        let mut pending_tx = PendingTransaction::new(tx_hash, self.provider());
 
                         pending_tx.state =
                             PendingTxState::RevmTransactOutput(logs, revm_result.block_number);
@@ -341,13 +687,7 @@ impl Middleware for RevmMiddleware {
                 }
             }
             TransactionOutcome::Error(err) => {
-                return Err(RevmMiddlewareError::Receive(
-                    format!(
-                        "Error recieving response from the environement with environment error: {}",
-                        err
-                    )
-                    .to_string(),
-                ));
+                return Err(environment_error(err));
             }
         }
     }
@@ -360,97 +700,153 @@ impl Middleware for RevmMiddleware {
     /// targeting an existing contract or deploying a new one. After
     /// executing the call, it returns the output, but no worldstate change will
     /// be documented in the `revm` DB.
+    ///
+    /// Given the number of a past `block`, the call is made against the state
+    /// as of the end of that block, which has to be retained by the
+    /// [`Environment`].
     async fn call(
         &self,
         tx: &TypedTransaction,
-        _block: Option<BlockId>,
+        block: Option<BlockId>,
     ) -> Result<Bytes, Self::Error> {
-        if self
-            .provider()
-            .as_ref()
-            .environment_state
-            .load(std::sync::atomic::Ordering::SeqCst)
-            == crate::environment::State::Paused
-        {
-            return Err(RevmMiddlewareError::Send("Environment Paused".to_string()));
-        }
-        let tx = tx.clone();
-
-        // Check the `to` field of the transaction to determine if it is a call or a
-        // deploy. If there is no `to` field, then it is a `Deploy` else it is a
-        // `Call`.
-        let transact_to = match tx.to_addr() {
-            Some(to) => TransactTo::Call(B160::from(*to)),
-            None => TransactTo::Create(CreateScheme::Create),
-        };
-        let tx_env = TxEnv {
-            caller: B160::from(self.wallet.address()),
-            gas_limit: u64::MAX,
-            gas_price: U256::ZERO,
-            gas_priority_fee: None,
-            transact_to,
-            value: U256::ZERO,
-            data: bytes::Bytes::from(
-                tx.data()
-                    .ok_or(RevmMiddlewareError::MissingData(
-                        "Data missing in transaction!".to_string(),
-                    ))?
-                    .to_vec(),
-            ),
-            chain_id: None,
-            nonce: None,
-            access_list: Vec::new(),
-        };
+        let (output, _) = self.call_with_gas(tx, block).await?;
+        Ok(output)
+    }
+
+    /// Signs `data` as an Ethereum signed message with the wallet of this
+    /// client, which must be the wallet of `from`.
+    async fn sign<T: Into<Bytes> + Send + Sync>(
+        &self,
+        data: T,
+        from: &Address,
+    ) -> Result<Signature, Self::Error> {
+        self.check_signer(*from)?;
+        self.wallet
+            .sign_message(data.into())
+            .await
+            .map_err(|e| RevmMiddlewareError::Signing(e.to_string()))
+    }
+
+    /// Signs a transaction with the wallet of this client, which must be the
+    /// wallet of `from`. Without a `chain_id`, the transaction is signed for
+    /// the chain id of the wallet.
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+        from: Address,
+    ) -> Result<Signature, Self::Error> {
+        self.check_signer(from)?;
+        self.wallet
+            .sign_transaction(tx)
+            .await
+            .map_err(|e| RevmMiddlewareError::Signing(e.to_string()))
+    }
+
+    /// Estimates the gas a transaction needs by executing it as a call on the
+    /// current state of the [`Environment`] or the state as of the end of a
+    /// past `block` whose state it retains. The estimate is the smallest gas
+    /// limit up to the `gas` of the transaction with which it succeeds, found
+    /// by binary search.
+    async fn estimate_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<ethers::types::U256, Self::Error> {
+        let tx_env = tx_env(self.wallet.address(), tx)?;
+        Ok(self.estimate(tx_env, block_number(block)?)?.into())
+    }
+
+    /// Returns the number of the current block of the [`Environment`].
+    async fn get_block_number(&self) -> Result<U64, Self::Error> {
         self.provider()
-            .as_ref()
-            .tx_sender
-            .send((
-                false,
-                tx_env.clone(),
-                self.provider().as_ref().result_sender.clone(),
-            ))
-            .map_err(|e| RevmMiddlewareError::Send(e.to_string()))?;
-        let revm_result = self
-            .provider()
-            .as_ref()
-            .result_receiver
-            .recv()
-            .map_err(|e| RevmMiddlewareError::Receive(e.to_string()))?;
+            .get_block_number()
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
 
-        match revm_result.outcome {
-            TransactionOutcome::Success(execution_result) => {
-                let output = unpack_execution_result(execution_result)?.output;
-                match output {
-                    Output::Create(bytes, ..) => {
-                        return Ok(Bytes::from(bytes.to_vec()));
-                    }
-                    Output::Call(bytes) => {
-                        return Ok(Bytes::from(bytes.to_vec()));
-                    }
-                }
-            }
-            TransactionOutcome::Error(err) => {
-                return Err(RevmMiddlewareError::Receive(
-                    format!(
-                        "Error recieving response from the environement with environment error: {}",
-                        err
-                    )
-                    .to_string(),
-                ));
-            }
-        }
+    /// Returns the chain ID of the [`Environment`].
+    async fn get_chainid(&self) -> Result<ethers::types::U256, Self::Error> {
+        self.provider()
+            .get_chainid()
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Returns the balance of an account in the current state of the
+    /// [`Environment`] or as of the end of a past `block` whose state it
+    /// retains.
+    async fn get_balance<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<BlockId>,
+    ) -> Result<ethers::types::U256, Self::Error> {
+        self.provider()
+            .get_balance(from, block)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Returns the nonce of an account in the current state of the
+    /// [`Environment`] or as of the end of a past `block` whose state it
+    /// retains.
+    async fn get_transaction_count<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<BlockId>,
+    ) -> Result<ethers::types::U256, Self::Error> {
+        self.provider()
+            .get_transaction_count(from, block)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Returns the runtime bytecode of an account in the current state of the
+    /// [`Environment`] or as of the end of a past `block` whose state it
+    /// retains.
+    async fn get_code<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        at: T,
+        block: Option<BlockId>,
+    ) -> Result<Bytes, Self::Error> {
+        self.provider()
+            .get_code(at, block)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Returns the value of a storage slot of an account in the current state
+    /// of the [`Environment`] or as of the end of a past `block` whose state
+    /// it retains.
+    async fn get_storage_at<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        location: H256,
+        block: Option<BlockId>,
+    ) -> Result<H256, Self::Error> {
+        self.provider()
+            .get_storage_at(from, location, block)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Returns the logs stored by the [`Environment`] that match the `filter`
+    /// and were emitted in the blocks of its range, which defaults to the
+    /// current block.
+    async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Self::Error> {
+        self.provider()
+            .get_logs(filter)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
     }
 
     /// Creates a new filter for incoming Ethereum logs based on certain
-    /// criteria.
+    /// criteria or for new blocks.
     ///
-    /// Currently, this method supports log filters. Other filters like
-    /// `NewBlocks` and `PendingTransactions` are not yet implemented.
+    /// Currently, this method supports log and `NewBlocks` filters. The
+    /// `PendingTransactions` filter is not yet implemented.
     async fn new_filter(&self, filter: FilterKind<'_>) -> Result<ethers::types::U256, Self::Error> {
         let (_method, args) = match filter {
-            FilterKind::NewBlocks => unimplemented!(
-                "Filtering via new `FilterKind::NewBlocks` has not been implemented yet!"
-            ),
+            FilterKind::NewBlocks => return self.new_block_filter(FilterReceiver::Blocks).await,
             FilterKind::PendingTransactions => {
                 unimplemented!("Filtering via `FilterKind::PendingTransactions` has not been implemented yet! 
                 At the current development stage of Arbiter, transactions do not actually sit in a pending state
@@ -463,10 +859,9 @@ impl Middleware for RevmMiddleware {
         hasher.update(serde_json::to_string(&args).map_err(RevmMiddlewareError::Json)?);
         let hash = hasher.finalize();
         let id = ethers::types::U256::from(ethers::types::H256::from_slice(&hash).as_bytes());
-        let (event_sender, event_receiver) =
-            crossbeam_channel::unbounded::<Vec<revm::primitives::Log>>();
-        let filter_receiver = FilterReceiver {
-            filter,
+        let (event_sender, event_receiver) = crossbeam_channel::unbounded::<EmittedLogs>();
+        let filter_receiver = FilterReceiver::Logs {
+            filter: Box::new(filter),
             receiver: event_receiver,
         };
         self.provider()
@@ -498,7 +893,106 @@ impl Middleware for RevmMiddleware {
         filter: &Filter,
     ) -> Result<FilterWatcher<'b, Self::Provider, Log>, Self::Error> {
         let id = self.new_filter(FilterKind::Logs(filter)).await?;
-        Ok(FilterWatcher::new(id, self.provider()).interval(Duration::ZERO))
+        Ok(FilterWatcher::new(id, self.provider()).interval(FILTER_POLL_INTERVAL))
+    }
+
+    /// Starts watching for new blocks, yielding the hash of the
+    /// [`BlockHeader`] of every block the [`Environment`] has moved on from.
+    /// Use [`RevmMiddleware::watch_headers`] to get the headers themselves.
+    async fn watch_blocks(&self) -> Result<FilterWatcher<'_, Self::Provider, H256>, Self::Error> {
+        let id = self.new_filter(FilterKind::NewBlocks).await?;
+        Ok(FilterWatcher::new(id, self.provider()).interval(FILTER_POLL_INTERVAL))
+    }
+
+    /// Subscribes via `eth_subscribe` with the given `params`. Unlike a
+    /// filter, whose changes are polled for, the notifications of a
+    /// subscription are pushed to the stream as the [`Environment`]
+    /// broadcasts them.
+    async fn subscribe<T, R>(
+        &self,
+        params: T,
+    ) -> Result<SubscriptionStream<'_, Self::Provider, R>, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send + Sync,
+        <Self as Middleware>::Provider: PubsubClient,
+    {
+        self.provider()
+            .subscribe(params)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Cancels the subscription with the given `id`, returning whether it
+    /// existed.
+    async fn unsubscribe<T>(&self, id: T) -> Result<bool, Self::Error>
+    where
+        T: Into<ethers::types::U256> + Send + Sync,
+        <Self as Middleware>::Provider: PubsubClient,
+    {
+        self.provider()
+            .unsubscribe(id)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Subscribes to the blocks the [`Environment`] moves on from, which are
+    /// built from their [`BlockHeader`] and carry no transactions.
+    async fn subscribe_blocks(
+        &self,
+    ) -> Result<SubscriptionStream<'_, Self::Provider, Block<H256>>, Self::Error>
+    where
+        <Self as Middleware>::Provider: PubsubClient,
+    {
+        self.provider()
+            .subscribe_blocks()
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Transactions are executed right away rather than sitting in a pending
+    /// state, so this subscription fails with
+    /// [`ProviderError::UnsupportedRPC`].
+    async fn subscribe_pending_txs(
+        &self,
+    ) -> Result<SubscriptionStream<'_, Self::Provider, H256>, Self::Error>
+    where
+        <Self as Middleware>::Provider: PubsubClient,
+    {
+        self.provider()
+            .subscribe_pending_txs()
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Transactions are executed right away rather than sitting in a pending
+    /// state, so this subscription fails with
+    /// [`ProviderError::UnsupportedRPC`].
+    async fn subscribe_full_pending_txs(
+        &self,
+    ) -> Result<SubscriptionStream<'_, Self::Provider, Transaction>, Self::Error>
+    where
+        <Self as Middleware>::Provider: PubsubClient,
+    {
+        self.provider()
+            .subscribe_full_pending_txs()
+            .await
+            .map_err(RevmMiddlewareError::Provider)
+    }
+
+    /// Subscribes to the logs that match `filter`, which are pushed to the
+    /// stream as the transactions emitting them are executed.
+    async fn subscribe_logs<'a>(
+        &'a self,
+        filter: &Filter,
+    ) -> Result<SubscriptionStream<'a, Self::Provider, Log>, Self::Error>
+    where
+        <Self as Middleware>::Provider: PubsubClient,
+    {
+        self.provider()
+            .subscribe_logs(filter)
+            .await
+            .map_err(RevmMiddlewareError::Provider)
     }
 }
 
@@ -507,8 +1001,8 @@ impl Middleware for RevmMiddleware {
 #[derive(Debug)]
 pub struct Connection {
     /// Used to send calls and transactions to the [`Environment`] to be
-    /// executed by `revm`.
-    tx_sender: TxSender,
+    /// executed by `revm` as well as queries of its state.
+    instruction_sender: InstructionSender,
 
     /// Used to send results back to a client that made a call/transaction with
     /// the [`Environment`]. This [`ResultSender`] is passed along with a
@@ -528,6 +1022,12 @@ pub struct Connection {
     /// generated by `revm` and output by the [`Environment`].
     filter_receivers: Arc<tokio::sync::Mutex<HashMap<ethers::types::U256, FilterReceiver>>>,
 
+    /// The subscriptions made via `eth_subscribe`, keyed by their ID.
+    subscriptions: Arc<Mutex<HashMap<ethers::types::U256, Subscription>>>,
+
+    /// The [`LogStore`] of the [`Environment`] that past logs are read from.
+    log_store: Arc<Mutex<LogStore>>,
+
     environment_state: Arc<crate::environment::AtomicState>,
 }
 
@@ -536,21 +1036,27 @@ impl JsonRpcClient for Connection {
     type Error = ProviderError;
 
     /// Processes a JSON-RPC request and returns the response.
-    /// Currently only handles the `eth_getFilterChanges` call since this is
-    /// used for polling events emitted from the [`Environment`].
+    /// Handles the `eth_getFilterChanges` call used for polling events emitted
+    /// from the [`Environment`] as well as the `eth_blockNumber`,
+    /// `eth_chainId`, `eth_getBalance`, `eth_getTransactionCount`,
+    /// `eth_getCode` and `eth_getStorageAt` reads of its state, the latter
+    /// four as of a past block number if given, and `eth_getLogs` reads of
+    /// the logs it has stored. `eth_subscribe` to `logs` or `newHeads` and
+    /// `eth_unsubscribe` manage the subscriptions streamed via
+    /// [`PubsubClient`]. Any other method fails with
+    /// [`ProviderError::UnsupportedRPC`].
     async fn request<T: Serialize + Send + Sync, R: DeserializeOwned>(
         &self,
         method: &str,
         params: T,
     ) -> Result<R, ProviderError> {
+        // Convert the params `T` into a JSON `Value` to read them
+        let params = serde_json::to_value(&params)?;
         match method {
             "eth_getFilterChanges" => {
-                // Get the `Filter` ID from the params `T`
-                // First convert it into a JSON `Value`
-                let value = serde_json::to_value(&params)?;
-
-                // Take this value as an array then cast it to a string
-                let str = value.as_array().ok_or(ProviderError::CustomError(
+                // Get the `Filter` ID from the params: take them as an array then cast the
+                // first one to a string
+                let str = params.as_array().ok_or(ProviderError::CustomError(
                     "The params value passed to the `Connection` via a `request` was empty. 
                     This is likely due to not specifying a specific `Filter` ID!".to_string()
                 ))?[0]
@@ -564,7 +1070,8 @@ impl JsonRpcClient for Connection {
                         format!("The `str` representation of the filter ID could not be cast into `U256` due to: {:?}!", 
                         e)))?;
 
-                // Get the corresponding `filter_receiver` and await for logs to appear.
+                // Get the corresponding `filter_receiver` and take what it has received
+                // since the last poll without waiting for more.
                 let mut filter_receivers = self.filter_receivers.lock().await;
                 let filter_receiver =
                     filter_receivers
@@ -573,54 +1080,574 @@ impl JsonRpcClient for Connection {
                             "The filter ID does not seem to match any that this client owns!"
                                 .to_string(),
                         ))?;
-                let mut logs = vec![];
-                let filtered_params = FilteredParams::new(Some(filter_receiver.filter.clone()));
-                if let Ok(received_logs) = filter_receiver.receiver.recv() {
-                    let ethers_logs = revm_logs_to_ethers_logs(received_logs);
-                    for log in ethers_logs {
-                        if filtered_params.filter_address(&log)
-                            && filtered_params.filter_topics(&log)
-                        {
-                            logs.push(log);
-                        }
+                let (filter, receiver) = match filter_receiver {
+                    FilterReceiver::Logs { filter, receiver } => (filter, receiver),
+                    FilterReceiver::Blocks(receiver) => {
+                        let hashes: Vec<H256> = next_headers(receiver)
+                            .iter()
+                            .map(BlockHeader::hash)
+                            .collect();
+                        return respond(hashes);
                     }
-                }
+                    FilterReceiver::Headers(receiver) => return respond(next_headers(receiver)),
+                };
+                let filtered_params = FilteredParams::new(Some(filter.as_ref().clone()));
+                let logs: Vec<Log> = receiver
+                    .try_iter()
+                    .flat_map(|emitted_logs| filter_logs(&filtered_params, emitted_logs))
+                    .collect();
                 // Take the logs and Stringify then JSONify to cast into `R`.
                 let logs_str = serde_json::to_string(&logs)?;
                 let logs_deserializeowned: R = serde_json::from_str(&logs_str)?;
                 return Ok(logs_deserializeowned);
             }
+            "eth_blockNumber" => {
+                let block_number = self
+                    .query(EnvironmentQuery::BlockNumber, None)?
+                    .into_uint()?;
+                respond(
+                    convert_uint_to_u64(block_number)
+                        .map_err(|e| ProviderError::CustomError(e.to_string()))?,
+                )
+            }
+            "eth_chainId" => respond(recast_uint(
+                self.query(EnvironmentQuery::ChainId, None)?.into_uint()?,
+            )),
+            "eth_getBalance" => {
+                let address: Address = param(&params, 0)?;
+                let block_number = block_param(&params, 1)?;
+                respond(recast_uint(
+                    self.query(EnvironmentQuery::Balance(B160::from(address)), block_number)?
+                        .into_uint()?,
+                ))
+            }
+            "eth_getTransactionCount" => {
+                let address: Address = param(&params, 0)?;
+                let block_number = block_param(&params, 1)?;
+                respond(recast_uint(
+                    self.query(
+                        EnvironmentQuery::TransactionCount(B160::from(address)),
+                        block_number,
+                    )?
+                    .into_uint()?,
+                ))
+            }
+            "eth_getCode" => {
+                let address: Address = param(&params, 0)?;
+                let block_number = block_param(&params, 1)?;
+                match self.query(EnvironmentQuery::Code(B160::from(address)), block_number)? {
+                    QueryOutcome::Bytes(code) => respond(Bytes::from(code)),
+                    outcome => Err(unexpected(outcome)),
+                }
+            }
+            "eth_getStorageAt" => {
+                let address: Address = param(&params, 0)?;
+                let slot: ethers::types::U256 = param(&params, 1)?;
+                let block_number = block_param(&params, 2)?;
+                let value = self
+                    .query(
+                        EnvironmentQuery::Storage(B160::from(address), recast_ethers_uint(slot)),
+                        block_number,
+                    )?
+                    .into_uint()?;
+                respond(H256::from(value.to_be_bytes::<32>()))
+            }
+            "eth_getLogs" => {
+                let filter: Filter = param(&params, 0)?;
+                let current = convert_uint_to_u64(
+                    self.query(EnvironmentQuery::BlockNumber, None)?
+                        .into_uint()?,
+                )
+                .map_err(|e| ProviderError::CustomError(e.to_string()))?;
+                // Like `eth_getLogs`, a range without bounds covers the current block
+                let (from, to) = match filter.block_option {
+                    FilterBlockOption::Range {
+                        from_block,
+                        to_block,
+                    } => (
+                        range_bound(from_block, current),
+                        range_bound(to_block, current),
+                    ),
+                    FilterBlockOption::AtBlockHash(hash) => {
+                        return Err(ProviderError::CustomError(format!(
+                            "Blocks can only be referred to by number, not by hash {:?}!",
+                            hash
+                        )))
+                    }
+                };
+                let logs = self
+                    .log_store
+                    .lock()
+                    .map_err(|e| {
+                        ProviderError::CustomError(format!(
+                            "Failed to gain lock on the `LogStore` due to: {:?}",
+                            e
+                        ))
+                    })?
+                    .query(&filter, from, to);
+                respond(logs)
+            }
+            "eth_subscribe" => {
+                let kind: String = param(&params, 0)?;
+                // The last header finished before subscribing is the parent of
+                // the first one the subscription receives
+                let mut parent_hash = H256::zero();
+                let filter_receiver = match kind.as_str() {
+                    "logs" => {
+                        let filter: Filter = param(&params, 1)?;
+                        let (event_sender, event_receiver) =
+                            crossbeam_channel::unbounded::<EmittedLogs>();
+                        self.event_broadcaster()?.add_sender(event_sender);
+                        FilterReceiver::Logs {
+                            filter: Box::new(filter),
+                            receiver: event_receiver,
+                        }
+                    }
+                    "newHeads" => {
+                        let (header_sender, header_receiver) =
+                            crossbeam_channel::unbounded::<BlockHeader>();
+                        let mut event_broadcaster = self.event_broadcaster()?;
+                        event_broadcaster.add_header_sender(header_sender);
+                        if let Some(header) = event_broadcaster.last_header() {
+                            parent_hash = header.hash();
+                        }
+                        FilterReceiver::Blocks(header_receiver)
+                    }
+                    _ => {
+                        log::warn!(
+                            "Subscribing to `{}` is not supported by the `Connection`",
+                            kind
+                        );
+                        return Err(ProviderError::UnsupportedRPC);
+                    }
+                };
+                let id = ethers::types::U256::from(rand::random::<u128>());
+                self.subscriptions()?.insert(
+                    id,
+                    Subscription::Pending {
+                        filter_receiver,
+                        parent_hash,
+                    },
+                );
+                let subscriptions = Arc::downgrade(&self.subscriptions);
+                // Drop the subscription if it is still not streamed by then, even if
+                // the subscriptions are not looked at again
+                thread::spawn(move || {
+                    thread::sleep(PENDING_SUBSCRIPTION_TIMEOUT);
+                    if let Some(subscriptions) = subscriptions.upgrade() {
+                        let mut subscriptions =
+                            subscriptions.lock().unwrap_or_else(PoisonError::into_inner);
+                        if let Some(Subscription::Pending { .. }) = subscriptions.get(&id) {
+                            subscriptions.remove(&id);
+                        }
+                    }
+                });
+                respond(id)
+            }
+            "eth_unsubscribe" => {
+                let id: ethers::types::U256 = param(&params, 0)?;
+                respond(self.subscriptions()?.remove(&id).is_some())
+            }
             _ => {
-                unimplemented!("We don't cover this case yet.")
-            } // TODO: This can probably be avoided somehow
+                log::warn!(
+                    "The method `{}` is not supported by the `Connection`",
+                    method
+                );
+                Err(ProviderError::UnsupportedRPC)
+            }
+        }
+    }
+}
+
+impl PubsubClient for Connection {
+    type NotificationStream = mpsc::UnboundedReceiver<Box<RawValue>>;
+
+    /// Starts streaming the notifications of the subscription `id` made via
+    /// `eth_subscribe`. A thread forwards them from the [`EventBroadcaster`]
+    /// until the subscription is cancelled. Subscriptions that were not
+    /// streamed within five seconds of being made are gone.
+    fn subscribe<T: Into<ethers::types::U256>>(
+        &self,
+        id: T,
+    ) -> Result<Self::NotificationStream, ProviderError> {
+        let id = id.into();
+        let mut subscriptions = self.subscriptions()?;
+        let (filter_receiver, parent_hash) = match subscriptions.remove(&id) {
+            Some(Subscription::Pending {
+                filter_receiver,
+                parent_hash,
+            }) => (filter_receiver, parent_hash),
+            Some(streaming) => {
+                subscriptions.insert(id, streaming);
+                return Err(ProviderError::CustomError(format!(
+                    "The subscription {:?} is already being streamed!",
+                    id
+                )));
+            }
+            None => {
+                return Err(ProviderError::CustomError(
+                    "The subscription ID does not seem to match any that this client owns!"
+                        .to_string(),
+                ))
+            }
+        };
+        let (notification_sender, notification_stream) = mpsc::unbounded();
+        let (stop_sender, stop_receiver) = crossbeam_channel::bounded(0);
+        thread::spawn(move || {
+            forward_notifications(
+                filter_receiver,
+                parent_hash,
+                notification_sender,
+                stop_receiver,
+            )
+        });
+        subscriptions.insert(id, Subscription::Streaming { _stop: stop_sender });
+        Ok(notification_stream)
+    }
+
+    /// Cancels the subscription `id`, which stops its forwarding thread.
+    fn unsubscribe<T: Into<ethers::types::U256>>(&self, id: T) -> Result<(), ProviderError> {
+        self.subscriptions()?.remove(&id.into());
+        Ok(())
+    }
+}
+
+impl Connection {
+    /// Sends an [`EnvironmentQuery`] to the [`Environment`] and waits for its
+    /// answer, which is read as of the end of `block_number` if given.
+    pub(crate) fn query(
+        &self,
+        query: EnvironmentQuery,
+        block_number: Option<u64>,
+    ) -> Result<QueryOutcome, ProviderError> {
+        self.instruct(|sender| Instruction::Query(query, block_number, sender))
+    }
+
+    /// Locks the [`EventBroadcaster`] to take more receivers of the broadcast
+    /// from it.
+    fn event_broadcaster(&self) -> Result<MutexGuard<'_, EventBroadcaster>, ProviderError> {
+        self.event_broadcaster.lock().map_err(|e| {
+            ProviderError::CustomError(format!(
+                "Failed to gain lock on the `Connection`'s `event_broadcaster` due to {:?} ",
+                e
+            ))
+        })
+    }
+
+    /// Locks the subscriptions made via `eth_subscribe`.
+    fn subscriptions(
+        &self,
+    ) -> Result<MutexGuard<'_, HashMap<ethers::types::U256, Subscription>>, ProviderError> {
+        self.subscriptions.lock().map_err(|e| {
+            ProviderError::CustomError(format!(
+                "Failed to gain lock on the `Connection`'s `subscriptions` due to {:?} ",
+                e
+            ))
+        })
+    }
+
+    /// Sends the [`Instruction`] built around a fresh [`QuerySender`] to the
+    /// [`Environment`] and waits for its answer.
+    pub(crate) fn instruct(
+        &self,
+        instruction: impl FnOnce(QuerySender) -> Instruction,
+    ) -> Result<QueryOutcome, ProviderError> {
+        let (query_sender, query_receiver) = crossbeam_channel::bounded(1);
+        self.instruction_sender
+            .send(instruction(query_sender))
+            .map_err(|e| {
+                ProviderError::CustomError(format!(
+                    "Failed to send the instruction to the `Environment` due to: {:?}",
+                    e
+                ))
+            })?;
+        query_receiver
+            .recv()
+            .map_err(|e| {
+                ProviderError::CustomError(format!(
+                    "Failed to receive the answer of the `Environment` due to: {:?}",
+                    e
+                ))
+            })?
+            .map_err(|e| ProviderError::CustomError(e.to_string()))
+    }
+}
+
+impl QueryOutcome {
+    /// Takes the number out of a [`QueryOutcome::Uint`].
+    pub(crate) fn into_uint(self) -> Result<U256, ProviderError> {
+        match self {
+            QueryOutcome::Uint(value) => Ok(value),
+            outcome => Err(unexpected(outcome)),
         }
     }
 }
 
-/// Packages together a [`crossbeam_channel::Receiver<Vec<Log>>`] along with a
-/// [`Filter`] for events. Allows the client to have a stream of filtered
-/// events.
+/// The error for an [`Environment`] answering a query with the wrong kind of
+/// [`QueryOutcome`].
+fn unexpected(outcome: QueryOutcome) -> ProviderError {
+    ProviderError::CustomError(format!(
+        "The `Environment` answered the query with an unexpected {:?}!",
+        outcome
+    ))
+}
+
+/// Deserializes the parameter at `index` of the JSON `params` of a request.
+fn param<D: DeserializeOwned>(
+    params: &serde_json::Value,
+    index: usize,
+) -> Result<D, ProviderError> {
+    let param = params
+        .get(index)
+        .ok_or(ProviderError::CustomError(format!(
+            "The params value passed to the `Connection` via a `request` is missing the parameter at index {}!",
+            index
+        )))?;
+    Ok(serde_json::from_value(param.clone())?)
+}
+
+/// Reads the optional block parameter at `index` of the JSON `params` of a
+/// request as the number of the block to read the state at, where `None`
+/// stands for the current state.
+fn block_param(params: &serde_json::Value, index: usize) -> Result<Option<u64>, ProviderError> {
+    match params.get(index) {
+        Some(block) => Ok(past_block_number(serde_json::from_value(block.clone())?)),
+        None => Ok(None),
+    }
+}
+
+/// The number of the past block a [`BlockNumber`] refers to, where `None`
+/// stands for the current state.
+pub(crate) fn past_block_number(block: BlockNumber) -> Option<u64> {
+    match block {
+        BlockNumber::Number(number) => Some(number.as_u64()),
+        BlockNumber::Earliest => Some(0),
+        _ => None,
+    }
+}
+
+/// The number of the block a bound of a block range refers to, where the
+/// current block stands in for any but a block number.
+fn range_bound(bound: Option<BlockNumber>, current: U64) -> U64 {
+    match bound.and_then(past_block_number) {
+        Some(number) => number.into(),
+        None => current,
+    }
+}
+
+/// The number of the block to make a call at given by a [`BlockId`], where
+/// `None` stands for the current state. Blocks cannot be referred to by hash.
+fn block_number(block: Option<BlockId>) -> Result<Option<u64>, RevmMiddlewareError> {
+    match block {
+        Some(BlockId::Number(block)) => Ok(past_block_number(block)),
+        Some(BlockId::Hash(hash)) => Err(RevmMiddlewareError::Conversion(format!(
+            "blocks can only be referred to by number, not by hash {:?}",
+            hash
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Builds the [`TxEnv`] that `revm` executes for a transaction sent by
+/// `caller`, honouring the `value`, `nonce` and `gas` of the transaction.
+/// Without a `nonce`, the transaction uses the current nonce of the caller and
+/// without `gas`, it may use as much gas as it likes.
+pub(crate) fn tx_env(caller: Address, tx: &TypedTransaction) -> Result<TxEnv, RevmMiddlewareError> {
+    // Check the `to` field of the transaction to determine if it is a call or a
+    // deploy. If there is no `to` field, then it is a `Deploy` else it is a
+    // `Call`.
+    let transact_to = match tx.to_addr() {
+        Some(to) => TransactTo::Call(B160::from(*to)),
+        None => TransactTo::Create(CreateScheme::Create),
+    };
+    let nonce = tx
+        .nonce()
+        .map(|nonce| u64::try_from(*nonce))
+        .transpose()
+        .map_err(|e| RevmMiddlewareError::Conversion(e.to_string()))?;
+    Ok(TxEnv {
+        caller: B160::from(caller),
+        gas_limit: tx
+            .gas()
+            .map(|gas| u64::try_from(*gas).unwrap_or(u64::MAX))
+            .unwrap_or(u64::MAX),
+        gas_price: U256::ZERO,
+        gas_priority_fee: None,
+        transact_to,
+        value: tx
+            .value()
+            .map(|value| recast_ethers_uint(*value))
+            .unwrap_or_default(),
+        // Plain ETH transfers come without data
+        data: bytes::Bytes::from(tx.data().map(|data| data.to_vec()).unwrap_or_default()),
+        chain_id: None,
+        nonce,
+        access_list: Vec::new(),
+    })
+}
+
+/// Casts the answer to a request into the type `R` expected by the caller by
+/// going through JSON.
+fn respond<S: Serialize, R: DeserializeOwned>(response: S) -> Result<R, ProviderError> {
+    Ok(serde_json::from_value(serde_json::to_value(response)?)?)
+}
+
+/// Takes the [`BlockHeader`]s of the blocks finished since the last poll.
+fn next_headers(receiver: &crossbeam_channel::Receiver<BlockHeader>) -> Vec<BlockHeader> {
+    receiver.try_iter().collect()
+}
+
+/// What a filter installed by a client receives from the
+/// [`EventBroadcaster`]. Allows the client to have a stream of filtered events
+/// or new blocks.
 #[derive(Debug)]
-pub(crate) struct FilterReceiver {
-    /// The filter definition used for this receiver.
-    /// Comes from the `ethers-rs` crate.
-    pub(crate) filter: Filter,
+pub(crate) enum FilterReceiver {
+    /// Packages together a [`crossbeam_channel::Receiver<EmittedLogs>`] along
+    /// with a [`Filter`] for events.
+    Logs {
+        /// The filter definition used for this receiver.
+        /// Comes from the `ethers-rs` crate.
+        filter: Box<Filter>,
+
+        /// The receiver for the channel that receives logs from the
+        /// broadcaster. These are filtered upon reception.
+        receiver: crossbeam_channel::Receiver<EmittedLogs>,
+    },
+
+    /// Receives the [`BlockHeader`]s of finished blocks, which are handed out
+    /// as their hashes.
+    Blocks(crossbeam_channel::Receiver<BlockHeader>),
+
+    /// Receives the [`BlockHeader`]s of finished blocks, which are handed out
+    /// whole.
+    Headers(crossbeam_channel::Receiver<BlockHeader>),
+}
+
+/// A subscription made via `eth_subscribe` on a [`Connection`].
+#[derive(Debug)]
+pub(crate) enum Subscription {
+    /// Receives from the [`EventBroadcaster`] until it is streamed or, after
+    /// [`PENDING_SUBSCRIPTION_TIMEOUT`], dropped.
+    Pending {
+        /// Receives what the subscription is notified of.
+        filter_receiver: FilterReceiver,
+
+        /// The hash of the last block finished before subscribing.
+        parent_hash: H256,
+    },
+
+    /// Streamed by a thread that forwards notifications.
+    Streaming {
+        /// Stops the thread once dropped.
+        _stop: crossbeam_channel::Sender<()>,
+    },
+}
+
+/// Forwards what `filter_receiver` gets from the [`EventBroadcaster`] as
+/// notifications of a subscription, logs one by one and headers as
+/// [`Block`]s, the first of which has `parent_hash` as its parent. Stops once
+/// the stream or `stop` is dropped.
+fn forward_notifications(
+    filter_receiver: FilterReceiver,
+    mut parent_hash: H256,
+    notifications: mpsc::UnboundedSender<Box<RawValue>>,
+    stop: crossbeam_channel::Receiver<()>,
+) {
+    match filter_receiver {
+        FilterReceiver::Logs { filter, receiver } => {
+            let filtered_params = FilteredParams::new(Some(*filter));
+            loop {
+                crossbeam_channel::select! {
+                    recv(receiver) -> emitted_logs => match emitted_logs {
+                        Ok(emitted_logs) => {
+                            for log in filter_logs(&filtered_params, emitted_logs) {
+                                if !notify(&notifications, &log) {
+                                    return;
+                                }
+                            }
+                        }
+                        Err(_) => return,
+                    },
+                    recv(stop) -> _ => return,
+                }
+            }
+        }
+        FilterReceiver::Blocks(receiver) | FilterReceiver::Headers(receiver) => {
+            // Headers arrive in order, so each is the parent of the next
+            loop {
+                crossbeam_channel::select! {
+                    recv(receiver) -> header => match header {
+                        Ok(header) => {
+                            let block = Block {
+                                parent_hash,
+                                ..Block::from(&header)
+                            };
+                            if !notify(&notifications, &block) {
+                                return;
+                            }
+                            parent_hash = header.hash();
+                        }
+                        Err(_) => return,
+                    },
+                    recv(stop) -> _ => return,
+                }
+            }
+        }
+    }
+}
 
-    /// The receiver for the channel that receives logs from the broadcaster.
-    /// These are filtered upon reception.
-    pub(crate) receiver: crossbeam_channel::Receiver<Vec<revm::primitives::Log>>,
+/// Pushes a `notification` to the stream of a subscription, returning whether
+/// the stream is still open.
+fn notify<S: Serialize>(
+    notifications: &mpsc::UnboundedSender<Box<RawValue>>,
+    notification: &S,
+) -> bool {
+    match serde_json::value::to_raw_value(notification) {
+        Ok(notification) => notifications.unbounded_send(notification).is_ok(),
+        Err(e) => {
+            log::error!("Failed to serialize a notification due to {:?}", e);
+            false
+        }
+    }
+}
+
+/// The logs among `emitted_logs` that pass `filtered_params`, along with the
+/// number of the block they were emitted in.
+fn filter_logs(filtered_params: &FilteredParams, emitted_logs: EmittedLogs) -> Vec<Log> {
+    revm_logs_to_ethers_logs(emitted_logs.logs)
+        .into_iter()
+        .map(|mut log| {
+            log.block_number = Some(emitted_logs.block_number);
+            log
+        })
+        .filter(|log| filtered_params.filter_address(log) && filtered_params.filter_topics(log))
+        .collect()
 }
 
 /// Contains the result of a successful transaction execution.
 #[derive(Debug)]
 struct Success {
     _reason: revm::primitives::Eval,
-    _gas_used: u64,
+    gas_used: u64,
     _gas_refunded: u64,
     logs: Vec<ethers::types::Log>,
     output: Output,
 }
 
+/// Converts the error an [`Environment`] sent back instead of executing a
+/// call or transaction.
+fn environment_error(err: EnvironmentError) -> RevmMiddlewareError {
+    match err {
+        EnvironmentError::Execution(EVMError::Transaction(invalid)) => {
+            RevmMiddlewareError::InvalidTransaction(format!("{:?}", invalid))
+        }
+        err => RevmMiddlewareError::Receive(format!(
+            "Error recieving response from the environement with environment error: {}",
+            err
+        )),
+    }
+}
+
 /// Unpacks the result of the EVM execution.
 ///
 /// This function converts the raw execution result from the EVM into a more
@@ -640,7 +1667,7 @@ fn unpack_execution_result(
             let logs = revm_logs_to_ethers_logs(logs);
             Ok(Success {
                 _reason: reason,
-                _gas_used: gas_used,
+                gas_used,
                 _gas_refunded: gas_refunded,
                 logs,
                 output,
@@ -661,12 +1688,28 @@ fn unpack_execution_result(
 /// address types. The provided address type from Revm is transformed into the
 /// corresponding type used in the Ethers library.
 #[inline]
-fn recast_address(address: B160) -> Address {
+pub(crate) fn recast_address(address: B160) -> Address {
     // This unwrap should never fail as the `B160` will always cast into `[u8; 20]`.
     let temp: [u8; 20] = address.as_bytes().try_into().unwrap();
     Address::from(temp)
 }
 
+/// Converts the 256-bit unsigned integer type used by `revm` to the one used
+/// by `ethers-rs`.
+#[inline]
+pub(crate) fn recast_uint(input: U256) -> ethers::types::U256 {
+    ethers::types::U256::from_big_endian(&input.to_be_bytes::<32>())
+}
+
+/// Converts the 256-bit unsigned integer type used by `ethers-rs` to the one
+/// used by `revm`.
+#[inline]
+pub(crate) fn recast_ethers_uint(input: ethers::types::U256) -> U256 {
+    let mut bytes = [0_u8; 32];
+    input.to_big_endian(&mut bytes);
+    U256::from_be_bytes(bytes)
+}
+
 /// Converts the 256-bit byte array type used by `revm` to the one used by
 /// `ethers-rs`.
 ///
@@ -686,7 +1729,7 @@ fn recast_b256(input: revm::primitives::B256) -> ethers::types::H256 {
 /// converts each log entry to the corresponding format used by the `ethers-rs`
 /// library.
 #[inline]
-fn revm_logs_to_ethers_logs(
+pub(crate) fn revm_logs_to_ethers_logs(
     revm_logs: Vec<revm::primitives::Log>,
 ) -> Vec<ethers::core::types::Log> {
     let mut logs: Vec<ethers::core::types::Log> = vec![];
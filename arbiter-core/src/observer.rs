@@ -0,0 +1,183 @@
+//! The `observer` module provides a way to sample contract views of an
+//! [`Environment`] as it moves from block to block.
+//!
+//! Many quantities of interest, e.g., the price of a `LiquidExchange` or the
+//! token balances of each agent, are not emitted as events. An [`Observer`]
+//! is a read-only call that the [`Environment`] makes itself at the end of
+//! every block (or every `n`th block) against the committed state. The results
+//! are kept as a time series of [`Observation`]s that can be inspected or
+//! exported via the [`Manager`]. Since the calls are made on the
+//! [`Environment`]'s own thread, they neither count towards the size of a block
+//! nor interleave with the transactions sent through its socket.
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use ethers::{
+    abi::{Function, Token},
+    types::{Address, U64},
+};
+use log::warn;
+use revm::{
+    db::{CacheDB, EmptyDB},
+    primitives::{ExecutionResult, Output, TransactTo, TxEnv, B160, U256},
+    EVM,
+};
+
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::{environment::Environment, manager::Manager};
+use crate::{
+    environment::{convert_uint_to_u64, EnvironmentError},
+    recorder::{escape_csv, format_token},
+};
+
+/// Alias for the observers of an [`Environment`] keyed by their labels.
+pub(crate) type Observers = Arc<Mutex<BTreeMap<String, Observer>>>;
+
+/// The result of an [`Observer`]'s call at the end of a block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// The number of the block the call was made at the end of.
+    pub block_number: U64,
+
+    /// The timestamp of the block the call was made at the end of.
+    pub block_timestamp: U64,
+
+    /// The decoded return values of the call.
+    pub values: Vec<Token>,
+}
+
+impl Observation {
+    /// Formats the return values of the call as strings. Integers are given in
+    /// decimal and addresses and bytes as `0x` prefixed hex.
+    pub fn formatted_values(&self) -> Vec<String> {
+        self.values.iter().map(format_token).collect()
+    }
+}
+
+/// A read-only call made by an [`Environment`] at the end of every `every`
+/// blocks along with the [`Observation`]s made so far.
+#[derive(Debug, Clone)]
+pub struct Observer {
+    /// The contract that is called.
+    pub to: Address,
+
+    /// The ABI of the called function, used to decode its return values.
+    pub function: Function,
+
+    /// The calldata of the call.
+    pub data: ethers::types::Bytes,
+
+    /// The call is made at the end of every block whose number is a multiple
+    /// of `every`.
+    pub every: u64,
+
+    /// The [`Observation`]s made so far in order of their block.
+    pub observations: Vec<Observation>,
+}
+
+/// Makes the calls of every due [`Observer`] against the committed state of
+/// the `evm` and records their results. Called by the [`Environment`] at the
+/// end of every block before moving on to the next.
+pub(crate) fn observe(
+    evm: &mut EVM<CacheDB<EmptyDB>>,
+    observers: &Observers,
+    label: &str,
+) -> Result<(), EnvironmentError> {
+    let mut observers = observers
+        .lock()
+        .map_err(|e| EnvironmentError::Communication(format!("{:?}", e)))?;
+    if observers.is_empty() {
+        return Ok(());
+    }
+    let block_number = convert_uint_to_u64(evm.env.block.number)
+        .map_err(|e| EnvironmentError::Conversion(format!("{:?}", e)))?;
+    let block_timestamp = convert_uint_to_u64(evm.env.block.timestamp)
+        .map_err(|e| EnvironmentError::Conversion(format!("{:?}", e)))?;
+    for (observer_label, observer) in observers.iter_mut() {
+        if block_number.as_u64() % observer.every != 0 {
+            continue;
+        }
+        evm.env.tx = TxEnv {
+            caller: B160::zero(),
+            gas_limit: u64::MAX,
+            gas_price: U256::ZERO,
+            gas_priority_fee: None,
+            transact_to: TransactTo::Call(B160::from(observer.to.to_fixed_bytes())),
+            value: U256::ZERO,
+            data: bytes::Bytes::from(observer.data.to_vec()),
+            chain_id: None,
+            nonce: None,
+            access_list: Vec::new(),
+        };
+        let output = match evm.transact() {
+            Ok(result_and_state) => match result_and_state.result {
+                ExecutionResult::Success {
+                    output: Output::Call(output),
+                    ..
+                } => output,
+                result => {
+                    warn!(
+                        "Observer {} of the environment labeled {} failed in block {}: {:?}",
+                        observer_label, label, block_number, result
+                    );
+                    continue;
+                }
+            },
+            // A failed call must not take the environment down with it, so the
+            // observer just misses this block
+            Err(e) => {
+                warn!(
+                    "Observer {} of the environment labeled {} could not be executed in block {}: {:?}",
+                    observer_label, label, block_number, e
+                );
+                continue;
+            }
+        };
+        match observer.function.decode_output(&output) {
+            Ok(values) => observer.observations.push(Observation {
+                block_number,
+                block_timestamp,
+                values,
+            }),
+            Err(e) => warn!(
+                "Observer {} of the environment labeled {} returned undecodable output in block {}: {:?}",
+                observer_label, label, block_number, e
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Writes the [`Observation`]s of every [`Observer`] as rows of a CSV file
+/// with the columns `observer,block_number,block_timestamp,values`. The
+/// formatted return values of a call are joined into a single column.
+pub(crate) fn write_csv<P: AsRef<Path>>(
+    path: P,
+    observers: &BTreeMap<String, Observer>,
+) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "observer,block_number,block_timestamp,values")?;
+    for (label, observer) in observers {
+        for observation in &observer.observations {
+            writeln!(
+                writer,
+                "{},{},{},{}",
+                escape_csv(label),
+                observation.block_number,
+                observation.block_timestamp,
+                escape_csv(&observation.formatted_values().join(", "))
+            )?;
+        }
+    }
+    writer.flush()
+}
@@ -0,0 +1,279 @@
+//! The `price_driver` module provides the [`PriceDriver`], an [`Agent`] that
+//! walks a contract's price along a price path as the [`Environment`] moves
+//! from block to block.
+//!
+//! Prices are usually sampled from a seeded [`PriceProcess`] so that a
+//! simulation is reproducible, but any path of prices may be given. The prices
+//! are pushed as WADs through a setter taking a single `uint256`, which is
+//! `setPrice` for a [`LiquidExchange`]. By default, a price is pushed at every
+//! block, but the [`Schedule`] of a driver may also space them out by blocks
+//! or by the time steps of the path.
+//!
+//! Setters are usually restricted to an admin. Since the [`RevmMiddleware`] of
+//! an agent is seeded by the agent's label, a contract can be deployed by a
+//! client created with the same label as the [`PriceDriver`] so that the
+//! driver is allowed to set its price.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use arbiter_core::{
+//!     bindings::liquid_exchange::LiquidExchange,
+//!     math::{float_to_wad, PriceProcess, TimeGrid},
+//!     middleware::RevmMiddleware,
+//!     price_driver::{PriceDriver, Schedule},
+//! };
+//! # use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+//! # use ethers::types::Address;
+//! # use std::sync::Arc;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let mut manager = Manager::new();
+//! # let params = EnvironmentParameters { block_rate: 1.0, seed: 1 };
+//! # manager.add_environment("example_env", params)?;
+//! # manager.start_environment("example_env")?;
+//! # let (token_x, token_y) = (Address::random(), Address::random());
+//! let admin = Arc::new(RevmMiddleware::new(
+//!     manager.environments.get("example_env").unwrap(),
+//!     Some("price_driver".to_string()),
+//! ));
+//! let liquid_exchange = LiquidExchange::deploy(admin, (token_x, token_y, float_to_wad(1000.0)))?
+//!     .send()
+//!     .await?;
+//!
+//! let process = PriceProcess::GeometricBrownianMotion {
+//!     drift: 0.0,
+//!     volatility: 0.5,
+//! };
+//! let path = process.sample(1000.0, TimeGrid::new(0.0, 1.0, 365)?, 1)?;
+//! let mut driver = PriceDriver::liquid_exchange(liquid_exchange.address(), path.prices.clone());
+//! // Push the daily prices of the path with a block every twelve seconds
+//! driver.schedule = Schedule::time_steps(&path, 12.0 / 86_400.0 / 365.0)?;
+//! manager.add_agent("price_driver", "example_env", driver)?;
+//! # Ok(())
+//! # }
+//! ```
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::{
+    abi::{Function, Token},
+    providers::Middleware,
+    types::{Address, TransactionRequest, U64},
+};
+
+use crate::{
+    agent::{Agent, AgentError},
+    bindings::liquid_exchange::LIQUIDEXCHANGE_ABI,
+    math::{price_to_wad, MathError, PricePath},
+    middleware::RevmMiddleware,
+};
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::{
+    bindings::liquid_exchange::LiquidExchange, environment::Environment, math::PriceProcess,
+};
+
+/// When a [`PriceDriver`] pushes the prices of its path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// One price every given amount of blocks.
+    Blocks(u64),
+
+    /// One price per time step of a path, where every block lasts
+    /// `block_time` units of the path's time. The price at time `t` is due
+    /// once `(t - times[0]) / block_time` blocks have passed since the first
+    /// price was pushed. If several prices are due in the same block, only
+    /// the latest of them is pushed.
+    TimeSteps {
+        /// The points in time of the prices of the path.
+        times: Vec<f64>,
+        /// The amount of the path's time that passes in a block.
+        block_time: f64,
+    },
+}
+
+impl Schedule {
+    /// Constructs a [`Schedule::TimeSteps`] for the prices of `path`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Schedule)` if `block_time` is positive and finite.
+    /// * `Err(MathError::InvalidTimeGrid)` otherwise.
+    pub fn time_steps(path: &PricePath, block_time: f64) -> Result<Self, MathError> {
+        if !(block_time.is_finite() && block_time > 0.0) {
+            return Err(MathError::InvalidTimeGrid(format!(
+                "a block must last a positive amount of time but lasts {}",
+                block_time
+            )));
+        }
+        Ok(Self::TimeSteps {
+            times: path.times.clone(),
+            block_time,
+        })
+    }
+}
+
+/// An [`Agent`] that pushes the prices of a path to a contract according to
+/// its [`Schedule`]. The driver does nothing once the path is exhausted.
+#[derive(Debug, Clone)]
+pub struct PriceDriver {
+    /// The contract whose price is set.
+    pub contract: Address,
+
+    /// The setter of the price, which must take the price as a single WAD
+    /// `uint256`.
+    pub setter: Function,
+
+    /// The prices to push in order.
+    pub path: Vec<f64>,
+
+    /// When the prices are pushed.
+    pub schedule: Schedule,
+
+    /// The index of the next price of the path to push.
+    next: usize,
+
+    /// The block the first price was pushed in.
+    first_push: Option<U64>,
+
+    /// The block the last price was pushed in.
+    last_push: Option<U64>,
+}
+
+impl PriceDriver {
+    /// Constructs a new [`PriceDriver`] that pushes the prices of `path` to
+    /// `contract` through `setter` at every block.
+    pub fn new(contract: Address, setter: Function, path: Vec<f64>) -> Self {
+        Self {
+            contract,
+            setter,
+            path,
+            schedule: Schedule::Blocks(1),
+            next: 0,
+            first_push: None,
+            last_push: None,
+        }
+    }
+
+    /// Constructs a new [`PriceDriver`] that pushes the prices of `path` to the
+    /// [`LiquidExchange`] at `liquid_exchange` at every block.
+    pub fn liquid_exchange(liquid_exchange: Address, path: Vec<f64>) -> Self {
+        // The bindings are generated from the contract, so `setPrice` exists.
+        let setter = LIQUIDEXCHANGE_ABI.function("setPrice").unwrap().clone();
+        Self::new(liquid_exchange, setter, path)
+    }
+
+    /// The index of the price of the path that is due in the block with the
+    /// given number, if any.
+    fn due(&self, block_number: U64) -> Option<usize> {
+        if self.next >= self.path.len() {
+            return None;
+        }
+        match &self.schedule {
+            Schedule::Blocks(every) => match self.last_push {
+                Some(last_push) if block_number < last_push.saturating_add((*every).into()) => None,
+                _ => Some(self.next),
+            },
+            Schedule::TimeSteps { times, block_time } => {
+                let blocks = match self.first_push {
+                    Some(first_push) => block_number.saturating_sub(first_push).as_u64(),
+                    None => 0,
+                };
+                // Allow for the rounding of the times of the path
+                let elapsed = (blocks as f64 + 1e-9) * block_time;
+                (self.next..self.path.len().min(times.len()))
+                    .take_while(|index| times[*index] - times[0] <= elapsed)
+                    .last()
+            }
+        }
+    }
+
+    /// Moves past the price with the given index, which was pushed in the
+    /// block with the given number.
+    fn pushed(&mut self, index: usize, block_number: U64) {
+        self.next = index + 1;
+        self.first_push.get_or_insert(block_number);
+        self.last_push = Some(block_number);
+    }
+}
+
+#[async_trait]
+impl Agent for PriceDriver {
+    async fn on_block(
+        &mut self,
+        client: Arc<RevmMiddleware>,
+        block_number: U64,
+    ) -> Result<(), AgentError> {
+        let index = match self.due(block_number) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let price =
+            price_to_wad(self.path[index]).map_err(|e| AgentError::Custom(e.to_string()))?;
+        let data = self
+            .setter
+            .encode_input(&[Token::Uint(price)])
+            .map_err(|e| AgentError::Custom(e.to_string()))?;
+        let tx = TransactionRequest::new().to(self.contract).data(data);
+        client.send_transaction(tx, None).await?.await?;
+        self.pushed(index, block_number);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The blocks in which the prices of the driver are pushed along with the
+    /// index of the pushed price.
+    fn pushes(driver: &mut PriceDriver, blocks: std::ops::Range<u64>) -> Vec<(u64, usize)> {
+        let mut pushes = vec![];
+        for block_number in blocks {
+            if let Some(index) = driver.due(block_number.into()) {
+                driver.pushed(index, block_number.into());
+                pushes.push((block_number, index));
+            }
+        }
+        pushes
+    }
+
+    #[test]
+    fn schedules() {
+        let path = PricePath {
+            times: vec![0.0, 0.01, 0.02, 0.03],
+            prices: vec![1.0, 2.0, 3.0, 4.0],
+        };
+        let mut driver = PriceDriver::liquid_exchange(Address::zero(), path.prices.clone());
+        driver.schedule = Schedule::Blocks(3);
+        assert_eq!(
+            pushes(&mut driver, 5..20),
+            vec![(5, 0), (8, 1), (11, 2), (14, 3)]
+        );
+
+        // The next block never comes due rather than overflowing
+        let mut driver = PriceDriver::liquid_exchange(Address::zero(), path.prices.clone());
+        driver.schedule = Schedule::Blocks(u64::MAX);
+        assert_eq!(pushes(&mut driver, 5..20), vec![(5, 0)]);
+
+        // A block lasts half a time step, so a price is due every other block
+        let mut driver = PriceDriver::liquid_exchange(Address::zero(), path.prices.clone());
+        driver.schedule = Schedule::time_steps(&path, 0.005).unwrap();
+        assert_eq!(
+            pushes(&mut driver, 5..20),
+            vec![(5, 0), (7, 1), (9, 2), (11, 3)]
+        );
+
+        // A block lasts two and a half time steps, so prices are skipped
+        let mut driver = PriceDriver::liquid_exchange(Address::zero(), path.prices.clone());
+        driver.schedule = Schedule::time_steps(&path, 0.025).unwrap();
+        assert_eq!(pushes(&mut driver, 5..20), vec![(5, 0), (6, 2), (7, 3)]);
+
+        assert!(Schedule::time_steps(&path, 0.0).is_err());
+        assert!(Schedule::time_steps(&path, f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,382 @@
+//! The `recorder` module provides a way to stream the events emitted in an
+//! [`Environment`] to files while a simulation is running.
+//!
+//! A [`Recorder`] watches the events emitted in an [`Environment`] via its
+//! `EventBroadcaster`. Every log that matches the recorder's [`Filter`] is
+//! decoded with the events of a contract's [`Abi`], e.g., the
+//! `LIQUIDEXCHANGE_ABI` of a binding, and handed as a [`Record`] to each of
+//! the recorder's [`Sink`]s. The [`CsvSink`]
+//! and [`JsonLinesSink`] write records to files, and any other destination can
+//! be supported by implementing [`Sink`].
+//!
+//! Recorders are created and attached to environments via the [`Manager`],
+//! which flushes them when their [`Environment`] is stopped.
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError};
+use ethers::{
+    abi::{Abi, RawLog, Token},
+    types::{Address, Bytes, Filter, FilteredParams, I256, U64},
+};
+use serde_json::json;
+use thiserror::Error;
+
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::manager::Manager;
+use crate::{
+    environment::{EmittedLogs, Environment},
+    middleware::revm_logs_to_ethers_logs,
+};
+
+/// A single decoded event emitted in an [`Environment`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// The number of the block the event was emitted in.
+    pub block_number: U64,
+
+    /// The timestamp of the block the event was emitted in.
+    pub block_timestamp: U64,
+
+    /// The address of the contract that emitted the event.
+    pub address: Address,
+
+    /// The name of the event as declared in the contract's ABI, e.g., `Swap`.
+    pub event: String,
+
+    /// The values of the event's parameters in the order they are declared.
+    pub values: Vec<Token>,
+}
+
+impl Record {
+    /// Formats the values of the event's parameters as strings. Integers are
+    /// given in decimal and addresses and bytes as `0x` prefixed hex.
+    pub fn formatted_values(&self) -> Vec<String> {
+        self.values.iter().map(format_token).collect()
+    }
+}
+
+/// Errors that can occur while recording events with a [`Recorder`].
+#[derive(Error, Debug)]
+pub enum RecorderError {
+    /// [`RecorderError::Io`] is thrown when a [`Sink`] fails to write to its
+    /// destination.
+    #[error("error writing records! the source error is: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// [`RecorderError::Serialization`] is thrown when a [`Record`] could not
+    /// be serialized.
+    #[error("error serializing a record! the source error is: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// [`RecorderError::ThreadPanic`] is thrown when the thread of a
+    /// [`Recorder`] has panicked.
+    #[error("joining on the recorder thread resulted in a panic")]
+    ThreadPanic,
+
+    /// [`RecorderError::EventBroadcaster`] is thrown when the [`Recorder`]
+    /// could not subscribe to the events of its [`Environment`].
+    #[error("error subscribing to the environment's events! due to: {0}")]
+    EventBroadcaster(String),
+}
+
+/// A destination that [`Record`]s are streamed to.
+pub trait Sink: Send {
+    /// Writes a single [`Record`]. Implementations may buffer records until
+    /// [`Sink::flush`] is called.
+    fn write(&mut self, record: &Record) -> Result<(), RecorderError>;
+
+    /// Makes sure every record written so far has reached its destination.
+    fn flush(&mut self) -> Result<(), RecorderError>;
+}
+
+/// A [`Sink`] that writes [`Record`]s as rows of a CSV file with the columns
+/// `block_number,block_timestamp,address,event,values`. The formatted values
+/// of an event are joined into a single column.
+#[derive(Debug)]
+pub struct CsvSink {
+    writer: BufWriter<File>,
+}
+
+impl CsvSink {
+    /// Creates the CSV file at `path` and writes its header.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, RecorderError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "block_number,block_timestamp,address,event,values")?;
+        Ok(Self { writer })
+    }
+}
+
+impl Sink for CsvSink {
+    fn write(&mut self, record: &Record) -> Result<(), RecorderError> {
+        writeln!(
+            self.writer,
+            "{},{},{:?},{},{}",
+            record.block_number,
+            record.block_timestamp,
+            record.address,
+            escape_csv(&record.event),
+            escape_csv(&record.formatted_values().join(", "))
+        )?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), RecorderError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A [`Sink`] that writes every [`Record`] as a JSON object on its own line.
+#[derive(Debug)]
+pub struct JsonLinesSink {
+    writer: BufWriter<File>,
+}
+
+impl JsonLinesSink {
+    /// Creates the JSON-lines file at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, RecorderError> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+}
+
+impl Sink for JsonLinesSink {
+    fn write(&mut self, record: &Record) -> Result<(), RecorderError> {
+        let line = json!({
+            "block_number": record.block_number.as_u64(),
+            "block_timestamp": record.block_timestamp.as_u64(),
+            "address": record.address,
+            "event": record.event,
+            "values": record.formatted_values(),
+        });
+        serde_json::to_writer(&mut self.writer, &line)?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), RecorderError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Streams the decoded events of an [`Environment`] to a set of [`Sink`]s on
+/// a separate thread.
+#[derive(Debug)]
+pub struct Recorder {
+    /// The label of the [`Environment`] whose events are recorded.
+    pub environment: String,
+
+    /// Tells the thread of the [`Recorder`] that the [`Environment`] has been
+    /// stopped.
+    finished: Arc<AtomicBool>,
+
+    /// [`JoinHandle`] for the thread in which the [`Recorder`] is recording.
+    /// The thread flushes every sink and finishes once the [`Environment`] is
+    /// stopped.
+    handle: Option<JoinHandle<Result<(), RecorderError>>>,
+}
+
+impl Recorder {
+    /// Privately accessible constructor function for creating a [`Recorder`].
+    /// Subscribes to the events of the `environment` and starts recording on a
+    /// new thread. Logs that are not an event of the `abi` are skipped. This
+    /// function should be accessed by the [`Manager`].
+    pub(crate) fn new(
+        environment: &Environment,
+        filter: Filter,
+        abi: Abi,
+        sinks: Vec<Box<dyn Sink>>,
+    ) -> Result<Self, RecorderError> {
+        let (event_sender, event_receiver) = unbounded();
+        environment
+            .socket
+            .event_broadcaster
+            .lock()
+            .map_err(|e| RecorderError::EventBroadcaster(format!("{:?}", e)))?
+            .add_sender(event_sender);
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let thread_finished = Arc::clone(&finished);
+        let handle = thread::spawn(move || {
+            let mut recording = Recording {
+                filtered_params: FilteredParams::new(Some(filter)),
+                abi,
+                sinks,
+            };
+            loop {
+                match event_receiver.recv_timeout(Duration::from_millis(100)) {
+                    Ok(emitted_logs) => recording.record(emitted_logs)?,
+                    // The environment's thread has been joined by the time this is set, so
+                    // every log it emitted is already waiting in the channel
+                    Err(RecvTimeoutError::Timeout) if thread_finished.load(Ordering::SeqCst) => {
+                        break
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            recording.finish(&event_receiver)
+        });
+
+        Ok(Self {
+            environment: environment.label.clone(),
+            finished,
+            handle: Some(handle),
+        })
+    }
+
+    /// Records the last of the events and flushes every sink. Must only be
+    /// called once the [`Environment`]'s thread has been joined.
+    pub(crate) fn finish(&mut self) -> Result<(), RecorderError> {
+        self.finished.store(true, Ordering::SeqCst);
+        match self.handle.take() {
+            Some(handle) => handle.join().unwrap_or(Err(RecorderError::ThreadPanic)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The state owned by the thread of a [`Recorder`].
+struct Recording {
+    /// The parsed filter that logs must match in order to be recorded.
+    filtered_params: FilteredParams,
+
+    /// The ABI whose events the logs are decoded with.
+    abi: Abi,
+
+    /// The destinations of the records.
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl Recording {
+    /// Decodes the logs that match the filter and writes them to every sink.
+    fn record(&mut self, emitted_logs: EmittedLogs) -> Result<(), RecorderError> {
+        for log in revm_logs_to_ethers_logs(emitted_logs.logs) {
+            if !(self.filtered_params.filter_address(&log)
+                && self.filtered_params.filter_topics(&log))
+            {
+                continue;
+            }
+            let address = log.address;
+            let raw_log = RawLog::from(log);
+            let decoded = self
+                .abi
+                .events()
+                .filter(|event| raw_log.topics.first() == Some(&event.signature()))
+                .find_map(|event| {
+                    event
+                        .parse_log(raw_log.clone())
+                        .ok()
+                        .map(|log| (event.name.clone(), log))
+                });
+            let (event, log) = match decoded {
+                Some(decoded) => decoded,
+                None => continue,
+            };
+            let record = Record {
+                block_number: emitted_logs.block_number,
+                block_timestamp: emitted_logs.block_timestamp,
+                address,
+                event,
+                values: log.params.into_iter().map(|param| param.value).collect(),
+            };
+            for sink in self.sinks.iter_mut() {
+                sink.write(&record)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records whatever is left in the channel and flushes every sink.
+    fn finish(mut self, event_receiver: &Receiver<EmittedLogs>) -> Result<(), RecorderError> {
+        while let Ok(emitted_logs) = event_receiver.try_recv() {
+            self.record(emitted_logs)?;
+        }
+        for sink in self.sinks.iter_mut() {
+            sink.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a [`Token`] for use in a [`Record`].
+pub(crate) fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("{:?}", address),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => value.clone(),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => Bytes::from(bytes.clone()).to_string(),
+        Token::Array(tokens) | Token::FixedArray(tokens) => format!(
+            "[{}]",
+            tokens
+                .iter()
+                .map(format_token)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Token::Tuple(tokens) => format!(
+            "({})",
+            tokens
+                .iter()
+                .map(format_token)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+pub(crate) fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+
+    use super::*;
+
+    #[test]
+    fn format_values() {
+        let record = Record {
+            block_number: U64::from(1),
+            block_timestamp: U64::from(12),
+            address: Address::zero(),
+            event: "Swap".to_string(),
+            values: vec![
+                Token::Uint(U256::exp10(18)),
+                Token::Int(I256::from(-1).into_raw()),
+                Token::Array(vec![Token::Bool(true), Token::Bytes(vec![0xab])]),
+            ],
+        };
+        assert_eq!(
+            record.formatted_values(),
+            vec!["1000000000000000000", "-1", "[true, 0xab]"]
+        );
+        assert_eq!(escape_csv("a, \"b\""), "\"a, \"\"b\"\"\"");
+        assert_eq!(escape_csv("Swap"), "Swap");
+    }
+}
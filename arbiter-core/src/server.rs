@@ -0,0 +1,993 @@
+//! The `server` module serves an [`Environment`] over JSON-RPC so that tools
+//! written in other languages, e.g., `cast`, Foundry scripts, web3.py or
+//! frontends, can talk to a running simulation as if it were a local node.
+//!
+//! The server listens on a local address for JSON-RPC requests, both as HTTP
+//! `POST`s and as messages over a WebSocket, and answers them through a
+//! [`RevmMiddleware`] of the [`Environment`]. It is started via
+//! [`Manager::serve_rpc`] or the `arbiter serve` command and runs until it is
+//! stopped via [`Manager::stop_rpc`] or the [`Manager`] is dropped.
+//!
+//! Supported methods:
+//! - State reads: `eth_chainId`, `eth_blockNumber`, `eth_getBalance`,
+//!   `eth_getTransactionCount`, `eth_getCode`, `eth_getStorageAt`, `eth_call`,
+//!   `eth_estimateGas` and `eth_getLogs`. Past blocks can only be read if the
+//!   [`Environment`] retains their state.
+//! - Transactions: `eth_sendTransaction` from any account without a signature,
+//!   as if every account were impersonated, and `eth_sendRawTransaction`, along
+//!   with `eth_getTransactionByHash` and `eth_getTransactionReceipt` for the
+//!   last 10,000 transactions sent through the server. Raw transactions signed
+//!   for another chain are rejected.
+//! - Blocks: `eth_getBlockByNumber` and `eth_getBlockByHash` for the current
+//!   block and the last 1,024 blocks finished while serving, `evm_mine` and
+//!   `anvil_mine`, which mines at most 10,000 blocks at once.
+//! - Cheats: `anvil_setBalance`, `anvil_setCode`, `anvil_setStorageAt` and
+//!   `anvil_setNonce` as well as their `hardhat_` aliases. The impersonation
+//!   methods are accepted but change nothing.
+//! - Fees and node information: `eth_gasPrice`, `eth_maxPriorityFeePerGas` and
+//!   `eth_feeHistory`, which are all zero and of which the latter covers at
+//!   most 1,024 blocks, `eth_accounts`, `net_version` and `web3_clientVersion`.
+//!
+//! Any other method, including subscriptions via `eth_subscribe`, fails with
+//! the JSON-RPC "method not found" error.
+
+#![warn(missing_docs, unsafe_code)]
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use ethers::{
+    providers::{JsonRpcClient, Middleware, ProviderError},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, Block, BlockNumber, Bytes, FeeHistory,
+        Signature, Transaction, TransactionReceipt, TransactionRequest, H256, U256, U64,
+    },
+    utils::{keccak256, rlp::Rlp},
+};
+use log::warn;
+use revm::primitives::{ExecutionResult, Output, B160};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tungstenite::{
+    handshake::derive_accept_key, protocol::Role, Error as WebSocketError, Message, WebSocket,
+};
+
+#[cfg_attr(doc, doc(hidden))]
+#[cfg_attr(doc, allow(unused_imports))]
+#[cfg(doc)]
+use crate::{environment::Environment, manager::Manager};
+use crate::{
+    environment::{
+        BlockHeader, Cheat, EnvironmentQuery, Instruction, QuerySender, TransactionOutcome,
+    },
+    middleware::{
+        past_block_number, recast_address, recast_ethers_uint, recast_uint,
+        revm_logs_to_ethers_logs, tx_env, RevmMiddleware, RevmMiddlewareError,
+    },
+};
+
+/// The JSON-RPC error code for a request that is not valid JSON.
+const PARSE_ERROR: i64 = -32700;
+
+/// The JSON-RPC error code for JSON that is not a request.
+const INVALID_REQUEST: i64 = -32600;
+
+/// The JSON-RPC error code for a method the server does not support.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The JSON-RPC error code for parameters that cannot be read.
+const INVALID_PARAMS: i64 = -32602;
+
+/// The JSON-RPC error code for a request the [`Environment`] failed to answer.
+const SERVER_ERROR: i64 = -32000;
+
+/// The error code nodes answer a reverted call with, along with its output as
+/// the data of the error.
+const EXECUTION_REVERTED: i64 = 3;
+
+/// The most blocks `anvil_mine` mines at once, as the server answers no other
+/// request while mining.
+const MAX_MINED_BLOCKS: u64 = 10_000;
+
+/// The most finished blocks whose headers the server keeps, beyond which the
+/// oldest are forgotten.
+const MAX_KEPT_BLOCKS: usize = 1024;
+
+/// The most transactions sent through the server that it keeps along with
+/// their receipts, beyond which the oldest are forgotten.
+const MAX_KEPT_TRANSACTIONS: usize = 10_000;
+
+/// The most blocks `eth_feeHistory` covers, as in geth.
+const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+
+/// The most reward percentiles `eth_feeHistory` may be asked for.
+const MAX_FEE_HISTORY_PERCENTILES: usize = 100;
+
+/// The largest HTTP request line or header the server reads.
+const MAX_HEADER_SIZE: u64 = 8 * 1024;
+
+/// The largest HTTP request body the server reads.
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// A JSON-RPC error object.
+#[derive(Debug, Serialize)]
+struct RpcError {
+    /// The code of the error.
+    code: i64,
+
+    /// A description of the error.
+    message: String,
+
+    /// More information on the error, e.g., the output of a reverted call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl RpcError {
+    /// Creates an [`RpcError`] without data.
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<ProviderError> for RpcError {
+    fn from(err: ProviderError) -> Self {
+        match err {
+            ProviderError::SerdeJson(e) => Self::new(INVALID_PARAMS, e.to_string()),
+            err => Self::new(SERVER_ERROR, err.to_string()),
+        }
+    }
+}
+
+impl From<RevmMiddlewareError> for RpcError {
+    fn from(err: RevmMiddlewareError) -> Self {
+        match err {
+            RevmMiddlewareError::ExecutionRevert { output, .. } => Self {
+                code: EXECUTION_REVERTED,
+                message: "execution reverted".to_string(),
+                data: Some(json!(Bytes::from(output.to_vec()))),
+            },
+            RevmMiddlewareError::Provider(e) => e.into(),
+            err => Self::new(SERVER_ERROR, err.to_string()),
+        }
+    }
+}
+
+/// What the server keeps track of on top of the [`Environment`] it serves.
+struct Node {
+    /// The client that requests are answered through.
+    client: RevmMiddleware,
+
+    /// Receives the [`BlockHeader`] of every block the [`Environment`]
+    /// finishes.
+    header_receiver: crossbeam_channel::Receiver<BlockHeader>,
+
+    /// The headers of the last [`MAX_KEPT_BLOCKS`] blocks finished while
+    /// serving by their number.
+    headers: BTreeMap<u64, BlockHeader>,
+
+    /// The last [`MAX_KEPT_TRANSACTIONS`] transactions sent through the server
+    /// along with their receipts by their hash.
+    transactions: HashMap<H256, (Transaction, TransactionReceipt)>,
+
+    /// The hashes of the kept transactions from the oldest to the newest.
+    transaction_hashes: VecDeque<H256>,
+}
+
+/// The open connections of an [`RpcServer`] by the address of their peer.
+type Connections = Arc<Mutex<HashMap<SocketAddr, TcpStream>>>;
+
+/// A handle to the JSON-RPC server started by [`serve`]. The server stops
+/// accepting connections and closes the open ones when it is stopped or
+/// dropped.
+#[derive(Debug)]
+pub(crate) struct RpcServer {
+    /// Tells the thread of the server to stop accepting connections.
+    stopped: Arc<AtomicBool>,
+
+    /// The open connections, which are shut down when the server is stopped.
+    connections: Connections,
+
+    /// [`JoinHandle`] for the thread in which connections are accepted.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RpcServer {
+    /// Stops accepting connections, waits for the thread of the server to
+    /// finish and shuts down the open connections so that their threads
+    /// finish as well. Returns an error if the thread has panicked.
+    pub(crate) fn stop(&mut self) -> thread::Result<()> {
+        self.stopped.store(true, Ordering::SeqCst);
+        let result = match self.handle.take() {
+            Some(handle) => handle.join(),
+            None => Ok(()),
+        };
+        let mut connections = self
+            .connections
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        for (_, stream) in connections.drain() {
+            // The peer may have closed the connection already
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        result
+    }
+}
+
+impl Drop for RpcServer {
+    fn drop(&mut self) {
+        if self.stop().is_err() {
+            warn!("The JSON-RPC server thread panicked");
+        }
+    }
+}
+
+/// Serves JSON-RPC requests to the [`Environment`] of the `client` on a
+/// separate thread until the returned [`RpcServer`] is stopped, with every
+/// connection taken from the `listener` handled on a thread of its own. The
+/// `listener` must not block so that the thread notices when it is stopped.
+pub(crate) fn serve(
+    listener: TcpListener,
+    client: RevmMiddleware,
+) -> Result<RpcServer, RevmMiddlewareError> {
+    let node = Node {
+        header_receiver: client.header_receiver()?,
+        client,
+        headers: BTreeMap::new(),
+        transactions: HashMap::new(),
+        transaction_hashes: VecDeque::new(),
+    };
+    let node = Arc::new(tokio::sync::Mutex::new(node));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let connections = Connections::default();
+    let thread_stopped = Arc::clone(&stopped);
+    let thread_connections = Arc::clone(&connections);
+    let handle = thread::spawn(move || {
+        while !thread_stopped.load(Ordering::SeqCst) {
+            let result = match listener.accept() {
+                Ok((stream, peer)) => accept(stream, peer, Arc::clone(&node), &thread_connections),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    // Keep up with the finished blocks while no requests come in
+                    if let Ok(mut node) = node.try_lock() {
+                        node.catch_up();
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("Failed to accept a JSON-RPC connection: {}", e);
+            }
+        }
+    });
+    Ok(RpcServer {
+        stopped,
+        connections,
+        handle: Some(handle),
+    })
+}
+
+/// Keeps track of a connection from `peer` and serves it on a thread of its
+/// own until either side closes it.
+fn accept(
+    stream: TcpStream,
+    peer: SocketAddr,
+    node: Arc<tokio::sync::Mutex<Node>>,
+    connections: &Connections,
+) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    connections
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(peer, stream.try_clone()?);
+    let connections = Arc::clone(connections);
+    thread::spawn(move || {
+        if let Err(e) = connect(stream, &node) {
+            warn!("Failed to serve JSON-RPC: {}", e);
+        }
+        connections
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&peer);
+    });
+    Ok(())
+}
+
+/// The parts of an HTTP request that the server looks at.
+struct HttpRequest {
+    /// The method of the request, e.g., `POST`.
+    method: String,
+
+    /// The headers of the request by their lowercase name.
+    headers: HashMap<String, String>,
+
+    /// The body of the request.
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Whether the header `name` holds `value`, ignoring case.
+    fn has_header(&self, name: &str, value: &str) -> bool {
+        self.headers
+            .get(name)
+            .is_some_and(|header| header.eq_ignore_ascii_case(value))
+    }
+}
+
+/// Answers the HTTP requests sent over a connection until it is closed or
+/// upgraded to a WebSocket.
+fn connect(mut stream: TcpStream, node: &tokio::sync::Mutex<Node>) -> std::io::Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    loop {
+        let request = match read_request(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => break,
+            // The rest of an oversized request is not read, so the connection
+            // cannot be used any further
+            Err(e) if e.kind() == ErrorKind::InvalidData => {
+                respond(&mut stream, "413 Payload Too Large", "")?;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+        match request.method.as_str() {
+            "GET" if request.has_header("upgrade", "websocket") => {
+                let key = request.headers.get("sec-websocket-key").ok_or_else(|| {
+                    std::io::Error::other("the WebSocket upgrade has no `Sec-WebSocket-Key`")
+                })?;
+                write!(
+                    stream,
+                    "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                    derive_accept_key(key.as_bytes())
+                )?;
+                // Clients wait for the upgrade before sending any messages, so
+                // nothing is left behind in the buffer of the reader
+                return serve_websocket(stream, node, &runtime);
+            }
+            "POST" => {
+                let body = runtime.block_on(answer(node, &request.body));
+                respond(&mut stream, "200 OK", &body)?;
+            }
+            // Browsers ask before sending requests across origins
+            "OPTIONS" => respond(&mut stream, "204 No Content", "")?,
+            _ => respond(&mut stream, "405 Method Not Allowed", "")?,
+        }
+        if request.has_header("connection", "close") {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the next HTTP request sent over a connection, if there is one. Fails
+/// with [`ErrorKind::InvalidData`] if a line of the request is longer than
+/// [`MAX_HEADER_SIZE`] or its body is larger than [`MAX_BODY_SIZE`].
+fn read_request(reader: &mut BufReader<TcpStream>) -> std::io::Result<Option<HttpRequest>> {
+    let mut line = String::new();
+    if read_line(reader, &mut line)? == 0 {
+        return Ok(None);
+    }
+    let method = line
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        if read_line(reader, &mut line)? == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_SIZE {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!("the request body is larger than {} bytes", MAX_BODY_SIZE),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(HttpRequest {
+        method,
+        headers,
+        body,
+    }))
+}
+
+/// Reads a line of an HTTP request into `line`, failing if it is longer than
+/// [`MAX_HEADER_SIZE`].
+fn read_line(reader: &mut BufReader<TcpStream>, line: &mut String) -> std::io::Result<usize> {
+    let read = reader.by_ref().take(MAX_HEADER_SIZE).read_line(line)?;
+    if read as u64 == MAX_HEADER_SIZE && !line.ends_with('\n') {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "a line of the request is longer than {} bytes",
+                MAX_HEADER_SIZE
+            ),
+        ));
+    }
+    Ok(read)
+}
+
+/// Writes an HTTP response with a JSON `body`, allowing requests from any
+/// origin.
+fn respond(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Answers the requests sent as messages over a WebSocket until it is closed.
+fn serve_websocket(
+    stream: TcpStream,
+    node: &tokio::sync::Mutex<Node>,
+    runtime: &tokio::runtime::Runtime,
+) -> std::io::Result<()> {
+    let mut websocket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    loop {
+        let request = match websocket.read() {
+            Ok(Message::Text(text)) => text.into_bytes(),
+            Ok(Message::Binary(bytes)) => bytes,
+            Ok(Message::Close(_))
+            | Err(WebSocketError::ConnectionClosed)
+            | Err(WebSocketError::AlreadyClosed) => return Ok(()),
+            // Pings are answered when flushing
+            Ok(_) => {
+                websocket.flush().map_err(std::io::Error::other)?;
+                continue;
+            }
+            Err(e) => return Err(std::io::Error::other(e)),
+        };
+        let body = runtime.block_on(answer(node, &request));
+        websocket
+            .send(Message::Text(body))
+            .map_err(std::io::Error::other)?;
+    }
+}
+
+/// Answers the JSON-RPC request or batch of requests in `body`.
+async fn answer(node: &tokio::sync::Mutex<Node>, body: &[u8]) -> String {
+    let response = match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(requests)) if !requests.is_empty() => {
+            let mut responses = vec![];
+            for request in requests {
+                responses.push(answer_request(node, request).await);
+            }
+            Value::Array(responses)
+        }
+        Ok(request @ Value::Object(_)) => answer_request(node, request).await,
+        Ok(_) => response(
+            Value::Null,
+            Err(RpcError::new(INVALID_REQUEST, "expected a request object")),
+        ),
+        Err(e) => response(Value::Null, Err(RpcError::new(PARSE_ERROR, e.to_string()))),
+    };
+    response.to_string()
+}
+
+/// Answers a single JSON-RPC request.
+async fn answer_request(node: &tokio::sync::Mutex<Node>, request: Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let result = match request.get("method").and_then(Value::as_str) {
+        Some(method) => {
+            let params = request.get("params").cloned().unwrap_or(json!([]));
+            node.lock().await.answer(method, &params).await
+        }
+        None => Err(RpcError::new(INVALID_REQUEST, "the request has no method")),
+    };
+    response(id, result)
+}
+
+/// The JSON-RPC response to the request with the given `id`.
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+impl Node {
+    /// Keeps the headers of the blocks finished since the last catch up,
+    /// forgetting the oldest beyond [`MAX_KEPT_BLOCKS`].
+    fn catch_up(&mut self) {
+        self.headers.extend(
+            self.header_receiver
+                .try_iter()
+                .map(|header| (header.number.as_u64(), header)),
+        );
+        while self.headers.len() > MAX_KEPT_BLOCKS {
+            self.headers.pop_first();
+        }
+    }
+
+    /// Answers a request for `method` with the given `params`.
+    async fn answer(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        self.catch_up();
+        match method {
+            "eth_chainId"
+            | "eth_blockNumber"
+            | "eth_getBalance"
+            | "eth_getTransactionCount"
+            | "eth_getCode"
+            | "eth_getStorageAt"
+            | "eth_getLogs" => Ok(self
+                .client
+                .provider()
+                .as_ref()
+                .request::<_, Value>(method, params)
+                .await?),
+            "eth_call" => {
+                let tx = call_request(params)?;
+                let block: Option<BlockNumber> = param(params, 1)?;
+                let tx_env = tx_env(tx.from.unwrap_or_default(), &tx.into())?;
+                let (output, _) = self
+                    .client
+                    .call_tx_env(tx_env, block.and_then(past_block_number))?;
+                Ok(json!(output))
+            }
+            "eth_estimateGas" => {
+                let tx = call_request(params)?;
+                let block: Option<BlockNumber> = param(params, 1)?;
+                let tx_env = tx_env(tx.from.unwrap_or_default(), &tx.into())?;
+                let gas = self
+                    .client
+                    .estimate(tx_env, block.and_then(past_block_number))?;
+                Ok(json!(U256::from(gas)))
+            }
+            "eth_sendTransaction" => {
+                let tx = call_request(params)?;
+                let from = tx.from.ok_or_else(|| {
+                    RpcError::new(INVALID_PARAMS, "the transaction has no `from` address")
+                })?;
+                let mut tx: TypedTransaction = tx.into();
+                if tx.nonce().is_none() {
+                    let nonce = self.client.get_transaction_count(from, None).await?;
+                    tx.set_nonce(nonce);
+                }
+                // Without a signature, the sender tells transactions apart
+                let hash = H256::from(keccak256(
+                    [tx.sighash().as_bytes(), from.as_bytes()].concat(),
+                ));
+                self.send(tx, from, hash, None)
+            }
+            "eth_sendRawTransaction" => {
+                let raw: Bytes = param(params, 0)?;
+                let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw))
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+                let from = signature
+                    .recover(tx.sighash())
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+                // Transactions signed without a chain ID are valid on any chain
+                if let Some(chain_id) = tx.chain_id() {
+                    let expected = self.query(EnvironmentQuery::ChainId)?;
+                    if U256::from(chain_id.as_u64()) != expected {
+                        return Err(RpcError::new(
+                            INVALID_PARAMS,
+                            format!(
+                                "the transaction is signed for chain {} instead of {}",
+                                chain_id, expected
+                            ),
+                        ));
+                    }
+                }
+                let hash = tx.hash(&signature);
+                self.send(tx, from, hash, Some(signature))
+            }
+            "eth_getTransactionByHash" => {
+                let hash: H256 = param(params, 0)?;
+                Ok(json!(self.transactions.get(&hash).map(|(tx, _)| tx)))
+            }
+            "eth_getTransactionReceipt" => {
+                let hash: H256 = param(params, 0)?;
+                let receipt = self.transactions.get(&hash).map(|(_, receipt)| {
+                    // The block of a receipt has a hash once it is finished
+                    let block_hash = receipt
+                        .block_number
+                        .and_then(|number| self.headers.get(&number.as_u64()))
+                        .map(BlockHeader::hash);
+                    let mut receipt = receipt.clone();
+                    receipt.block_hash = block_hash;
+                    for log in receipt.logs.iter_mut() {
+                        log.block_hash = block_hash;
+                    }
+                    receipt
+                });
+                Ok(json!(receipt))
+            }
+            "eth_getBlockByNumber" => {
+                let block: BlockNumber = param(params, 0)?;
+                let current = self.query(EnvironmentQuery::BlockNumber)?.as_u64();
+                let block = match past_block_number(block) {
+                    Some(number) if number < current => {
+                        self.headers.get(&number).map(|header| self.block(header))
+                    }
+                    Some(number) if number > current => None,
+                    _ => Some(self.current_block(current)?),
+                };
+                Ok(json!(block))
+            }
+            "eth_getBlockByHash" => {
+                let hash: H256 = param(params, 0)?;
+                let header = self.headers.values().find(|header| header.hash() == hash);
+                Ok(json!(header.map(|header| self.block(header))))
+            }
+            "evm_mine" | "anvil_mine" => {
+                // `evm_mine` may be given a timestamp, which the blocks do not follow
+                let blocks = match method {
+                    "anvil_mine" => param::<Option<U256>>(params, 0)?.unwrap_or(U256::one()),
+                    _ => U256::one(),
+                };
+                if blocks > U256::from(MAX_MINED_BLOCKS) {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        format!("at most {} blocks can be mined at once", MAX_MINED_BLOCKS),
+                    ));
+                }
+                for _ in 0..blocks.as_u64() {
+                    self.instruct(Instruction::Mine)?;
+                }
+                Ok(json!("0x0"))
+            }
+            "anvil_setBalance" | "hardhat_setBalance" => {
+                let address: Address = param(params, 0)?;
+                let balance: U256 = param(params, 1)?;
+                self.instruct(|sender| {
+                    Instruction::Cheat(
+                        Cheat::Deal(B160::from(address), recast_ethers_uint(balance)),
+                        sender,
+                    )
+                })?;
+                Ok(Value::Null)
+            }
+            "anvil_setCode" | "hardhat_setCode" => {
+                let address: Address = param(params, 0)?;
+                let code: Bytes = param(params, 1)?;
+                self.instruct(|sender| {
+                    Instruction::Cheat(Cheat::Etch(B160::from(address), code.0), sender)
+                })?;
+                Ok(Value::Null)
+            }
+            "anvil_setStorageAt" | "hardhat_setStorageAt" => {
+                let address: Address = param(params, 0)?;
+                let slot: U256 = param(params, 1)?;
+                let value: H256 = param(params, 2)?;
+                self.instruct(|sender| {
+                    Instruction::Cheat(
+                        Cheat::Store(
+                            B160::from(address),
+                            recast_ethers_uint(slot),
+                            recast_ethers_uint(U256::from_big_endian(value.as_bytes())),
+                        ),
+                        sender,
+                    )
+                })?;
+                Ok(json!(true))
+            }
+            "anvil_setNonce" | "hardhat_setNonce" => {
+                let address: Address = param(params, 0)?;
+                let nonce: U256 = param(params, 1)?;
+                let nonce = u64::try_from(nonce).map_err(|_| {
+                    RpcError::new(INVALID_PARAMS, "the nonce does not fit into 64 bits")
+                })?;
+                self.instruct(|sender| {
+                    Instruction::Cheat(Cheat::SetNonce(B160::from(address), nonce), sender)
+                })?;
+                Ok(Value::Null)
+            }
+            // Every account can send transactions through the server already
+            "anvil_impersonateAccount"
+            | "anvil_stopImpersonatingAccount"
+            | "anvil_autoImpersonateAccount"
+            | "hardhat_impersonateAccount"
+            | "hardhat_stopImpersonatingAccount" => Ok(Value::Null),
+            "eth_gasPrice" | "eth_maxPriorityFeePerGas" => Ok(json!(U256::zero())),
+            "eth_feeHistory" => {
+                let blocks: U256 = param(params, 0)?;
+                let percentiles: Option<Vec<f64>> = param(params, 2)?;
+                let current = self.query(EnvironmentQuery::BlockNumber)?;
+                Ok(json!(fee_history(
+                    blocks,
+                    current,
+                    &percentiles.unwrap_or_default()
+                )?))
+            }
+            "eth_accounts" => Ok(json!([])),
+            "net_version" => Ok(json!(self.query(EnvironmentQuery::ChainId)?.to_string())),
+            "web3_clientVersion" => Ok(json!(format!("arbiter/{}", env!("CARGO_PKG_VERSION")))),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("the method {} is not supported", method),
+            )),
+        }
+    }
+
+    /// Sends a transaction from `from` to the [`Environment`] and keeps it
+    /// along with its receipt under `hash`. Unlike with
+    /// [`Middleware::send_transaction`], a reverted transaction is included
+    /// with a failed receipt.
+    fn send(
+        &mut self,
+        tx: TypedTransaction,
+        from: Address,
+        hash: H256,
+        signature: Option<Signature>,
+    ) -> Result<Value, RpcError> {
+        let tx_env = Box::new(tx_env(from, &tx)?);
+        let revm_result = self
+            .client
+            .execute(|sender| Instruction::Transaction(true, tx_env, sender))?;
+        let execution_result = match revm_result.outcome {
+            TransactionOutcome::Success(execution_result) => execution_result,
+            TransactionOutcome::Error(e) => return Err(RpcError::new(SERVER_ERROR, e.to_string())),
+        };
+        let block_number = revm_result.block_number;
+        let gas_used = U256::from(execution_result.gas_used());
+        let (status, logs, contract_address) = match execution_result {
+            ExecutionResult::Success { logs, output, .. } => {
+                let contract_address = match output {
+                    Output::Create(_, address) => address.map(recast_address),
+                    Output::Call(_) => None,
+                };
+                (1, revm_logs_to_ethers_logs(logs), contract_address)
+            }
+            ExecutionResult::Revert { .. } | ExecutionResult::Halt { .. } => (0, vec![], None),
+        };
+        let logs = logs
+            .into_iter()
+            .map(|mut log| {
+                log.block_number = Some(block_number);
+                log.transaction_hash = Some(hash);
+                log
+            })
+            .collect();
+        let receipt = TransactionReceipt {
+            transaction_hash: hash,
+            block_number: Some(block_number),
+            from,
+            to: tx.to_addr().copied(),
+            cumulative_gas_used: gas_used,
+            gas_used: Some(gas_used),
+            contract_address,
+            logs,
+            status: Some(U64::from(status)),
+            effective_gas_price: Some(U256::zero()),
+            ..Default::default()
+        };
+        let signature = signature.unwrap_or(Signature {
+            r: U256::zero(),
+            s: U256::zero(),
+            v: 0,
+        });
+        let transaction = Transaction {
+            hash,
+            nonce: tx.nonce().copied().unwrap_or_default(),
+            block_number: Some(block_number),
+            from,
+            to: tx.to_addr().copied(),
+            value: tx.value().copied().unwrap_or_default(),
+            gas: tx.gas().copied().unwrap_or_default(),
+            input: tx.data().cloned().unwrap_or_default(),
+            v: signature.v.into(),
+            r: signature.r,
+            s: signature.s,
+            chain_id: tx.chain_id().map(|chain_id| U256::from(chain_id.as_u64())),
+            ..Default::default()
+        };
+        self.keep_transaction(hash, transaction, receipt);
+        Ok(json!(hash))
+    }
+
+    /// Keeps a transaction sent through the server along with its receipt,
+    /// forgetting the oldest beyond [`MAX_KEPT_TRANSACTIONS`].
+    fn keep_transaction(
+        &mut self,
+        hash: H256,
+        transaction: Transaction,
+        receipt: TransactionReceipt,
+    ) {
+        if self
+            .transactions
+            .insert(hash, (transaction, receipt))
+            .is_none()
+        {
+            self.transaction_hashes.push_back(hash);
+        }
+        while self.transaction_hashes.len() > MAX_KEPT_TRANSACTIONS {
+            if let Some(hash) = self.transaction_hashes.pop_front() {
+                self.transactions.remove(&hash);
+            }
+        }
+    }
+
+    /// The block of a finished [`BlockHeader`].
+    fn block(&self, header: &BlockHeader) -> Block<H256> {
+        let number = header.number.as_u64();
+        let parent_hash = number
+            .checked_sub(1)
+            .and_then(|parent| self.headers.get(&parent))
+            .map(BlockHeader::hash)
+            .unwrap_or_default();
+        Block {
+            parent_hash,
+            ..Block::from(header)
+        }
+    }
+
+    /// The current block, which has no hash yet as it is not finished.
+    fn current_block(&self, number: u64) -> Result<Block<H256>, RpcError> {
+        let parent_hash = number
+            .checked_sub(1)
+            .and_then(|parent| self.headers.get(&parent))
+            .map(BlockHeader::hash)
+            .unwrap_or_default();
+        Ok(Block {
+            parent_hash,
+            number: Some(number.into()),
+            timestamp: self.query(EnvironmentQuery::Timestamp)?,
+            base_fee_per_gas: Some(self.query(EnvironmentQuery::BaseFee)?),
+            ..Default::default()
+        })
+    }
+
+    /// Answers an [`EnvironmentQuery`] about the current state that is
+    /// answered with a number.
+    fn query(&self, query: EnvironmentQuery) -> Result<U256, RpcError> {
+        let value = self
+            .client
+            .provider()
+            .as_ref()
+            .query(query, None)?
+            .into_uint()?;
+        Ok(recast_uint(value))
+    }
+
+    /// Sends an [`Instruction`] that changes the state of the [`Environment`]
+    /// and waits for it to be carried out.
+    fn instruct(
+        &self,
+        instruction: impl FnOnce(QuerySender) -> Instruction,
+    ) -> Result<(), RpcError> {
+        self.client.provider().as_ref().instruct(instruction)?;
+        Ok(())
+    }
+}
+
+/// Deserializes the parameter at `index` of the JSON `params` of a request,
+/// where a missing parameter reads as `null`.
+fn param<D: DeserializeOwned>(params: &Value, index: usize) -> Result<D, RpcError> {
+    let param = params.get(index).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(param).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+/// The fees of up to `blocks` blocks ending with the `current` one, which are
+/// all zero. Covers at most [`MAX_FEE_HISTORY_BLOCKS`] blocks and fails if
+/// more than [`MAX_FEE_HISTORY_PERCENTILES`] reward percentiles are asked for.
+fn fee_history(blocks: U256, current: U256, percentiles: &[f64]) -> Result<FeeHistory, RpcError> {
+    if percentiles.len() > MAX_FEE_HISTORY_PERCENTILES {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            format!(
+                "at most {} reward percentiles can be given",
+                MAX_FEE_HISTORY_PERCENTILES
+            ),
+        ));
+    }
+    let blocks = blocks
+        .min(U256::from(MAX_FEE_HISTORY_BLOCKS))
+        .min(current.saturating_add(U256::one()));
+    let count = blocks.as_usize();
+    Ok(FeeHistory {
+        base_fee_per_gas: vec![U256::zero(); count + 1],
+        gas_used_ratio: vec![0.0; count],
+        oldest_block: current + U256::one() - blocks,
+        reward: vec![vec![U256::zero(); percentiles.len()]; count],
+    })
+}
+
+/// Reads the call or transaction that is the first parameter of a request.
+/// Tools send its calldata as either `data` or `input`.
+fn call_request(params: &Value) -> Result<TransactionRequest, RpcError> {
+    let mut tx: Value = param(params, 0)?;
+    if let Some(object) = tx.as_object_mut() {
+        if !object.contains_key("data") {
+            if let Some(input) = object.remove("input") {
+                object.insert("data".to_string(), input);
+            }
+        }
+    }
+    serde_json::from_value(tx).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::{tests::TEST_ENV_LABEL, Environment, EnvironmentParameters};
+
+    #[test]
+    fn node_forgets_old_blocks_and_transactions() {
+        let params = EnvironmentParameters {
+            block_rate: 1.0,
+            seed: 1,
+        };
+        let environment = Environment::new(TEST_ENV_LABEL, params).unwrap();
+        let (header_sender, header_receiver) = crossbeam_channel::unbounded();
+        let mut node = Node {
+            client: RevmMiddleware::new(&environment, None),
+            header_receiver,
+            headers: BTreeMap::new(),
+            transactions: HashMap::new(),
+            transaction_hashes: VecDeque::new(),
+        };
+
+        for number in 0..MAX_KEPT_BLOCKS as u64 + 10 {
+            let header = BlockHeader {
+                number: number.into(),
+                timestamp: U64::zero(),
+                base_fee: U256::zero(),
+                transaction_count: 0,
+                gas_used: 0,
+            };
+            header_sender.send(header).unwrap();
+        }
+        node.catch_up();
+        assert_eq!(node.headers.len(), MAX_KEPT_BLOCKS);
+        assert_eq!(node.headers.keys().next(), Some(&10));
+
+        for hash in 0..MAX_KEPT_TRANSACTIONS as u64 + 10 {
+            let hash = H256::from_low_u64_be(hash);
+            node.keep_transaction(hash, Transaction::default(), TransactionReceipt::default());
+        }
+        assert_eq!(node.transactions.len(), MAX_KEPT_TRANSACTIONS);
+        assert!(!node.transactions.contains_key(&H256::from_low_u64_be(9)));
+        assert!(node.transactions.contains_key(&H256::from_low_u64_be(10)));
+    }
+
+    #[test]
+    fn fee_history_is_bounded() {
+        let history = fee_history(U256::MAX, U256::from(5000), &[50.0]).unwrap();
+        assert_eq!(
+            history.gas_used_ratio.len(),
+            MAX_FEE_HISTORY_BLOCKS as usize
+        );
+        assert_eq!(
+            history.oldest_block,
+            U256::from(5001 - MAX_FEE_HISTORY_BLOCKS)
+        );
+
+        // Only the blocks that exist are covered
+        let history = fee_history(U256::from(10), U256::from(2), &[]).unwrap();
+        assert_eq!(history.gas_used_ratio.len(), 3);
+        assert_eq!(history.oldest_block, U256::zero());
+
+        let percentiles = vec![50.0; MAX_FEE_HISTORY_PERCENTILES + 1];
+        assert!(fee_history(U256::one(), U256::zero(), &percentiles).is_err());
+    }
+}
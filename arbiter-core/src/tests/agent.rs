@@ -0,0 +1,164 @@
+use std::sync::Mutex;
+
+use ethers::types::Log;
+
+use super::*;
+use crate::agent::*;
+
+/// What a [`Watcher`] has seen so far.
+#[derive(Debug, Default)]
+struct Seen {
+    started: bool,
+    blocks: Vec<U64>,
+    transfers: usize,
+    shut_down: bool,
+}
+
+/// An [`Agent`] that approves itself when it starts and stops and watches the
+/// transfers of the token.
+struct Watcher {
+    token: Address,
+    seen: Arc<Mutex<Seen>>,
+}
+
+#[async_trait::async_trait]
+impl Agent for Watcher {
+    fn filters(&self) -> Vec<Filter> {
+        vec![Filter::new()
+            .address(self.token)
+            .event("Transfer(address,address,uint256)")]
+    }
+
+    async fn startup(&mut self, client: Arc<RevmMiddleware>) -> Result<(), AgentError> {
+        let token = ArbiterToken::new(self.token, client.clone());
+        token
+            .approve(
+                client.default_sender().unwrap(),
+                TEST_APPROVAL_AMOUNT.into(),
+            )
+            .send()
+            .await?
+            .await?;
+        self.seen.lock().unwrap().started = true;
+        Ok(())
+    }
+
+    async fn on_block(
+        &mut self,
+        _client: Arc<RevmMiddleware>,
+        block_number: U64,
+    ) -> Result<(), AgentError> {
+        self.seen.lock().unwrap().blocks.push(block_number);
+        Ok(())
+    }
+
+    async fn on_event(&mut self, _client: Arc<RevmMiddleware>, log: Log) -> Result<(), AgentError> {
+        assert_eq!(log.address, self.token);
+        self.seen.lock().unwrap().transfers += 1;
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, client: Arc<RevmMiddleware>) -> Result<(), AgentError> {
+        let token = ArbiterToken::new(self.token, client.clone());
+        token
+            .approve(
+                client.default_sender().unwrap(),
+                TEST_APPROVAL_AMOUNT.into(),
+            )
+            .send()
+            .await?
+            .await?;
+        self.seen.lock().unwrap().shut_down = true;
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn agent_receives_blocks_and_events() -> Result<()> {
+    let (arbiter_token, mut manager, _) = managed_deploy_and_start().await?;
+
+    let seen = Arc::new(Mutex::new(Seen::default()));
+    let watcher = Watcher {
+        token: arbiter_token.address(),
+        seen: seen.clone(),
+    };
+    manager.add_agent("watcher", TEST_ENV_LABEL, watcher)?;
+    assert!(manager.add_agent("watcher", TEST_ENV_LABEL, Idle).is_err());
+
+    let to = Address::from_str(TEST_MINT_TO)?;
+    let mut block_number = U64::zero();
+    while block_number < U64::from(3) {
+        block_number = arbiter_token
+            .mint(to, TEST_MINT_AMOUNT.into())
+            .send()
+            .await?
+            .await?
+            .unwrap()
+            .block_number
+            .unwrap();
+    }
+    manager.stop_environment(TEST_ENV_LABEL)?;
+
+    let seen = seen.lock().unwrap();
+    assert!(seen.started);
+    // The shutdown hook was able to transact before the environment stopped
+    assert!(seen.shut_down);
+    assert!(seen.transfers > 0);
+    assert!(!seen.blocks.is_empty());
+    // Every finished block is seen, even the ones without a transfer
+    assert!(seen.blocks.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    Ok(())
+}
+
+/// An [`Agent`] that does nothing.
+struct Idle;
+
+impl Agent for Idle {}
+
+#[test]
+fn agent_requires_environment() {
+    let mut manager = Manager::new();
+    assert!(matches!(
+        manager.add_agent("idle", TEST_ENV_LABEL, Idle),
+        Err(ManagerError::EnvironmentDoesNotExist(_))
+    ));
+}
+
+/// An [`Agent`] that finishes a block when it shuts down.
+struct Miner;
+
+#[async_trait::async_trait]
+impl Agent for Miner {
+    async fn shutdown(&mut self, client: Arc<RevmMiddleware>) -> Result<(), AgentError> {
+        client.mine()?;
+        Ok(())
+    }
+}
+
+#[test]
+fn agent_shutdown_is_not_held_up_by_clock() -> Result<()> {
+    let mut manager = Manager::new();
+    let labels = [TEST_ENV_LABEL, "idle"];
+    for label in labels {
+        let params = EnvironmentParameters {
+            block_rate: TEST_BLOCK_RATE,
+            seed: TEST_ENV_SEED,
+        };
+        manager.add_environment(label, params)?;
+    }
+    let params = ClockParameters {
+        genesis_timestamp: 1_000,
+        block_time: 12,
+    };
+    manager.add_clock("clock", &labels, params)?;
+    for label in labels {
+        manager.start_environment(label)?;
+    }
+    manager.add_agent("miner", TEST_ENV_LABEL, Miner)?;
+
+    // The idle environment never finishes a block, so the block finished by the
+    // miner only ends once its environment has left the clock
+    manager.stop_environment(TEST_ENV_LABEL)?;
+    manager.stop_environment("idle")?;
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Differential tests of the Rust mirrors of `ArbiterMath` against the
+//! deployed contract.
+
+use ethers::{
+    contract::ContractCall,
+    types::{I256, U256},
+};
+use rand::{rngs::StdRng, Rng};
+
+use super::{contracts::startup, *};
+use crate::{
+    differential::{DifferentialReport, DifferentialTest, Tolerance},
+    fixed_point::{FixedPointError, Wad},
+};
+
+const SAMPLES: usize = 256;
+const SEED: u64 = 1234;
+const ONE: i128 = 1_000_000_000_000_000_000;
+
+/// Asserts the report has no mismatches, showing the first ones otherwise.
+fn check<I: std::fmt::Debug, O: std::fmt::Debug>(report: DifferentialReport<I, O>) {
+    assert!(
+        report.passed(),
+        "{} differs from the contract: {}\n{:#?}",
+        report.name,
+        report,
+        report.mismatches.iter().take(5).collect::<Vec<_>>()
+    );
+}
+
+/// A `uint256` whose magnitude is spread over all bit lengths.
+fn wide(rng: &mut StdRng) -> U256 {
+    let bits = rng.gen_range(0..=256);
+    let value = U256([rng.gen(), rng.gen(), rng.gen(), rng.gen()]);
+    if bits == 256 {
+        value
+    } else {
+        value & ((U256::one() << bits) - 1)
+    }
+}
+
+/// A WAD uniformly drawn from `[low, high)` given in units of `1e18`.
+fn wad(rng: &mut StdRng, low: f64, high: f64) -> I256 {
+    I256::from(rng.gen_range((low * ONE as f64) as i128..(high * ONE as f64) as i128))
+}
+
+/// A value that is mostly a WAD in `[low, high)` but sometimes any `int256`.
+fn input(rng: &mut StdRng, low: f64, high: f64) -> I256 {
+    if rng.gen_bool(0.9) {
+        wad(rng, low, high)
+    } else {
+        I256::from_raw(wide(rng))
+    }
+}
+
+#[tokio::test]
+async fn gaussian_mirrors() -> Result<()> {
+    let (_manager, client) = startup()?;
+    let arbiter_math = ArbiterMath::deploy(client, ())?.send().await?;
+
+    let report = DifferentialTest::new("cdf", SAMPLES, SEED)
+        .run(
+            |rng| input(rng, -10.0, 10.0),
+            |x| cdf(*x),
+            |x| arbiter_math.cdf(*x),
+        )
+        .await;
+    check(report);
+
+    let report = DifferentialTest::new("pdf", SAMPLES, SEED)
+        .run(
+            |rng| input(rng, -12.0, 12.0),
+            |x| pdf(*x),
+            |x| arbiter_math.pdf(*x),
+        )
+        .await;
+    check(report);
+
+    let report = DifferentialTest::new("ppf", SAMPLES, SEED)
+        .run(
+            |rng| input(rng, -0.1, 1.1),
+            |x| ppf(*x),
+            |x| arbiter_math.ppf(*x),
+        )
+        .await;
+    check(report);
+    Ok(())
+}
+
+#[tokio::test]
+async fn fixed_point_mirrors() -> Result<()> {
+    let (_manager, client) = startup()?;
+    let arbiter_math = ArbiterMath::deploy(client, ())?.send().await?;
+
+    let report = DifferentialTest::new("log", SAMPLES, SEED)
+        .run(
+            |rng| input(rng, -10.0, 100.0),
+            |x| log(*x),
+            |x| arbiter_math.log(*x),
+        )
+        .await;
+    check(report);
+
+    let report = DifferentialTest::new("sqrt", SAMPLES, SEED)
+        .run(
+            wide,
+            |x| Ok::<_, MathError>(sqrt(*x)),
+            |x| arbiter_math.sqrt(*x),
+        )
+        .await;
+    check(report);
+
+    type WadOp = fn(Wad, Wad) -> std::result::Result<Wad, FixedPointError>;
+    type ContractOp =
+        fn(&ArbiterMath<RevmMiddleware>, U256, U256) -> ContractCall<RevmMiddleware, U256>;
+    let operations: [(&str, WadOp, ContractOp); 4] = [
+        ("mulWadDown", Wad::mul_down, ArbiterMath::mul_wad_down),
+        ("mulWadUp", Wad::mul_up, ArbiterMath::mul_wad_up),
+        ("divWadDown", Wad::div_down, ArbiterMath::div_wad_down),
+        ("divWadUp", Wad::div_up, ArbiterMath::div_wad_up),
+    ];
+    for (name, operation, contract_operation) in operations {
+        let report = DifferentialTest::new(name, SAMPLES, SEED)
+            .run(
+                |rng| (wide(rng), wide(rng)),
+                |(x, y)| operation(Wad::from_raw(*x), Wad::from_raw(*y)).map(|z| z.raw()),
+                |(x, y)| contract_operation(&arbiter_math, *x, *y),
+            )
+            .await;
+        check(report);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn invariant_mirror() -> Result<()> {
+    let (_manager, client) = startup()?;
+    let arbiter_math = ArbiterMath::deploy(client, ())?.send().await?;
+
+    let year = 31_556_952_u64;
+    let report = DifferentialTest::new("invariant", SAMPLES, SEED)
+        .run(
+            |rng| {
+                let reserve_y = wad(rng, 0.0, 5.0).into_raw();
+                let reserve_x = match rng.gen_range(0..10) {
+                    0 => U256::zero(),
+                    1 => U256::exp10(18),
+                    2 => wide(rng),
+                    _ => wad(rng, 0.0, 1.0).into_raw(),
+                };
+                let strike = wad(rng, 0.0, 5.0).into_raw();
+                let volatility = wad(rng, 0.0, 3.0).into_raw();
+                let tau = match rng.gen_range(0..4) {
+                    0 => U256::zero(),
+                    _ => U256::from(rng.gen_range(0..10 * year)),
+                };
+                (reserve_y, reserve_x, strike, volatility, tau)
+            },
+            |&(reserve_y, reserve_x, strike, volatility, tau)| {
+                invariant(reserve_y, reserve_x, strike, volatility, tau)
+            },
+            |&(reserve_y, reserve_x, strike, volatility, tau)| {
+                arbiter_math.invariant(reserve_y, reserve_x, strike, volatility, tau)
+            },
+        )
+        .await;
+    check(report);
+    Ok(())
+}
+
+#[tokio::test]
+async fn differential_test_reports_disagreement() -> Result<()> {
+    let (_manager, client) = startup()?;
+    let arbiter_math = ArbiterMath::deploy(client.clone(), ())?.send().await?;
+
+    // A model that is off by one disagrees exactly, but not up to a tolerance of
+    // one
+    let off_by_one = |x: &U256| Ok::<_, MathError>(sqrt(*x) + 1);
+    let mut test = DifferentialTest::new("sqrt", SAMPLES, SEED);
+    let report = test.run(wide, off_by_one, |x| arbiter_math.sqrt(*x)).await;
+    assert_eq!(report.mismatches.len(), SAMPLES);
+    assert_eq!(report.max_absolute_error, 1.0);
+    test.tolerance = Tolerance::Absolute(1.0);
+    let report = test.run(wide, off_by_one, |x| arbiter_math.sqrt(*x)).await;
+    assert!(report.passed());
+    test.tolerance = Tolerance::Absolute(0.5);
+    let report = test.run(wide, off_by_one, |x| arbiter_math.sqrt(*x)).await;
+    assert_eq!(report.mismatches.len(), SAMPLES);
+
+    // Failing where the contract does not revert is a mismatch
+    let report = DifferentialTest::new("sqrt", SAMPLES, SEED)
+        .run(
+            wide,
+            |_| Err::<U256, _>("the model failed"),
+            |x| arbiter_math.sqrt(*x),
+        )
+        .await;
+    assert_eq!(report.mismatches.len(), SAMPLES);
+
+    // So is a contract call that fails without reverting, here because there is
+    // no contract whose output could be decoded
+    let no_contract = ArbiterMath::new(client.default_sender().unwrap(), client);
+    let report = DifferentialTest::new("sqrt", SAMPLES, SEED)
+        .run(
+            wide,
+            |_| Err::<U256, _>("the model failed"),
+            |x| no_contract.sqrt(*x),
+        )
+        .await;
+    assert_eq!(report.reverts, 0);
+    assert_eq!(report.mismatches.len(), SAMPLES);
+
+    // Whereas the model failing where the contract reverts is agreement
+    let report = DifferentialTest::new("log", SAMPLES, SEED)
+        .run(
+            |rng| -wad(rng, 0.0, 10.0),
+            |x| log(*x),
+            |x| arbiter_math.log(*x),
+        )
+        .await;
+    assert!(report.passed());
+    assert_eq!(report.reverts, SAMPLES);
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use super::*;
+use crate::{
+    arbitrageur::*,
+    bindings::{constant_product_pool::*, liquid_exchange::*},
+};
+
+const ARBITRAGEUR_LABEL: &str = "arbitrageur";
+
+/// Waits for the arbitrageur to have finished acting on the given price of the
+/// exchange.
+async fn wait_for_price(inventory: &Arc<std::sync::Mutex<Inventory>>, price: f64) -> Inventory {
+    for _ in 0..500 {
+        let inventory = inventory.lock().unwrap().clone();
+        if inventory.price == price {
+            return inventory;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the arbitrageur never acted on the price {}", price);
+}
+
+/// The marginal price of token X in token Y of the pool.
+async fn pool_price(pool: &ConstantProductPool<RevmMiddleware>) -> Result<f64> {
+    Ok(wad_to_float(pool.reserve_y().call().await?) / wad_to_float(pool.reserve_x().call().await?))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn arbitrageur_aligns_the_pool_with_the_exchange() -> Result<()> {
+    let (mut manager, admin) = managed_start()?;
+    // The arbitrageur transacts from the same address as a client with its label
+    let arbitrageur_address = RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(ARBITRAGEUR_LABEL.to_string()),
+    )
+    .default_sender()
+    .unwrap();
+
+    let mut tokens = vec![];
+    for symbol in ["ARBX", "ARBY"] {
+        let token = ArbiterToken::deploy(
+            admin.clone(),
+            (symbol.to_string(), symbol.to_string(), TEST_ARG_DECIMALS),
+        )?
+        .send()
+        .await?;
+        tokens.push(token);
+    }
+    let (token_x, token_y) = (&tokens[0], &tokens[1]);
+    let liquid_exchange = LiquidExchange::deploy(
+        admin.clone(),
+        (token_x.address(), token_y.address(), float_to_wad(1000.0)),
+    )?
+    .send()
+    .await?;
+    let pool = ConstantProductPool::deploy(admin.clone(), (token_x.address(), token_y.address()))?
+        .send()
+        .await?;
+
+    let admin_address = admin.default_sender().unwrap();
+    for (token, liquidity) in [(token_x, 1000.0), (token_y, 1_000_000.0)] {
+        token
+            .mint(admin_address, float_to_wad(liquidity))
+            .send()
+            .await?
+            .await?;
+        token
+            .approve(pool.address(), float_to_wad(liquidity))
+            .send()
+            .await?
+            .await?;
+        token
+            .mint(liquid_exchange.address(), float_to_wad(1000.0 * liquidity))
+            .send()
+            .await?
+            .await?;
+        token
+            .mint(arbitrageur_address, float_to_wad(liquidity / 10.0))
+            .send()
+            .await?
+            .await?;
+    }
+    pool.add_liquidity(float_to_wad(1000.0), float_to_wad(1_000_000.0))
+        .send()
+        .await?
+        .await?;
+
+    let arbitrageur = Arbitrageur::new(
+        liquid_exchange.address(),
+        pool.address(),
+        token_x.address(),
+        token_y.address(),
+    );
+    let inventory = arbitrageur.inventory();
+    manager.add_agent(ARBITRAGEUR_LABEL, TEST_ENV_LABEL, arbitrageur)?;
+
+    // Token X gets more expensive, so the arbitrageur buys it from the pool until
+    // the pool's price is within its fee of the exchange's
+    liquid_exchange
+        .set_price(float_to_wad(1100.0))
+        .send()
+        .await?
+        .await?;
+    let after_buy = wait_for_price(&inventory, 1100.0).await;
+    assert_eq!(after_buy.trades, 1);
+    let price = pool_price(&pool).await?;
+    assert!(price < 1100.0 && price > 1100.0 * (1.0 - POOL_FEE));
+    assert!(after_buy.pnl() > 0.0);
+    assert!(after_buy.token_y > float_to_wad(100_000.0));
+
+    // Token X gets cheaper, so the arbitrageur sells it to the pool
+    liquid_exchange
+        .set_price(float_to_wad(900.0))
+        .send()
+        .await?
+        .await?;
+    let after_sell = wait_for_price(&inventory, 900.0).await;
+    assert_eq!(after_sell.trades, 2);
+    let price = pool_price(&pool).await?;
+    assert!(price > 900.0 && price < 900.0 / (1.0 - POOL_FEE));
+    assert!(after_sell.token_x > float_to_wad(100.0));
+    assert_eq!(
+        after_sell.token_x,
+        token_x.balance_of(arbitrageur_address).call().await?
+    );
+
+    // A price within the fee of the pool's is left alone
+    liquid_exchange
+        .set_price(float_to_wad(901.0))
+        .send()
+        .await?
+        .await?;
+    let after_hold = wait_for_price(&inventory, 901.0).await;
+    assert_eq!(after_hold.trades, 2);
+    manager.stop_environment(TEST_ENV_LABEL)?;
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use ethers::{
+    abi::AbiEncode,
+    types::{Bytes, U256},
+};
+
+use super::*;
+use crate::{bindings::arbiter_bridge::*, bridge::*};
+
+const TEST_BRIDGE_AMOUNT: u128 = 100;
+const TEST_BLOCK_DELAY: u64 = 1;
+
+/// Starts an L1 and an L2 with an `ArbiterBridge` deployed in each, where
+/// every `MessageSent` on the L1 is relayed to the L2 contract.
+async fn start_bridged_environments(
+    manager: &mut Manager,
+) -> Result<(
+    Arc<RevmMiddleware>,
+    ArbiterBridge<RevmMiddleware>,
+    ArbiterBridge<RevmMiddleware>,
+)> {
+    for label in ["l1", "l2"] {
+        let params = EnvironmentParameters {
+            block_rate: TEST_BLOCK_RATE,
+            seed: TEST_ENV_SEED,
+        };
+        manager.add_environment(label, params)?;
+    }
+    let l1_client = Arc::new(RevmMiddleware::new(
+        manager.environments.get("l1").unwrap(),
+        Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
+    ));
+    let l2_client = Arc::new(RevmMiddleware::new(
+        manager.environments.get("l2").unwrap(),
+        Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
+    ));
+    manager.start_environment("l1")?;
+    manager.start_environment("l2")?;
+    // The L1 client mints on the L1 and the relayer delivers on the L2
+    let relayer = Address::from_str(TEST_MINT_TO)?;
+    let l1_bridge = ArbiterBridge::deploy(l1_client.clone(), l1_client.default_sender().unwrap())?
+        .send()
+        .await?;
+    let l2_bridge = ArbiterBridge::deploy(l2_client, relayer)?.send().await?;
+
+    let params = BridgeParameters {
+        filter: l1_bridge.message_sent_filter().filter,
+        block_delay: TEST_BLOCK_DELAY,
+        relayer,
+    };
+    let l2_bridge_address = l2_bridge.address();
+    manager.add_bridge("l1_to_l2", "l1", "l2", params, move |log| {
+        let sent = MessageSentFilter::decode_log(&log.clone().into()).ok()?;
+        Some(BridgeMessage {
+            to: l2_bridge_address,
+            data: Bytes::from(
+                DeliverCall {
+                    recipient: sent.recipient,
+                    amount: sent.amount,
+                }
+                .encode(),
+            ),
+        })
+    })?;
+    Ok((l1_client, l1_bridge, l2_bridge))
+}
+
+/// Waits for the bridge to deliver to `recipient` on its own thread and
+/// returns the balance it ends up with.
+async fn delivered_balance(
+    l2_bridge: &ArbiterBridge<RevmMiddleware>,
+    recipient: Address,
+) -> Result<U256> {
+    let mut balance = U256::zero();
+    for _ in 0..100 {
+        balance = l2_bridge.balance_of(recipient).call().await?;
+        if !balance.is_zero() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    Ok(balance)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn bridge_delivers_messages_after_delay() -> Result<()> {
+    let mut manager = Manager::new();
+    let (l1_client, l1_bridge, l2_bridge) = start_bridged_environments(&mut manager).await?;
+
+    let recipient = Address::from_str(TEST_MINT_TO)?;
+    l1_bridge
+        .mint(
+            l1_client.default_sender().unwrap(),
+            TEST_BRIDGE_AMOUNT.into(),
+        )
+        .send()
+        .await?
+        .await?;
+    let receipt = l1_bridge
+        .send(recipient, TEST_BRIDGE_AMOUNT.into())
+        .send()
+        .await?
+        .await?
+        .unwrap();
+    let sent_in = receipt.block_number.unwrap();
+    assert_eq!(
+        l1_bridge
+            .balance_of(l1_client.default_sender().unwrap())
+            .call()
+            .await?,
+        0.into()
+    );
+
+    // Nothing can be delivered until the L1 has moved on by `TEST_BLOCK_DELAY`
+    // blocks
+    while l1_client.get_block_number().await? < sent_in + TEST_BLOCK_DELAY {
+        assert_eq!(l2_bridge.balance_of(recipient).call().await?, 0.into());
+        l1_bridge
+            .mint(l1_client.default_sender().unwrap(), 0.into())
+            .send()
+            .await?
+            .await?;
+    }
+
+    assert_eq!(
+        delivered_balance(&l2_bridge, recipient).await?,
+        TEST_BRIDGE_AMOUNT.into()
+    );
+
+    // Only the operator of a bridge can mint or deliver
+    assert!(l2_bridge.mint(recipient, 1.into()).send().await.is_err());
+    assert!(l2_bridge.deliver(recipient, 1.into()).send().await.is_err());
+    assert_eq!(
+        l2_bridge.balance_of(recipient).call().await?,
+        TEST_BRIDGE_AMOUNT.into()
+    );
+
+    manager.stop_environment("l1")?;
+    manager.stop_environment("l2")?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn bridge_delivers_on_finished_blocks_and_stops_with_environment() -> Result<()> {
+    let mut manager = Manager::new();
+    let (l1_client, l1_bridge, l2_bridge) = start_bridged_environments(&mut manager).await?;
+    let sender = l1_client.default_sender().unwrap();
+    let recipient = Address::from_str(TEST_MINT_TO)?;
+    l1_bridge
+        .mint(sender, TEST_BRIDGE_AMOUNT.into())
+        .send()
+        .await?
+        .await?;
+    l1_bridge
+        .send(recipient, TEST_BRIDGE_AMOUNT.into())
+        .send()
+        .await?
+        .await?;
+
+    // Finishing blocks delivers the message without any further events
+    for _ in 0..TEST_BLOCK_DELAY {
+        l1_client.mine()?;
+    }
+    assert_eq!(
+        delivered_balance(&l2_bridge, recipient).await?,
+        TEST_BRIDGE_AMOUNT.into()
+    );
+
+    // Stopping the destination joins the bridge, and the source carries on
+    // without it
+    manager.stop_environment("l2")?;
+    assert!(manager.bridges.get("l1_to_l2").unwrap().handle.is_none());
+    l1_bridge.mint(sender, 1.into()).send().await?.await?;
+    l1_bridge.send(recipient, 1.into()).send().await?.await?;
+    manager.stop_environment("l1")?;
+    Ok(())
+}
+
+#[test]
+fn bridge_requires_existing_environments() {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: TEST_BLOCK_RATE,
+        seed: TEST_ENV_SEED,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params).unwrap();
+    let params = BridgeParameters {
+        filter: Filter::new(),
+        block_delay: TEST_BLOCK_DELAY,
+        relayer: Address::zero(),
+    };
+    let result = manager.add_bridge("bridge", TEST_ENV_LABEL, "missing", params, |_| None);
+    assert!(matches!(
+        result,
+        Err(ManagerError::EnvironmentDoesNotExist(label)) if label == "missing"
+    ));
+    assert!(manager.bridges.is_empty());
+}
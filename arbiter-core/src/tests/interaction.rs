@@ -5,6 +5,9 @@ use std::{
 
 use anyhow::Ok;
 use assert_matches::assert_matches;
+use ethers::{
+    contract::ContractError, providers::ProviderError, types::transaction::eip712::TypedData,
+};
 use futures::stream::Stream;
 
 use super::*;
@@ -231,7 +234,7 @@ async fn transaction_loop() -> Result<()> {
     // tx_0 is the transaction that creates the token contract
     let (arbiter_token, env, client) = deploy_and_start().await?;
 
-    let mut dist = env.seeded_poisson.clone();
+    let mut dist = env.block_sizes.clone();
     let expected_tx_per_block = dist.sample();
     println!("expected_tx_per_block: {}", expected_tx_per_block);
 
@@ -302,3 +305,504 @@ async fn pause_prevents_processing_transactions() {
         .await;
     assert!(arbiter_math_2.is_ok());
 }
+
+#[tokio::test]
+async fn read_state() -> Result<()> {
+    let (arbiter_token, _environment, client) = deploy_and_start().await?;
+    let sender = client.default_sender().unwrap();
+
+    assert_eq!(client.get_block_number().await?, U64::from(0));
+    assert_eq!(client.get_chainid().await?, ethers::types::U256::from(1));
+    assert_eq!(
+        client.get_balance(sender, None).await?,
+        ethers::types::U256::zero()
+    );
+    // The deployment of the token bumped the nonce of its deployer
+    assert_eq!(
+        client.get_transaction_count(sender, None).await?,
+        ethers::types::U256::one()
+    );
+
+    let code = client.get_code(arbiter_token.address(), None).await?;
+    assert!(!code.is_empty());
+    assert!(client.get_code(sender, None).await?.is_empty());
+
+    // The `admin` of the token comes after the six slots of the solmate `ERC20`
+    let admin = client
+        .get_storage_at(
+            arbiter_token.address(),
+            ethers::types::H256::from_low_u64_be(6),
+            None,
+        )
+        .await?;
+    assert_eq!(Address::from(admin), sender);
+    Ok(())
+}
+
+#[tokio::test]
+async fn unsupported_request() -> Result<()> {
+    let (_arbiter_token, _environment, client) = deploy_and_start().await?;
+    let gas_price = client
+        .provider()
+        .request::<_, ethers::types::U256>("eth_gasPrice", ())
+        .await;
+    assert_matches!(gas_price, Err(ProviderError::UnsupportedRPC));
+    Ok(())
+}
+
+#[tokio::test]
+async fn eth_transfers() -> Result<()> {
+    let (_arbiter_token, environment, client) = deploy_and_start().await?;
+    let receiver = RevmMiddleware::new(&environment, Some("receiver".to_string()));
+    let sender_address = client.default_sender().unwrap();
+    let receiver_address = receiver.default_sender().unwrap();
+    let one_ether = ethers::types::U256::exp10(18);
+
+    // Wallets start out without ETH, so the transfer is rejected
+    let transfer = ethers::types::TransactionRequest::new()
+        .to(receiver_address)
+        .value(one_ether);
+    assert_matches!(
+        client.send_transaction(transfer.clone(), None).await,
+        Err(RevmMiddlewareError::InvalidTransaction(_))
+    );
+    assert_matches!(
+        client.call(&transfer.clone().into(), None).await,
+        Err(RevmMiddlewareError::InvalidTransaction(_))
+    );
+
+    // Once funded by the faucet, the environment processes the same transfer
+    client.deal(sender_address, one_ether * 3)?;
+    assert_eq!(
+        client.get_balance(sender_address, None).await?,
+        one_ether * 3
+    );
+    client.send_transaction(transfer, None).await?.await?;
+    assert_eq!(
+        client.get_balance(sender_address, None).await?,
+        one_ether * 2
+    );
+    assert_eq!(
+        receiver.get_balance(receiver_address, None).await?,
+        one_ether
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn deal_after_read() -> Result<()> {
+    let (_arbiter_token, _environment, client) = deploy_and_start().await?;
+    let address = Address::random();
+    assert!(client.get_balance(address, None).await?.is_zero());
+    client.deal(address, 1000.into())?;
+    assert_eq!(client.get_balance(address, None).await?, 1000.into());
+    Ok(())
+}
+
+#[tokio::test]
+async fn nonces() -> Result<()> {
+    let (arbiter_token, _environment, client) = deploy_and_start().await?;
+    let sender = client.default_sender().unwrap();
+    assert_eq!(client.get_transaction_count(sender, None).await?, 1.into());
+
+    // The current nonce is accepted and bumped, a stale one is rejected
+    let mint = arbiter_token.mint(sender, 1.into());
+    mint.clone().nonce(1).send().await?.await?;
+    assert_eq!(client.get_transaction_count(sender, None).await?, 2.into());
+    assert_matches!(
+        mint.clone().nonce(1).send().await,
+        Err(ContractError::MiddlewareError {
+            e: RevmMiddlewareError::InvalidTransaction(_)
+        })
+    );
+
+    // Transactions without a nonce use the current one
+    mint.send().await?.await?;
+    assert_eq!(client.get_transaction_count(sender, None).await?, 3.into());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_nonces() -> Result<()> {
+    let (arbiter_token, _environment, client) = deploy_and_start().await?;
+    let sender = client.default_sender().unwrap();
+
+    // Transactions sent at once by the same client each take their own nonce
+    let sends = (0..10).map(|_| {
+        let mint = arbiter_token.mint(sender, 1.into());
+        tokio::spawn(async move { mint.send().await.map(|_| ()) })
+    });
+    for sent in futures::future::join_all(sends).await {
+        sent??;
+    }
+    assert_eq!(client.get_transaction_count(sender, None).await?, 11.into());
+    assert_eq!(arbiter_token.balance_of(sender).call().await?, 10.into());
+    Ok(())
+}
+
+#[tokio::test]
+async fn disabled_nonce_checks() -> Result<()> {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: 1.0,
+        seed: 1,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params)?;
+    manager.disable_nonce_checks(TEST_ENV_LABEL)?;
+    manager.start_environment(TEST_ENV_LABEL)?;
+    assert!(manager.disable_nonce_checks(TEST_ENV_LABEL).is_err());
+    let client = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
+    ));
+    let arbiter_math = ArbiterMath::deploy(client.clone(), ())?
+        .nonce(42)
+        .send()
+        .await;
+    assert!(arbiter_math.is_ok());
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_addresses() -> Result<()> {
+    let (_arbiter_token, _environment, client) = deploy_and_start().await?;
+    let args = (
+        TEST_ARG_NAME.to_string(),
+        TEST_ARG_SYMBOL.to_string(),
+        TEST_ARG_DECIMALS,
+    );
+
+    let predicted = client.next_create_address().await?;
+    let arbiter_token = ArbiterToken::deploy(client.clone(), args.clone())?
+        .send()
+        .await?;
+    assert_eq!(arbiter_token.address(), predicted);
+
+    let salt = ethers::types::H256::from_low_u64_be(1);
+    let deployment = ArbiterToken::deploy(client.clone(), args)?.deployer.tx;
+    let predicted = client.create2_address(&deployment, salt);
+    let address = client.deploy_create2(deployment.clone(), salt).await?;
+    assert_eq!(address, predicted);
+    let arbiter_token = ArbiterToken::new(address, client.clone());
+    assert_eq!(arbiter_token.symbol().call().await?, TEST_ARG_SYMBOL);
+
+    // The same salt and init code cannot be deployed twice
+    assert!(client.deploy_create2(deployment, salt).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn gas() -> Result<()> {
+    let (arbiter_token, _environment, client) = deploy_and_start().await?;
+    let sender = client.default_sender().unwrap();
+
+    // The estimate is exactly enough gas for the transaction to succeed
+    let mint = arbiter_token.mint(sender, 1.into());
+    let estimate = mint.estimate_gas().await?;
+    assert!(estimate > 21000.into());
+    assert_matches!(
+        mint.clone().gas(estimate - 1).send().await,
+        Err(ContractError::MiddlewareError {
+            e: RevmMiddlewareError::ExecutionHalt { .. }
+        })
+    );
+    assert_eq!(arbiter_token.balance_of(sender).call().await?, 0.into());
+    mint.gas(estimate).send().await?.await?;
+    assert_eq!(arbiter_token.balance_of(sender).call().await?, 1.into());
+
+    // Calls report the gas they used
+    let (output, gas_used) = client
+        .call_with_gas(&arbiter_token.balance_of(sender).tx, None)
+        .await?;
+    assert_eq!(ethers::types::U256::from_big_endian(&output), 1.into());
+    assert!(gas_used > 21000);
+    Ok(())
+}
+
+#[tokio::test]
+async fn signing() -> Result<()> {
+    let (arbiter_token, _environment, client) = deploy_and_start().await?;
+    let sender = client.default_sender().unwrap();
+
+    // Messages are signed by the wallet of the client and no one else
+    let signature = client.sign(b"arbiter".to_vec(), &sender).await?;
+    assert_eq!(signature.recover("arbiter")?, sender);
+    assert_matches!(
+        client.sign(b"arbiter".to_vec(), &Address::zero()).await,
+        Err(RevmMiddlewareError::Signing(_))
+    );
+
+    // So is EIP-712 typed data
+    let permit: TypedData = serde_json::from_value(serde_json::json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Permit": [
+                { "name": "owner", "type": "address" },
+                { "name": "value", "type": "uint256" }
+            ]
+        },
+        "primaryType": "Permit",
+        "domain": {
+            "name": "Arbiter Token",
+            "chainId": 1,
+            "verifyingContract": arbiter_token.address()
+        },
+        "message": { "owner": sender, "value": "1000" }
+    }))?;
+    let signature = client.sign_typed_data(&permit).await?;
+    assert_eq!(signature.recover_typed_data(&permit)?, sender);
+
+    // Transactions are signed with their nonce and chain id filled in
+    let mint = arbiter_token.mint(sender, 1.into());
+    let tx_hash = *mint.send().await?;
+    let mut tx = mint.tx.clone();
+    tx.set_from(sender).set_nonce(1).set_chain_id(1);
+    let signature = client.sign_transaction(&tx, sender).await?;
+    assert_eq!(signature.recover(tx.sighash())?, sender);
+    assert_eq!(tx.hash(&signature), tx_hash);
+    Ok(())
+}
+
+#[tokio::test]
+async fn historical_state() -> Result<()> {
+    // Deploy in block 0, then mint once in each of the blocks 1 to 3
+    let (arbiter_token, mut manager, client) =
+        deploy_and_start_with_single_transaction_blocks(2).await?;
+    assert!(manager.set_state_retention(TEST_ENV_LABEL, 3).is_err());
+    let sender = client.default_sender().unwrap();
+    for block in 1..=3 {
+        arbiter_token.mint(sender, 1.into()).send().await?.await?;
+        client.deal(sender, (100 * block).into())?;
+    }
+    assert_eq!(client.get_block_number().await?, 3.into());
+
+    // Only the state of the last two finished blocks is retained
+    let balance_of = arbiter_token.balance_of(sender);
+    assert_eq!(balance_of.clone().block(1).call().await?, 1.into());
+    assert_eq!(balance_of.clone().block(2).call().await?, 2.into());
+    assert_eq!(balance_of.clone().block(3).call().await?, 3.into());
+    assert!(balance_of.clone().block(0).call().await.is_err());
+    assert!(balance_of.clone().block(4).call().await.is_err());
+
+    // Gas is estimated on the state of the given block as well
+    let mint = arbiter_token.mint(sender, 1.into());
+    assert!(mint.clone().block(1).estimate_gas().await? > 21000.into());
+    assert!(mint.clone().block(0).estimate_gas().await.is_err());
+    assert_eq!(
+        client.get_balance(sender, Some(1.into())).await?,
+        100.into()
+    );
+    assert_eq!(client.get_balance(sender, None).await?, 300.into());
+    assert!(client.get_balance(sender, Some(0.into())).await.is_err());
+
+    // The balance is kept at the `keccak256(abi.encode(sender, 3))` slot
+    let slot = ethers::utils::keccak256(ethers::abi::encode(&[
+        ethers::abi::Token::Address(sender),
+        ethers::abi::Token::Uint(3.into()),
+    ]));
+    let value = client
+        .get_storage_at(arbiter_token.address(), slot.into(), Some(2.into()))
+        .await?;
+    assert_eq!(ethers::types::U256::from(value.as_bytes()), 2.into());
+    Ok(())
+}
+
+#[tokio::test]
+async fn new_blocks() -> Result<()> {
+    // Deploy in block 0 and mint in block 1, which finishes block 0, and in
+    // block 2, which finishes block 1
+    let (arbiter_token, _manager, client) =
+        deploy_and_start_with_single_transaction_blocks(0).await?;
+    let sender = client.default_sender().unwrap();
+    let mut blocks = client.watch_blocks().await?;
+    let mut headers = client.watch_headers().await?;
+    assert_ne!(blocks.id, headers.id);
+
+    // Polling a filter hands out what arrived so far without waiting for more
+    let changes: Vec<ethers::types::H256> = client.provider().get_filter_changes(blocks.id).await?;
+    assert!(changes.is_empty());
+    arbiter_token.mint(sender, 1.into()).send().await?.await?;
+    arbiter_token.mint(sender, 1.into()).send().await?.await?;
+
+    let deployment = headers.next().await.unwrap();
+    assert_eq!(deployment.number, 0.into());
+    assert_eq!(deployment.transaction_count, 1);
+    assert!(deployment.gas_used > 21000);
+    let mint = headers.next().await.unwrap();
+    assert_eq!(mint.number, 1.into());
+    assert_eq!(mint.transaction_count, 1);
+    assert!(mint.gas_used < deployment.gas_used);
+
+    assert_eq!(blocks.next().await, Some(deployment.hash()));
+    assert_eq!(blocks.next().await, Some(mint.hash()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn subscriptions() -> Result<()> {
+    // Deploy in block 0 and mint in block 1, which finishes block 0, and in
+    // block 2, which finishes block 1
+    let (arbiter_token, _manager, client) =
+        deploy_and_start_with_single_transaction_blocks(0).await?;
+    let sender = client.default_sender().unwrap();
+    let mut blocks = client.subscribe_blocks().await?;
+    let filter = Filter::new().address(arbiter_token.address());
+    let mut logs = client.subscribe_logs(&filter).await?;
+    let transfer_filter = arbiter_token.transfer_filter();
+    let mut transfers = transfer_filter.subscribe().await?;
+    arbiter_token.mint(sender, 1.into()).send().await?.await?;
+    arbiter_token.mint(sender, 2.into()).send().await?.await?;
+
+    let log = logs.next().await.unwrap();
+    assert_eq!(log.address, arbiter_token.address());
+    assert_eq!(log.block_number, Some(1.into()));
+    let transfer = transfers.next().await.unwrap()?;
+    assert_eq!(transfer.to, sender);
+    assert_eq!(transfer.amount, 1.into());
+    let transfer = transfers.next().await.unwrap()?;
+    assert_eq!(transfer.amount, 2.into());
+
+    let deployment = blocks.next().await.unwrap();
+    assert_eq!(deployment.number, Some(0.into()));
+    assert!(deployment.gas_used > 21000.into());
+    let mint = blocks.next().await.unwrap();
+    assert_eq!(mint.number, Some(1.into()));
+    assert_eq!(mint.parent_hash, deployment.hash.unwrap());
+
+    // Later subscriptions know the parent of the first block they receive
+    let mut later_blocks = client.subscribe_blocks().await?;
+    arbiter_token.mint(sender, 3.into()).send().await?.await?;
+    let later = later_blocks.next().await.unwrap();
+    assert_eq!(later.number, Some(2.into()));
+    assert_eq!(later.parent_hash, mint.hash.unwrap());
+
+    assert!(logs.unsubscribe().await?);
+    assert!(!client.unsubscribe(logs.id).await?);
+    assert!(client.subscribe_pending_txs().await.is_err());
+
+    // Subscriptions that are never streamed are dropped
+    let id: ethers::types::U256 = client
+        .provider()
+        .request("eth_subscribe", ["newHeads"])
+        .await?;
+    tokio::time::sleep(std::time::Duration::from_secs(6)).await;
+    assert!(!client.unsubscribe(id).await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn past_logs() -> Result<()> {
+    // Deploy in block 0, then mint, approve and mint in the blocks 1 to 3
+    // without any filter watching
+    let (arbiter_token, _manager, client) =
+        deploy_and_start_with_single_transaction_blocks(0).await?;
+    let sender = client.default_sender().unwrap();
+    let receiver = Address::from_str(TEST_MINT_TO)?;
+    arbiter_token.mint(sender, 1.into()).send().await?.await?;
+    arbiter_token
+        .approve(receiver, TEST_APPROVAL_AMOUNT.into())
+        .send()
+        .await?
+        .await?;
+    arbiter_token.mint(receiver, 2.into()).send().await?.await?;
+
+    let logs = client
+        .get_logs(&Filter::new().address(arbiter_token.address()).from_block(0))
+        .await?;
+    assert_eq!(logs.len(), 3);
+    for (log, block) in logs.iter().zip(1_u64..) {
+        assert_eq!(log.block_number, Some(block.into()));
+        assert_eq!(log.log_index, Some(0.into()));
+    }
+
+    // Events can be queried by block range and by topic
+    let transfers = arbiter_token
+        .transfer_filter()
+        .from_block(0)
+        .query()
+        .await?;
+    assert_eq!(transfers.len(), 2);
+    let approvals = arbiter_token
+        .approval_filter()
+        .from_block(2)
+        .to_block(2)
+        .query()
+        .await?;
+    assert_eq!(approvals.len(), 1);
+    assert_eq!(approvals[0].amount, TEST_APPROVAL_AMOUNT.into());
+    let to_receiver = arbiter_token
+        .transfer_filter()
+        .from_block(0)
+        .topic2(receiver)
+        .query()
+        .await?;
+    assert_eq!(to_receiver.len(), 1);
+    assert_eq!(to_receiver[0].amount, 2.into());
+
+    // A range that ends before it starts holds no logs
+    let reversed = Filter::new()
+        .address(arbiter_token.address())
+        .from_block(3)
+        .to_block(1);
+    assert!(client.get_logs(&reversed).await?.is_empty());
+    assert!(client
+        .get_logs(&Filter::new().from_block(3).to_block(1))
+        .await?
+        .is_empty());
+
+    // Without a range only the current block is searched
+    assert_eq!(arbiter_token.transfer_filter().query().await?.len(), 1);
+    assert!(arbiter_token.approval_filter().query().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn log_retention() -> Result<()> {
+    let (arbiter_token, mut manager, client) =
+        deploy_and_start_with_single_transaction_blocks(0).await?;
+    let sender = client.default_sender().unwrap();
+    manager.set_log_retention(TEST_ENV_LABEL, 2)?;
+
+    // Mint once in each of the blocks 1 to 4, of which only the logs of the
+    // blocks 3 and 4 are kept
+    for amount in 1..=4 {
+        arbiter_token
+            .mint(sender, amount.into())
+            .send()
+            .await?
+            .await?;
+    }
+    let transfers = arbiter_token
+        .transfer_filter()
+        .from_block(0)
+        .query()
+        .await?;
+    let amounts: Vec<_> = transfers.iter().map(|transfer| transfer.amount).collect();
+    assert_eq!(amounts, vec![3.into(), 4.into()]);
+    let to_sender = arbiter_token
+        .transfer_filter()
+        .from_block(0)
+        .topic2(sender)
+        .query()
+        .await?;
+    assert_eq!(to_sender.len(), 2);
+
+    // Without any retention no logs are kept at all
+    manager.set_log_retention(TEST_ENV_LABEL, 0)?;
+    arbiter_token.mint(sender, 5.into()).send().await?.await?;
+    assert!(arbiter_token
+        .transfer_filter()
+        .from_block(0)
+        .query()
+        .await?
+        .is_empty());
+    assert!(matches!(
+        manager.set_log_retention("nonexistent", 1),
+        Err(ManagerError::EnvironmentDoesNotExist(_))
+    ));
+    Ok(())
+}
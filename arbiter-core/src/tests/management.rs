@@ -8,13 +8,11 @@ fn add_environment() {
         seed: 1,
     };
     manager.add_environment(TEST_ENV_LABEL, params).unwrap();
-    assert!(manager
-        .environments
-        .contains_key(&TEST_ENV_LABEL.to_string()));
+    assert!(manager.environments.contains_key(TEST_ENV_LABEL));
     assert_eq!(
         manager
             .environments
-            .get(&TEST_ENV_LABEL.to_string())
+            .get(TEST_ENV_LABEL)
             .unwrap()
             .state
             .load(std::sync::atomic::Ordering::Relaxed),
@@ -34,7 +32,7 @@ fn run_environment() {
     assert_eq!(
         manager
             .environments
-            .get(&TEST_ENV_LABEL.to_string())
+            .get(TEST_ENV_LABEL)
             .unwrap()
             .state
             .load(std::sync::atomic::Ordering::Relaxed),
@@ -56,7 +54,7 @@ fn pause_environment() {
     assert_eq!(
         manager
             .environments
-            .get(&TEST_ENV_LABEL.to_string())
+            .get(TEST_ENV_LABEL)
             .unwrap()
             .state
             .load(std::sync::atomic::Ordering::Relaxed),
@@ -67,7 +65,7 @@ fn pause_environment() {
     assert_eq!(
         manager
             .environments
-            .get(&TEST_ENV_LABEL.to_string())
+            .get(TEST_ENV_LABEL)
             .unwrap()
             .state
             .load(std::sync::atomic::Ordering::Relaxed),
@@ -88,7 +86,7 @@ fn stop_environment() {
     assert_eq!(
         manager
             .environments
-            .get(&TEST_ENV_LABEL.to_string())
+            .get(TEST_ENV_LABEL)
             .unwrap()
             .state
             .load(std::sync::atomic::Ordering::Relaxed),
@@ -118,7 +116,7 @@ async fn stop_environment_after_transactions() -> Result<()> {
     assert_eq!(
         manager
             .environments
-            .get(&TEST_ENV_LABEL.to_string())
+            .get(TEST_ENV_LABEL)
             .unwrap()
             .state
             .load(std::sync::atomic::Ordering::Relaxed),
@@ -126,3 +124,241 @@ async fn stop_environment_after_transactions() -> Result<()> {
     );
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn clock_keeps_environments_in_lockstep() -> Result<()> {
+    let mut manager = Manager::new();
+    let labels = ["l1", "l2"];
+    for label in labels {
+        let params = EnvironmentParameters {
+            block_rate: 1.0,
+            seed: 1,
+        };
+        manager.add_environment(label, params)?;
+    }
+    let params = ClockParameters {
+        genesis_timestamp: 1_000,
+        block_time: 12,
+    };
+    manager.add_clock("clock", &labels, params)?;
+    let clock = manager.clocks.get("clock").unwrap().clone();
+    assert_eq!(
+        clock.now()?,
+        SimulationTime {
+            block_number: 0,
+            timestamp: 1_000
+        }
+    );
+
+    let mut handles = vec![];
+    for label in labels {
+        let environment = manager.environments.get(label).unwrap();
+        let transactions_per_block = environment.block_sizes.clone().sample();
+        let client = Arc::new(RevmMiddleware::new(environment, Some(label.to_string())));
+        manager.start_environment(label)?;
+
+        // Fill the first block and then send one more transaction which can only be
+        // processed once both environments have finished the first block.
+        handles.push(tokio::spawn(async move {
+            for _ in 0..transactions_per_block + 1 {
+                ArbiterMath::deploy(client.clone(), ())
+                    .unwrap()
+                    .send()
+                    .await
+                    .unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.await?;
+    }
+
+    assert_eq!(
+        clock.now()?,
+        SimulationTime {
+            block_number: 1,
+            timestamp: 1_012
+        }
+    );
+    for label in labels {
+        manager.stop_environment(label)?;
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn mine_idle_environment_on_clock() -> Result<()> {
+    let mut manager = Manager::new();
+    let labels = ["busy", "idle"];
+    for label in labels {
+        let params = EnvironmentParameters {
+            block_rate: 1.0,
+            seed: 1,
+        };
+        manager.add_environment(label, params)?;
+        manager.set_block_sizes(label, BlockSizeDistribution::Constant { size: 1 })?;
+    }
+    let params = ClockParameters {
+        genesis_timestamp: 0,
+        block_time: 12,
+    };
+    manager.add_clock("clock", &labels, params)?;
+    let clock = manager.clocks.get("clock").unwrap().clone();
+    for label in labels {
+        manager.start_environment(label)?;
+    }
+    let busy = Arc::new(RevmMiddleware::new(
+        manager.environments.get("busy").unwrap(),
+        Some("busy".to_string()),
+    ));
+    let idle = RevmMiddleware::new(manager.environments.get("idle").unwrap(), None);
+
+    // The second deployment finishes block 0, which waits on the idle environment
+    let deployments = tokio::spawn(async move {
+        for _ in 0..2 {
+            ArbiterMath::deploy(busy.clone(), ())?.send().await?;
+        }
+        Ok::<_, anyhow::Error>(())
+    });
+    let next_block = tokio::task::spawn_blocking(move || idle.mine()).await??;
+    assert_eq!(next_block, 1.into());
+    deployments.await??;
+    assert_eq!(clock.now()?.block_number, 1);
+
+    for label in labels {
+        manager.stop_environment(label)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn clock_requires_initialized_environments() {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: 1.0,
+        seed: 1,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params).unwrap();
+    manager.start_environment(TEST_ENV_LABEL).unwrap();
+    let params = ClockParameters {
+        genesis_timestamp: 0,
+        block_time: 12,
+    };
+    assert!(manager
+        .add_clock("clock", &[TEST_ENV_LABEL], params)
+        .is_err());
+    assert!(manager
+        .add_clock("clock", &["does_not_exist"], params)
+        .is_err());
+    manager.stop_environment(TEST_ENV_LABEL).unwrap();
+}
+
+#[test]
+fn invalid_block_rate() {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: -1.0,
+        seed: 1,
+    };
+    assert!(matches!(
+        manager.add_environment(TEST_ENV_LABEL, params),
+        Err(ManagerError::InvalidBlockSizes(_))
+    ));
+    assert!(manager.environments.is_empty());
+}
+
+#[tokio::test]
+async fn constant_block_sizes() -> Result<()> {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: 1.0,
+        seed: 1,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params)?;
+    assert!(matches!(
+        manager.set_block_sizes(
+            TEST_ENV_LABEL,
+            BlockSizeDistribution::Uniform { min: 2, max: 1 }
+        ),
+        Err(ManagerError::InvalidBlockSizes(_))
+    ));
+    manager.set_block_sizes(TEST_ENV_LABEL, BlockSizeDistribution::Constant { size: 2 })?;
+    let client = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
+    ));
+    manager.start_environment(TEST_ENV_LABEL)?;
+    assert!(matches!(
+        manager.set_block_sizes(TEST_ENV_LABEL, BlockSizeDistribution::Constant { size: 1 }),
+        Err(ManagerError::EnvironmentAlreadyRunning(_))
+    ));
+
+    // Every block holds exactly two transactions
+    let arbiter_token = ArbiterToken::deploy(
+        client.clone(),
+        (
+            TEST_ARG_NAME.to_string(),
+            TEST_ARG_SYMBOL.to_string(),
+            TEST_ARG_DECIMALS,
+        ),
+    )?
+    .send()
+    .await?;
+    let to = Address::from_str(TEST_MINT_TO)?;
+    let mut block_numbers = vec![];
+    for _ in 0..5 {
+        let receipt = arbiter_token
+            .approve(to, TEST_APPROVAL_AMOUNT.into())
+            .send()
+            .await?
+            .await?
+            .unwrap();
+        block_numbers.push(receipt.block_number.unwrap().as_u64());
+    }
+    assert_eq!(block_numbers, vec![0, 1, 1, 2, 2]);
+    manager.stop_environment(TEST_ENV_LABEL)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_block_sizes_still_advance() -> Result<()> {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: 1.0,
+        seed: 1,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params)?;
+    manager.set_block_sizes(TEST_ENV_LABEL, BlockSizeDistribution::Constant { size: 0 })?;
+    let client = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
+    ));
+    manager.start_environment(TEST_ENV_LABEL)?;
+
+    // A block drawn with no room still ends, so every transaction lands in a
+    // block of its own
+    let arbiter_token = ArbiterToken::deploy(
+        client.clone(),
+        (
+            TEST_ARG_NAME.to_string(),
+            TEST_ARG_SYMBOL.to_string(),
+            TEST_ARG_DECIMALS,
+        ),
+    )?
+    .send()
+    .await?;
+    let to = Address::from_str(TEST_MINT_TO)?;
+    let mut block_numbers = vec![];
+    for _ in 0..3 {
+        let receipt = arbiter_token
+            .approve(to, TEST_APPROVAL_AMOUNT.into())
+            .send()
+            .await?
+            .await?
+            .unwrap();
+        block_numbers.push(receipt.block_number.unwrap().as_u64());
+    }
+    assert_eq!(block_numbers, vec![2, 3, 4]);
+    manager.stop_environment(TEST_ENV_LABEL)?;
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::io::{Read, Write};
+
+use super::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn metrics_count_transactions_and_calls() -> Result<()> {
+    let (arbiter_token, mut manager, client) = managed_deploy_and_start().await?;
+    arbiter_token
+        .mint(Address::from_str(TEST_MINT_TO)?, TEST_MINT_AMOUNT.into())
+        .send()
+        .await?
+        .await?;
+    arbiter_token.name().call().await?;
+
+    let metrics = manager.metrics(TEST_ENV_LABEL)?;
+    assert_eq!(metrics.transactions, 2);
+    assert_eq!(metrics.calls, 1);
+    assert_eq!(metrics.reverts, 0);
+    assert_eq!(metrics.gas_used_per_transaction.count, 2);
+    assert_eq!(
+        metrics.transactions_per_sender[&client.default_sender().unwrap()],
+        2
+    );
+
+    let address = manager.serve_metrics("127.0.0.1:0")?;
+    // A client that never sends its request holds up neither other clients nor
+    // the shutdown of the server
+    let _idle = std::net::TcpStream::connect(address)?;
+    let mut stream = std::net::TcpStream::connect(address)?;
+    stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains(&format!(
+        "arbiter_transactions_total{{environment=\"{}\"}} 2\n",
+        TEST_ENV_LABEL
+    )));
+    assert!(matches!(
+        manager.serve_metrics("127.0.0.1:0"),
+        Err(ManagerError::MetricsServer(_))
+    ));
+
+    // The listener is closed once the server's thread has been joined
+    manager.stop_metrics()?;
+    assert!(std::net::TcpStream::connect(address).is_err());
+
+    manager.stop_environment(TEST_ENV_LABEL)?;
+    Ok(())
+}
+
+#[test]
+fn metrics_require_existing_environment() {
+    let manager = Manager::new();
+    assert!(matches!(
+        manager.metrics("missing"),
+        Err(ManagerError::EnvironmentDoesNotExist(label)) if label == "missing"
+    ));
+}
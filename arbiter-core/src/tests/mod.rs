@@ -1,9 +1,18 @@
 #![allow(missing_docs)]
 
 // mod interaction;
+mod agent;
+mod arbiter_math;
+mod arbitrageur;
+mod bridge;
 mod contracts;
 mod interaction;
 mod management;
+mod metrics;
+mod observer;
+mod price_driver;
+mod recorder;
+mod server;
 mod signer;
 
 use std::{str::FromStr, sync::Arc};
@@ -15,7 +24,8 @@ use ethers::{
 };
 
 use crate::{
-    bindings::{arbiter_math::*, arbiter_token::*},
+    bindings::{arbiter_math::*, arbiter_token::*, liquid_exchange::*},
+    clock::*,
     environment::{tests::TEST_ENV_LABEL, *},
     manager::*,
     math::*,
@@ -47,7 +57,7 @@ async fn deploy_and_start() -> Result<(
         block_rate: TEST_BLOCK_RATE,
         seed: TEST_ENV_SEED,
     };
-    let mut environment = Environment::new(TEST_ENV_LABEL, params);
+    let mut environment = Environment::new(TEST_ENV_LABEL, params)?;
     let client = Arc::new(RevmMiddleware::new(
         &environment,
         Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
@@ -68,3 +78,70 @@ async fn deploy_and_start() -> Result<(
         client,
     ))
 }
+
+/// Starts a managed environment with the test parameters and connects a
+/// client to it.
+fn managed_start() -> Result<(Manager, Arc<RevmMiddleware>)> {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: TEST_BLOCK_RATE,
+        seed: TEST_ENV_SEED,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params)?;
+    manager.start_environment(TEST_ENV_LABEL)?;
+    let client = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
+    ));
+    Ok((manager, client))
+}
+
+/// Starts a managed environment with the test parameters, then deploys an
+/// [`ArbiterToken`] from a client connected to it.
+async fn managed_deploy_and_start(
+) -> Result<(ArbiterToken<RevmMiddleware>, Manager, Arc<RevmMiddleware>)> {
+    let (manager, client) = managed_start()?;
+    let arbiter_token = ArbiterToken::deploy(
+        client.clone(),
+        (
+            TEST_ARG_NAME.to_string(),
+            TEST_ARG_SYMBOL.to_string(),
+            TEST_ARG_DECIMALS,
+        ),
+    )?
+    .send()
+    .await?;
+    Ok((arbiter_token, manager, client))
+}
+
+/// Starts a managed environment that keeps the state of `state_retention`
+/// past blocks and finishes a block with every transaction, then deploys an
+/// [`ArbiterToken`] in its block 0.
+async fn deploy_and_start_with_single_transaction_blocks(
+    state_retention: usize,
+) -> Result<(ArbiterToken<RevmMiddleware>, Manager, Arc<RevmMiddleware>)> {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: 1.0,
+        seed: 1,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params)?;
+    manager.set_block_sizes(TEST_ENV_LABEL, BlockSizeDistribution::Constant { size: 1 })?;
+    manager.set_state_retention(TEST_ENV_LABEL, state_retention)?;
+    manager.start_environment(TEST_ENV_LABEL)?;
+    let client = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
+    ));
+    let arbiter_token = ArbiterToken::deploy(
+        client.clone(),
+        (
+            TEST_ARG_NAME.to_string(),
+            TEST_ARG_SYMBOL.to_string(),
+            TEST_ARG_DECIMALS,
+        ),
+    )?
+    .send()
+    .await?;
+    Ok((arbiter_token, manager, client))
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn observer_samples_every_block() -> Result<()> {
+    let (arbiter_token, mut manager, _) = managed_deploy_and_start().await?;
+    manager.add_observer(
+        "total_supply",
+        TEST_ENV_LABEL,
+        arbiter_token.total_supply(),
+        1,
+    )?;
+
+    let to = Address::from_str(TEST_MINT_TO)?;
+    let mut block_number = U64::zero();
+    while block_number < U64::from(5) {
+        let receipt = arbiter_token
+            .mint(to, TEST_MINT_AMOUNT.into())
+            .send()
+            .await?
+            .await?
+            .unwrap();
+        block_number = receipt.block_number.unwrap();
+    }
+    manager.stop_environment(TEST_ENV_LABEL)?;
+
+    let observations = manager.observations(TEST_ENV_LABEL, "total_supply")?;
+    assert!(!observations.is_empty());
+    for pair in observations.windows(2) {
+        assert_eq!(pair[1].block_number, pair[0].block_number + 1);
+        assert!(pair[1].values[0].clone().into_uint() >= pair[0].values[0].clone().into_uint());
+    }
+    // The observer's calls are made by the environment itself and are not
+    // counted as calls sent through its socket
+    assert_eq!(manager.metrics(TEST_ENV_LABEL)?.calls, 0);
+
+    let directory = std::env::temp_dir().join(format!(
+        "arbiter_observer_samples_every_block_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&directory)?;
+    let path = directory.join("observations.csv");
+    manager.export_observations(TEST_ENV_LABEL, &path)?;
+    let csv = std::fs::read_to_string(&path)?;
+    assert_eq!(csv.lines().count(), observations.len() + 1);
+    assert!(csv.starts_with("observer,block_number,block_timestamp,values\ntotal_supply,"));
+    std::fs::remove_dir_all(directory)?;
+    Ok(())
+}
+
+#[test]
+fn observer_requires_valid_call() {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters {
+        block_rate: TEST_BLOCK_RATE,
+        seed: TEST_ENV_SEED,
+    };
+    manager.add_environment(TEST_ENV_LABEL, params).unwrap();
+    let client = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        None,
+    ));
+    let arbiter_token = ArbiterToken::new(Address::zero(), client);
+    assert!(matches!(
+        manager.add_observer("supply", TEST_ENV_LABEL, arbiter_token.total_supply(), 0),
+        Err(ManagerError::InvalidObserver(_))
+    ));
+    assert!(matches!(
+        manager.observations(TEST_ENV_LABEL, "supply"),
+        Err(ManagerError::ObserverDoesNotExist(_))
+    ));
+}
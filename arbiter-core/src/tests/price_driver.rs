@@ -0,0 +1,108 @@
+use super::*;
+use crate::{agent::AgentError, price_driver::*};
+
+const PRICE_DRIVER_LABEL: &str = "price_driver";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn price_driver_walks_the_path() -> Result<()> {
+    let (arbiter_token, mut manager, client) = managed_deploy_and_start().await?;
+    // The driver may only set the price if it deployed the exchange
+    let admin = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(PRICE_DRIVER_LABEL.to_string()),
+    ));
+    let liquid_exchange = LiquidExchange::deploy(
+        admin,
+        (
+            arbiter_token.address(),
+            arbiter_token.address(),
+            float_to_wad(1000.0),
+        ),
+    )?
+    .send()
+    .await?;
+    let liquid_exchange = LiquidExchange::new(liquid_exchange.address(), client.clone());
+
+    let path = PriceProcess::GeometricBrownianMotion {
+        drift: 0.0,
+        volatility: 0.5,
+    }
+    .sample(1000.0, TimeGrid::new(0.0, 0.04, 4)?, TEST_ENV_SEED)?
+    .prices;
+    let last_price = float_to_wad(*path.last().unwrap());
+    let mut price_changes = client
+        .watch(&Filter::new().address(liquid_exchange.address()))
+        .await?;
+    let driver = PriceDriver::liquid_exchange(liquid_exchange.address(), path.clone());
+    manager.add_agent(PRICE_DRIVER_LABEL, TEST_ENV_LABEL, driver)?;
+
+    // Keep the environment moving until the whole path has been pushed
+    let to = Address::from_str(TEST_MINT_TO)?;
+    let mut transactions = 0;
+    while liquid_exchange.price().call().await? != last_price {
+        assert!(transactions < 1000, "the price path was never completed");
+        arbiter_token
+            .approve(to, TEST_APPROVAL_AMOUNT.into())
+            .send()
+            .await?
+            .await?;
+        transactions += 1;
+    }
+    manager.stop_environment(TEST_ENV_LABEL)?;
+
+    // Every price of the path was pushed in order
+    for price in path {
+        let log = price_changes.next().await.unwrap();
+        let price_change = PriceChangeFilter::decode_log(&log.into())?;
+        assert_eq!(price_change.price, float_to_wad(price));
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn price_driver_rejects_invalid_prices() -> Result<()> {
+    let (arbiter_token, mut manager, client) = managed_deploy_and_start().await?;
+    let admin = Arc::new(RevmMiddleware::new(
+        manager.environments.get(TEST_ENV_LABEL).unwrap(),
+        Some(PRICE_DRIVER_LABEL.to_string()),
+    ));
+    let liquid_exchange = LiquidExchange::deploy(
+        admin,
+        (
+            arbiter_token.address(),
+            arbiter_token.address(),
+            float_to_wad(1000.0),
+        ),
+    )?
+    .send()
+    .await?;
+    let liquid_exchange = LiquidExchange::new(liquid_exchange.address(), client.clone());
+
+    // A negative price must not be pushed as a price of zero
+    let driver = PriceDriver::liquid_exchange(liquid_exchange.address(), vec![1100.0, -1.0]);
+    manager.add_agent(PRICE_DRIVER_LABEL, TEST_ENV_LABEL, driver)?;
+    let to = Address::from_str(TEST_MINT_TO)?;
+    let mut transactions = 0;
+    while liquid_exchange.price().call().await? != float_to_wad(1100.0) {
+        assert!(transactions < 1000, "the first price was never pushed");
+        arbiter_token
+            .approve(to, TEST_APPROVAL_AMOUNT.into())
+            .send()
+            .await?
+            .await?;
+        transactions += 1;
+    }
+    for _ in 0..10 {
+        arbiter_token
+            .approve(to, TEST_APPROVAL_AMOUNT.into())
+            .send()
+            .await?
+            .await?;
+    }
+    assert_eq!(liquid_exchange.price().call().await?, float_to_wad(1100.0));
+    assert!(matches!(
+        manager.stop_environment(TEST_ENV_LABEL),
+        Err(ManagerError::Agent(AgentError::Custom(_)))
+    ));
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::sync::Mutex;
+
+use super::*;
+use crate::recorder::*;
+
+/// A [`Sink`] that keeps every record in memory.
+struct MemorySink(Arc<Mutex<Vec<Record>>>);
+
+impl Sink for MemorySink {
+    fn write(&mut self, record: &Record) -> Result<(), RecorderError> {
+        self.0.lock().unwrap().push(record.clone());
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), RecorderError> {
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn recorder_streams_decoded_events() -> Result<()> {
+    let (arbiter_token, mut manager, _) = managed_deploy_and_start().await?;
+
+    let directory = std::env::temp_dir().join(format!(
+        "arbiter_recorder_streams_decoded_events_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&directory)?;
+    let records = Arc::new(Mutex::new(vec![]));
+    manager.add_recorder(
+        "token_events",
+        TEST_ENV_LABEL,
+        Filter::new().address(arbiter_token.address()),
+        ARBITERTOKEN_ABI.clone(),
+        vec![
+            Box::new(MemorySink(records.clone())),
+            Box::new(CsvSink::new(directory.join("events.csv"))?),
+            Box::new(JsonLinesSink::new(directory.join("events.jsonl"))?),
+        ],
+    )?;
+
+    let to = Address::from_str(TEST_MINT_TO)?;
+    arbiter_token
+        .mint(to, TEST_MINT_AMOUNT.into())
+        .send()
+        .await?
+        .await?;
+    arbiter_token
+        .approve(to, TEST_APPROVAL_AMOUNT.into())
+        .send()
+        .await?
+        .await?;
+    manager.stop_environment(TEST_ENV_LABEL)?;
+
+    // The sinks have been flushed by the time the environment is stopped
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].event, "Transfer");
+    assert_eq!(records[0].address, arbiter_token.address());
+    assert_eq!(
+        records[0].formatted_values(),
+        vec![
+            format!("{:?}", Address::zero()),
+            format!("{:?}", to),
+            TEST_MINT_AMOUNT.to_string(),
+        ]
+    );
+    assert_eq!(records[1].event, "Approval");
+
+    let csv = std::fs::read_to_string(directory.join("events.csv"))?;
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0], "block_number,block_timestamp,address,event,values");
+    assert!(rows[1].contains(",Transfer,"));
+    let jsonl = std::fs::read_to_string(directory.join("events.jsonl"))?;
+    let line: serde_json::Value = serde_json::from_str(jsonl.lines().nth(1).unwrap())?;
+    assert_eq!(line["event"], "Approval");
+    assert_eq!(line["values"][2], TEST_APPROVAL_AMOUNT.to_string());
+    std::fs::remove_dir_all(directory)?;
+    Ok(())
+}
+
+#[test]
+fn recorder_requires_existing_environment() {
+    let mut manager = Manager::new();
+    let result = manager.add_recorder(
+        "recorder",
+        "missing",
+        Filter::new(),
+        ARBITERTOKEN_ABI.clone(),
+        vec![],
+    );
+    assert!(matches!(
+        result,
+        Err(ManagerError::EnvironmentDoesNotExist(label)) if label == "missing"
+    ));
+    assert!(manager.recorders.is_empty());
+}
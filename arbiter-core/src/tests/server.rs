@@ -0,0 +1,261 @@
+use std::{
+    io::{Read, Write},
+    time::Duration,
+};
+
+use assert_matches::assert_matches;
+use ethers::{
+    contract::ContractError,
+    middleware::SignerMiddleware,
+    providers::{Http, Provider, ProviderError},
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip2718::TypedTransaction, BlockNumber, TransactionRequest, H256, U256},
+};
+use serde_json::{json, Value};
+use tungstenite::Message;
+
+use super::*;
+
+fn serve() -> Result<(Manager, std::net::SocketAddr)> {
+    let (mut manager, _) = managed_start()?;
+    let address = manager.serve_rpc(TEST_ENV_LABEL, "127.0.0.1:0")?;
+    Ok((manager, address))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn serve_rpc_over_http() -> Result<()> {
+    let (_manager, address) = serve()?;
+    let provider =
+        Provider::<Http>::try_from(format!("http://{}", address))?.interval(Duration::ZERO);
+    let chain_id = provider.get_chainid().await?;
+    let wallet =
+        LocalWallet::new(&mut ethers::core::rand::thread_rng()).with_chain_id(chain_id.as_u64());
+    let alice = wallet.address();
+
+    provider
+        .request::<_, ()>("anvil_setBalance", (alice, U256::exp10(18)))
+        .await?;
+    assert_eq!(provider.get_balance(alice, None).await?, U256::exp10(18));
+
+    // Signed transactions are sent raw
+    let client = Arc::new(SignerMiddleware::new(provider.clone(), wallet));
+    let arbiter_token = ArbiterToken::deploy(
+        client.clone(),
+        (
+            TEST_ARG_NAME.to_string(),
+            TEST_ARG_SYMBOL.to_string(),
+            TEST_ARG_DECIMALS,
+        ),
+    )?
+    .send()
+    .await?;
+    let receipt = arbiter_token
+        .mint(alice, TEST_MINT_AMOUNT.into())
+        .send()
+        .await?
+        .await?
+        .unwrap();
+    assert_eq!(receipt.status, Some(1.into()));
+    assert_eq!(receipt.logs.len(), 1);
+    assert_eq!(
+        arbiter_token.balance_of(alice).call().await?,
+        TEST_MINT_AMOUNT.into()
+    );
+
+    // Unsigned transactions can be sent from any account
+    let bob = Address::from_str(TEST_MINT_TO)?;
+    let transfer = TransactionRequest::new().from(alice).to(bob).value(1000);
+    let receipt = provider
+        .send_transaction(transfer, None)
+        .await?
+        .await?
+        .unwrap();
+    assert_eq!(receipt.status, Some(1.into()));
+    assert_eq!(provider.get_balance(bob, None).await?, U256::from(1000));
+    let transaction = provider
+        .get_transaction(receipt.transaction_hash)
+        .await?
+        .unwrap();
+    assert_eq!(transaction.from, alice);
+    assert_eq!(transaction.to, Some(bob));
+
+    // Reverted calls carry their output
+    let error = arbiter_token
+        .transfer_from(bob, alice, U256::one())
+        .call()
+        .await
+        .unwrap_err();
+    assert_matches!(error, ContractError::Revert(_));
+
+    // Mining finishes the current block right away
+    let block_number = provider.get_block_number().await?;
+    provider.request::<_, U256>("evm_mine", ()).await?;
+    assert_eq!(provider.get_block_number().await?, block_number + 1);
+    let block = provider.get_block(block_number).await?.unwrap();
+    assert_eq!(block.number, Some(block_number));
+    let hash = block.hash.unwrap();
+    assert_eq!(
+        provider.get_block(hash).await?.unwrap().number,
+        Some(block_number)
+    );
+    let latest = provider.get_block(BlockNumber::Latest).await?.unwrap();
+    assert_eq!(latest.number, Some(block_number + 1));
+    assert_eq!(latest.parent_hash, hash);
+
+    // Cheats change the state directly
+    let code = provider.get_code(arbiter_token.address(), None).await?;
+    provider
+        .request::<_, ()>("anvil_setCode", (bob, code.clone()))
+        .await?;
+    assert_eq!(provider.get_code(bob, None).await?, code);
+    provider
+        .request::<_, bool>(
+            "anvil_setStorageAt",
+            (bob, U256::one(), H256::from_low_u64_be(7)),
+        )
+        .await?;
+    assert_eq!(
+        provider
+            .get_storage_at(bob, H256::from_low_u64_be(1), None)
+            .await?,
+        H256::from_low_u64_be(7)
+    );
+    provider
+        .request::<_, ()>("anvil_setNonce", (bob, U256::from(42)))
+        .await?;
+    assert_eq!(
+        provider.get_transaction_count(bob, None).await?,
+        U256::from(42)
+    );
+
+    let error = provider
+        .request::<_, Value>("eth_subscribe", ["newHeads"])
+        .await
+        .unwrap_err();
+    assert_matches!(
+        error,
+        ProviderError::JsonRpcClientError(e) if e.as_error_response().unwrap().code == -32601
+    );
+    Ok(())
+}
+
+#[test]
+fn serve_rpc_over_websocket() -> Result<()> {
+    let (_manager, address) = serve()?;
+    let (mut websocket, _) = tungstenite::connect(format!("ws://{}", address))?;
+    let mut request = |body: Value| -> Result<Value> {
+        websocket.send(Message::Text(body.to_string()))?;
+        Ok(serde_json::from_str(websocket.read()?.to_text()?)?)
+    };
+    let alice = Address::from_str(TEST_MINT_TO)?;
+    let bob = Address::random();
+
+    request(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "hardhat_setBalance",
+        "params": [alice, U256::exp10(18)],
+    }))?;
+    let response = request(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "eth_sendTransaction",
+        "params": [{ "from": alice, "to": bob, "value": U256::from(1000) }],
+    }))?;
+    assert_eq!(response["id"], json!(2));
+    let hash = response["result"].clone();
+
+    // Batches are answered in order
+    let responses = request(json!([
+        {
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "eth_getTransactionReceipt",
+            "params": [hash],
+        },
+        {
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "eth_getBalance",
+            "params": [bob, "latest"],
+        },
+    ]))?;
+    assert_eq!(responses[0]["result"]["status"], json!("0x1"));
+    assert_eq!(responses[1]["result"], json!(U256::from(1000)));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn reject_invalid_requests() -> Result<()> {
+    let (_manager, address) = serve()?;
+    let provider =
+        Provider::<Http>::try_from(format!("http://{}", address))?.interval(Duration::ZERO);
+    let code = |error: ProviderError| match error {
+        ProviderError::JsonRpcClientError(e) => e.as_error_response().map(|e| e.code),
+        _ => None,
+    };
+    let bob = Address::random();
+
+    for blocks in [U256::from(10_001), U256::MAX] {
+        let error = provider
+            .request::<_, Value>("anvil_mine", [blocks])
+            .await
+            .unwrap_err();
+        assert_eq!(code(error), Some(-32602));
+    }
+    let error = provider
+        .request::<_, Value>("anvil_setNonce", (bob, U256::MAX))
+        .await
+        .unwrap_err();
+    assert_eq!(code(error), Some(-32602));
+    let error = provider
+        .request::<_, Value>(
+            "eth_feeHistory",
+            (U256::one(), BlockNumber::Latest, vec![50.0; 101]),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(code(error), Some(-32602));
+
+    // Transactions signed for another chain cannot be replayed
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(bob)
+        .value(1)
+        .nonce(0)
+        .gas(21_000)
+        .gas_price(0)
+        .chain_id(chain_id + 1)
+        .into();
+    let signature = wallet.sign_transaction_sync(&tx)?;
+    let error = provider
+        .send_raw_transaction(tx.rlp_signed(&signature))
+        .await
+        .unwrap_err();
+    assert_eq!(code(error), Some(-32602));
+
+    // Oversized requests are refused without being read
+    let mut stream = std::net::TcpStream::connect(address)?;
+    write!(
+        stream,
+        "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+        usize::MAX
+    )?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    assert!(response.starts_with("HTTP/1.1 413"));
+    Ok(())
+}
+
+#[test]
+fn stop_rpc() -> Result<()> {
+    let (mut manager, address) = serve()?;
+    let (mut websocket, _) = tungstenite::connect(format!("ws://{}", address))?;
+    manager.stop_rpc(TEST_ENV_LABEL)?;
+
+    // Open connections are closed and new ones are refused
+    assert!(!matches!(websocket.read(), Ok(Message::Text(_))));
+    assert!(std::net::TcpStream::connect(address).is_err());
+    Ok(())
+}
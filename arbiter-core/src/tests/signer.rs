@@ -8,7 +8,7 @@ fn simulation_signer() {
         block_rate: 1.0,
         seed: 1,
     };
-    let environment = &mut Environment::new(TEST_ENV_LABEL, params);
+    let environment = &mut Environment::new(TEST_ENV_LABEL, params).unwrap();
     let client = Arc::new(RevmMiddleware::new(
         environment,
         Some(TEST_SIGNER_SEED_AND_LABEL.to_string()),
@@ -25,7 +25,7 @@ fn multiple_signer_addresses() {
         block_rate: 1.0,
         seed: 1,
     };
-    let environment = &mut Environment::new(TEST_ENV_LABEL, params);
+    let environment = &mut Environment::new(TEST_ENV_LABEL, params).unwrap();
     let client_1 = Arc::new(RevmMiddleware::new(environment, Some("0".to_string())));
     let client_2 = Arc::new(RevmMiddleware::new(environment, Some("1".to_string())));
     assert_ne!(client_1.default_sender(), client_2.default_sender());
@@ -37,7 +37,7 @@ fn signer_collision() {
         block_rate: 1.0,
         seed: 1,
     };
-    let environment = &mut Environment::new(TEST_ENV_LABEL, params);
+    let environment = &mut Environment::new(TEST_ENV_LABEL, params).unwrap();
     let client_1 = Arc::new(RevmMiddleware::new(environment, Some("0".to_string())));
     let client_2 = Arc::new(RevmMiddleware::new(environment, Some("0".to_string())));
     assert_eq!(client_1.default_sender(), client_2.default_sender());
@@ -15,7 +15,6 @@ use std::process::Command;
 /// * `Err(std::io::Error)` if the command execution fails or if there's an
 ///   error in generating the bindings. This can also include if the `forge`
 ///   tool is not installed.
-
 pub(crate) fn forge_bind() -> std::io::Result<()> {
     let output = Command::new("forge")
         .arg("bind")
@@ -35,9 +34,6 @@ pub(crate) fn forge_bind() -> std::io::Result<()> {
     } else {
         let err_str = String::from_utf8_lossy(&output.stderr);
         println!("Command failed, error: {}, is forge installed?", err_str);
-        Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Command failed",
-        ))
+        Err(std::io::Error::other("Command failed"))
     }
 }
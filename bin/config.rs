@@ -0,0 +1,726 @@
+#![warn(missing_docs)]
+
+//! Declarative simulation configuration.
+//!
+//! A simulation is described in a `.toml` file which lists the environments to
+//! create, the clocks that keep them in lockstep, the contracts to deploy
+//! in each environment and the initial token balances to mint. For example:
+//!
+//! ```toml
+//! [[environments]]
+//! label = "l1"
+//! seed = 1
+//! block_rate = 2.0
+//!
+//! [[clocks]]
+//! label = "chain"
+//! environments = ["l1"]
+//! genesis_timestamp = 1_700_000_000
+//! block_time = 12
+//!
+//! [[deployments]]
+//! label = "token_x"
+//! environment = "l1"
+//! contract = "ArbiterToken"
+//! deployer = "admin"
+//! args = ["Token X", "TKX", 18]
+//!
+//! [[mints]]
+//! environment = "l1"
+//! token = "token_x"
+//! to = "arbitrageur"
+//! amount = "1000000000000000000"
+//!
+//! [run]
+//! blocks = 100
+//! timeout_seconds = 60
+//! output_directory = "output"
+//!
+//! [[run.actions]]
+//! environment = "l1"
+//! sender = "arbitrageur"
+//! contract = "token_x"
+//! function = "transfer"
+//! args = ["admin", "1"]
+//! ```
+//!
+//! The `run` table is only needed by `arbiter simulate`, which sends its
+//! actions once per block until the given block number is reached.
+//!
+//! Addresses in constructor arguments and mints may be given as hex strings,
+//! as the label of a contract deployed earlier in the same environment, or as
+//! the label of a client whose wallet is derived from that label. Clients are
+//! the deployers and the senders of run actions in each environment, and any
+//! other label is rejected.
+
+use std::{collections::HashMap, fs, sync::Arc};
+
+use arbiter_core::{
+    bindings::{
+        arbiter_bridge, arbiter_math, arbiter_token, constant_product_pool, liquid_exchange,
+    },
+    clock::ClockParameters,
+    environment::EnvironmentParameters,
+    manager::{Manager, ManagerError},
+    middleware::RevmMiddleware,
+};
+use ethers::{
+    abi::{Abi, ParamType, Token},
+    contract::ContractFactory,
+    providers::Middleware,
+    types::{Address, Bytes, I256, U256},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{Configurable, ConfigurationError};
+
+/// The contents of a simulation `.toml` file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationConfig {
+    /// The environments to create.
+    pub environments: Vec<EnvironmentConfig>,
+
+    /// The clocks that keep groups of environments in lockstep.
+    #[serde(default)]
+    pub clocks: Vec<ClockConfig>,
+
+    /// The contracts to deploy, in order.
+    #[serde(default)]
+    pub deployments: Vec<DeploymentConfig>,
+
+    /// The initial token balances to mint, in order.
+    #[serde(default)]
+    pub mints: Vec<MintConfig>,
+
+    /// How to run the simulation once it has been built.
+    pub run: Option<RunConfig>,
+}
+
+/// Describes a single environment.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnvironmentConfig {
+    /// The label of the environment.
+    pub label: String,
+
+    /// The seed used for the environment's block sizes.
+    pub seed: u64,
+
+    /// The expected number of transactions per block.
+    pub block_rate: f64,
+}
+
+/// Describes the chain time shared by a group of environments.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClockConfig {
+    /// The label of the clock.
+    pub label: String,
+
+    /// The labels of the environments kept in lockstep by the clock.
+    pub environments: Vec<String>,
+
+    /// The timestamp (in seconds) of block zero.
+    pub genesis_timestamp: u64,
+
+    /// The amount of seconds that pass between two consecutive blocks.
+    pub block_time: u64,
+}
+
+/// Describes a contract deployment.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeploymentConfig {
+    /// The label the deployed contract can be referred to by.
+    pub label: String,
+
+    /// The label of the environment to deploy into.
+    pub environment: String,
+
+    /// The name of the contract, which must be one of the bindings shipped
+    /// with `arbiter-core`.
+    pub contract: String,
+
+    /// The label of the client that deploys the contract.
+    pub deployer: String,
+
+    /// The constructor arguments in the order of the contract's constructor.
+    #[serde(default)]
+    pub args: Vec<toml::Value>,
+}
+
+/// Describes an initial mint of an `ArbiterToken`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MintConfig {
+    /// The label of the environment the token lives in.
+    pub environment: String,
+
+    /// The label of the token deployment.
+    pub token: String,
+
+    /// The receiver of the minted tokens.
+    pub to: String,
+
+    /// The amount of tokens to mint in their smallest unit.
+    pub amount: toml::Value,
+}
+
+/// Describes how long to run a simulation and what to do in every block.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunConfig {
+    /// The block number every environment is run up to.
+    pub blocks: u64,
+
+    /// Stop early once this many seconds have passed.
+    pub timeout_seconds: Option<u64>,
+
+    /// The directory results are written to.
+    #[serde(default = "default_output_directory")]
+    pub output_directory: String,
+
+    /// The calls that are sent at the start of every block, in order.
+    #[serde(default)]
+    pub actions: Vec<ActionConfig>,
+}
+
+/// Describes a call to a deployed contract.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionConfig {
+    /// The label of the environment the contract lives in.
+    pub environment: String,
+
+    /// The label of the client that sends the call.
+    pub sender: String,
+
+    /// The label of the contract deployment to call.
+    pub contract: String,
+
+    /// The name of the function to call.
+    pub function: String,
+
+    /// The arguments in the order of the function's inputs.
+    #[serde(default)]
+    pub args: Vec<toml::Value>,
+}
+
+/// The output directory used if none is configured.
+fn default_output_directory() -> String {
+    "output".to_string()
+}
+
+impl Configurable for SimulationConfig {
+    fn configure(command_path: &str) -> Result<Self, ConfigurationError> {
+        let contents = fs::read_to_string(command_path)?;
+        let config: SimulationConfig = toml::from_str(&contents)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl SimulationConfig {
+    /// Checks that every label referred to in the configuration exists.
+    fn validate(&self) -> Result<(), ConfigurationError> {
+        if self.environments.is_empty() {
+            return Err(ConfigurationError::MissingFieldsError(
+                "at least one environment is required".to_string(),
+            ));
+        }
+        let mut environments = HashMap::new();
+        for environment in &self.environments {
+            if environments
+                .insert(environment.label.as_str(), vec![])
+                .is_some()
+            {
+                return Err(ConfigurationError::InvalidFieldError(format!(
+                    "environment {} is defined twice",
+                    environment.label
+                )));
+            }
+        }
+        for clock in &self.clocks {
+            for label in &clock.environments {
+                if !environments.contains_key(label.as_str()) {
+                    return Err(ConfigurationError::InvalidFieldError(format!(
+                        "clock {} refers to unknown environment {}",
+                        clock.label, label
+                    )));
+                }
+            }
+        }
+        for deployment in &self.deployments {
+            if contract_artifacts(&deployment.contract).is_none() {
+                return Err(ConfigurationError::InvalidFieldError(format!(
+                    "deployment {} refers to unknown contract {}",
+                    deployment.label, deployment.contract
+                )));
+            }
+            let deployed = environments
+                .get_mut(deployment.environment.as_str())
+                .ok_or_else(|| {
+                    ConfigurationError::InvalidFieldError(format!(
+                        "deployment {} refers to unknown environment {}",
+                        deployment.label, deployment.environment
+                    ))
+                })?;
+            if deployed.contains(&deployment.label.as_str()) {
+                return Err(ConfigurationError::InvalidFieldError(format!(
+                    "deployment {} is defined twice in environment {}",
+                    deployment.label, deployment.environment
+                )));
+            }
+            deployed.push(deployment.label.as_str());
+        }
+        for mint in &self.mints {
+            let deployed = environments.get(mint.environment.as_str()).ok_or_else(|| {
+                ConfigurationError::InvalidFieldError(format!(
+                    "mint refers to unknown environment {}",
+                    mint.environment
+                ))
+            })?;
+            if !deployed.contains(&mint.token.as_str()) {
+                return Err(ConfigurationError::InvalidFieldError(format!(
+                    "mint refers to unknown token {} in environment {}",
+                    mint.token, mint.environment
+                )));
+            }
+        }
+        for action in self.run.iter().flat_map(|run| &run.actions) {
+            let deployment = self
+                .deployments
+                .iter()
+                .find(|deployment| {
+                    deployment.environment == action.environment
+                        && deployment.label == action.contract
+                })
+                .ok_or_else(|| {
+                    ConfigurationError::InvalidFieldError(format!(
+                        "action refers to unknown contract {} in environment {}",
+                        action.contract, action.environment
+                    ))
+                })?;
+            // Unknown contracts have been rejected above.
+            if let Some((abi, _)) = contract_artifacts(&deployment.contract) {
+                if abi.function(&action.function).is_err() {
+                    return Err(ConfigurationError::InvalidFieldError(format!(
+                        "contract {} has no function {}",
+                        deployment.contract, action.function
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the configured environments in a new [`Manager`], starts them
+    /// and then performs every deployment and mint in order. Clocks are
+    /// suspended meanwhile, as the environments are set up one at a time and
+    /// could not wait for each other at block boundaries.
+    pub async fn build(&self) -> Result<Simulation, SimulationError> {
+        let mut manager = Manager::new();
+        for environment in &self.environments {
+            let params = EnvironmentParameters {
+                block_rate: environment.block_rate,
+                seed: environment.seed,
+            };
+            manager.add_environment(environment.label.clone(), params)?;
+        }
+        for clock in &self.clocks {
+            let params = ClockParameters {
+                genesis_timestamp: clock.genesis_timestamp,
+                block_time: clock.block_time,
+            };
+            manager.add_clock(clock.label.clone(), &clock.environments, params)?;
+            manager.suspend_clock(clock.label.clone())?;
+        }
+        for environment in &self.environments {
+            manager.start_environment(environment.label.clone())?;
+        }
+
+        let mut simulation = Simulation {
+            manager,
+            clients: HashMap::new(),
+            contracts: HashMap::new(),
+        };
+        for deployment in &self.deployments {
+            simulation.client(&deployment.environment, &deployment.deployer)?;
+        }
+        for action in self.run.iter().flat_map(|run| &run.actions) {
+            simulation.client(&action.environment, &action.sender)?;
+        }
+        for deployment in &self.deployments {
+            simulation.deploy(deployment).await?;
+        }
+        for mint in &self.mints {
+            simulation.mint(mint).await?;
+        }
+        for clock in &self.clocks {
+            simulation.manager.resume_clock(clock.label.clone())?;
+        }
+        Ok(simulation)
+    }
+}
+
+/// A running simulation built from a [`SimulationConfig`].
+#[derive(Debug)]
+pub struct Simulation {
+    /// The manager holding every configured environment.
+    pub manager: Manager,
+
+    /// The clients created for each environment, keyed by environment label
+    /// and then by client label.
+    pub clients: HashMap<String, HashMap<String, Arc<RevmMiddleware>>>,
+
+    /// The deployed contracts of each environment, keyed by environment label
+    /// and then by deployment label.
+    pub contracts: HashMap<String, HashMap<String, DeployedContract>>,
+}
+
+/// A contract deployed while building a [`Simulation`].
+#[derive(Debug, Clone)]
+pub struct DeployedContract {
+    /// The name of the contract.
+    pub contract: String,
+
+    /// The address the contract was deployed to.
+    pub address: Address,
+
+    /// The label of the client that deployed the contract.
+    pub deployer: String,
+}
+
+/// Errors that can occur while building a [`Simulation`].
+#[derive(Error, Debug)]
+pub enum SimulationError {
+    /// Indicates that the [`Manager`] failed to set up an environment.
+    #[error("manager error: {0}")]
+    Manager(#[from] ManagerError),
+
+    /// Indicates that a value in the configuration could not be used.
+    #[error("configuration error: {0}")]
+    Configuration(#[from] ConfigurationError),
+
+    /// Indicates that a transaction sent while building the simulation failed.
+    #[error("transaction failed: {0}")]
+    Transaction(String),
+}
+
+impl Simulation {
+    /// Returns the client with the given label in the given environment,
+    /// creating it if it does not exist yet.
+    pub fn client(
+        &mut self,
+        environment: &str,
+        label: &str,
+    ) -> Result<Arc<RevmMiddleware>, SimulationError> {
+        let env = self
+            .manager
+            .environments
+            .get(environment)
+            .ok_or_else(|| ManagerError::EnvironmentDoesNotExist(environment.to_string()))?;
+        let client = self
+            .clients
+            .entry(environment.to_string())
+            .or_default()
+            .entry(label.to_string())
+            .or_insert_with(|| Arc::new(RevmMiddleware::new(env, Some(label.to_string()))));
+        Ok(client.clone())
+    }
+
+    /// Resolves an address given as a hex string, a deployment label or the
+    /// label of a configured client.
+    fn resolve_address(&self, environment: &str, value: &str) -> Result<Address, SimulationError> {
+        if let Ok(address) = value.parse::<Address>() {
+            return Ok(address);
+        }
+        if let Some(deployed) = self
+            .contracts
+            .get(environment)
+            .and_then(|contracts| contracts.get(value))
+        {
+            return Ok(deployed.address);
+        }
+        if let Some(client) = self
+            .clients
+            .get(environment)
+            .and_then(|clients| clients.get(value))
+        {
+            return Ok(client.default_sender().unwrap_or_default());
+        }
+        Err(ConfigurationError::InvalidFieldError(format!(
+            "{} is neither an address nor the label of a contract or client in environment {}",
+            value, environment
+        ))
+        .into())
+    }
+
+    /// Converts a `.toml` value into an ABI token of the given type.
+    pub(crate) fn tokenize(
+        &mut self,
+        environment: &str,
+        kind: &ParamType,
+        value: &toml::Value,
+    ) -> Result<Token, SimulationError> {
+        let invalid =
+            || ConfigurationError::InvalidFieldError(format!("{} is not a valid {}", value, kind));
+        let token = match (kind, value) {
+            (ParamType::Address, toml::Value::String(s)) => {
+                Token::Address(self.resolve_address(environment, s)?)
+            }
+            (ParamType::Uint(_), value) => Token::Uint(parse_uint(value).ok_or_else(invalid)?),
+            (ParamType::Int(_), toml::Value::Integer(i)) => Token::Int(I256::from(*i).into_raw()),
+            (ParamType::Int(_), toml::Value::String(s)) => {
+                Token::Int(I256::from_dec_str(s).map_err(|_| invalid())?.into_raw())
+            }
+            (ParamType::Bool, toml::Value::Boolean(b)) => Token::Bool(*b),
+            (ParamType::String, toml::Value::String(s)) => Token::String(s.clone()),
+            (ParamType::Bytes, toml::Value::String(s)) => {
+                Token::Bytes(s.parse::<Bytes>().map_err(|_| invalid())?.to_vec())
+            }
+            (ParamType::FixedBytes(size), toml::Value::String(s)) => {
+                let bytes = s.parse::<Bytes>().map_err(|_| invalid())?;
+                if bytes.len() != *size {
+                    return Err(invalid().into());
+                }
+                Token::FixedBytes(bytes.to_vec())
+            }
+            _ => return Err(invalid().into()),
+        };
+        Ok(token)
+    }
+
+    /// Deploys a single contract described by a [`DeploymentConfig`].
+    async fn deploy(&mut self, deployment: &DeploymentConfig) -> Result<(), SimulationError> {
+        // The configuration has been validated so the contract is known.
+        let (abi, bytecode) = contract_artifacts(&deployment.contract).ok_or_else(|| {
+            ConfigurationError::InvalidFieldError(format!(
+                "unknown contract {}",
+                deployment.contract
+            ))
+        })?;
+        let inputs = abi
+            .constructor()
+            .map(|constructor| constructor.inputs.clone())
+            .unwrap_or_default();
+        if inputs.len() != deployment.args.len() {
+            return Err(ConfigurationError::InvalidFieldError(format!(
+                "deployment {} expects {} constructor arguments but {} were given",
+                deployment.label,
+                inputs.len(),
+                deployment.args.len()
+            ))
+            .into());
+        }
+        let mut tokens = vec![];
+        for (input, arg) in inputs.iter().zip(&deployment.args) {
+            tokens.push(self.tokenize(&deployment.environment, &input.kind, arg)?);
+        }
+
+        let client = self.client(&deployment.environment, &deployment.deployer)?;
+        let contract = ContractFactory::new(abi, bytecode, client)
+            .deploy_tokens(tokens)
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?
+            .send()
+            .await
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?;
+        self.contracts
+            .entry(deployment.environment.clone())
+            .or_default()
+            .insert(
+                deployment.label.clone(),
+                DeployedContract {
+                    contract: deployment.contract.clone(),
+                    address: contract.address(),
+                    deployer: deployment.deployer.clone(),
+                },
+            );
+        Ok(())
+    }
+
+    /// Mints tokens as described by a [`MintConfig`]. The mint is sent by the
+    /// client that deployed the token since it is the token's admin.
+    async fn mint(&mut self, mint: &MintConfig) -> Result<(), SimulationError> {
+        let deployed = self
+            .contracts
+            .get(&mint.environment)
+            .and_then(|contracts| contracts.get(&mint.token))
+            .cloned()
+            .ok_or_else(|| {
+                ConfigurationError::InvalidFieldError(format!(
+                    "unknown token {} in environment {}",
+                    mint.token, mint.environment
+                ))
+            })?;
+        let amount = parse_uint(&mint.amount).ok_or_else(|| {
+            ConfigurationError::InvalidFieldError(format!("{} is not a valid amount", mint.amount))
+        })?;
+        let to = self.resolve_address(&mint.environment, &mint.to)?;
+        let admin = self.client(&mint.environment, &deployed.deployer)?;
+        arbiter_token::ArbiterToken::new(deployed.address, admin)
+            .mint(to, amount)
+            .send()
+            .await
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?
+            .await
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Returns the ABI and bytecode of a contract shipped with `arbiter-core`.
+pub(crate) fn contract_artifacts(contract: &str) -> Option<(Abi, Bytes)> {
+    match contract {
+        "ArbiterBridge" => Some((
+            arbiter_bridge::ARBITERBRIDGE_ABI.clone(),
+            arbiter_bridge::ARBITERBRIDGE_BYTECODE.clone(),
+        )),
+        "ArbiterMath" => Some((
+            arbiter_math::ARBITERMATH_ABI.clone(),
+            arbiter_math::ARBITERMATH_BYTECODE.clone(),
+        )),
+        "ArbiterToken" => Some((
+            arbiter_token::ARBITERTOKEN_ABI.clone(),
+            arbiter_token::ARBITERTOKEN_BYTECODE.clone(),
+        )),
+        "ConstantProductPool" => Some((
+            constant_product_pool::CONSTANTPRODUCTPOOL_ABI.clone(),
+            constant_product_pool::CONSTANTPRODUCTPOOL_BYTECODE.clone(),
+        )),
+        "LiquidExchange" => Some((
+            liquid_exchange::LIQUIDEXCHANGE_ABI.clone(),
+            liquid_exchange::LIQUIDEXCHANGE_BYTECODE.clone(),
+        )),
+        _ => None,
+    }
+}
+
+/// Parses an unsigned integer given either as a `.toml` integer or as a
+/// decimal string, which allows for values larger than an `i64`.
+fn parse_uint(value: &toml::Value) -> Option<U256> {
+    match value {
+        toml::Value::Integer(i) if *i >= 0 => Some(U256::from(*i)),
+        toml::Value::String(s) => U256::from_dec_str(s).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [[environments]]
+        label = "l1"
+        seed = 1
+        block_rate = 2.0
+
+        [[deployments]]
+        label = "token_x"
+        environment = "l1"
+        contract = "ArbiterToken"
+        deployer = "admin"
+        args = ["Token X", "TKX", 18]
+
+        [[deployments]]
+        label = "exchange"
+        environment = "l1"
+        contract = "LiquidExchange"
+        deployer = "admin"
+        args = ["token_x", "token_x", "1000000000000000000"]
+
+        [[deployments]]
+        label = "pool"
+        environment = "l1"
+        contract = "ConstantProductPool"
+        deployer = "admin"
+        args = ["token_x", "token_x"]
+
+        [[mints]]
+        environment = "l1"
+        token = "token_x"
+        to = "arbitrageur"
+        amount = "1000000000000000000"
+
+        [run]
+        blocks = 1
+
+        [[run.actions]]
+        environment = "l1"
+        sender = "arbitrageur"
+        contract = "token_x"
+        function = "transfer"
+        args = ["admin", "1"]
+    "#;
+
+    #[test]
+    fn unknown_references_are_rejected() {
+        let mut config: SimulationConfig = toml::from_str(CONFIG).unwrap();
+        config.validate().unwrap();
+        config.mints[0].token = "token_y".to_string();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigurationError::InvalidFieldError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn build_simulation() {
+        let config: SimulationConfig = toml::from_str(CONFIG).unwrap();
+        let mut simulation = config.build().await.unwrap();
+
+        let token = simulation.contracts["l1"]["token_x"].address;
+        let exchange = simulation.contracts["l1"]["exchange"].address;
+        let arbitrageur = simulation.client("l1", "arbitrageur").unwrap();
+        let admin = simulation.client("l1", "admin").unwrap();
+        let balance = arbiter_token::ArbiterToken::new(token, admin.clone())
+            .balance_of(arbitrageur.default_sender().unwrap())
+            .call()
+            .await
+            .unwrap();
+        assert_eq!(balance, U256::exp10(18));
+        let token_x = liquid_exchange::LiquidExchange::new(exchange, admin.clone())
+            .arbiter_token_x()
+            .call()
+            .await
+            .unwrap();
+        assert_eq!(token_x, token);
+        let pool = simulation.contracts["l1"]["pool"].address;
+        let token_x = constant_product_pool::ConstantProductPool::new(pool, admin)
+            .token_x()
+            .call()
+            .await
+            .unwrap();
+        assert_eq!(token_x, token);
+        simulation.manager.stop_environment("l1").unwrap();
+    }
+
+    #[tokio::test]
+    async fn build_simulation_with_idle_clock_peer() {
+        let mut config: SimulationConfig = toml::from_str(CONFIG).unwrap();
+        config.environments[0].block_rate = 1.0;
+        config.environments.push(EnvironmentConfig {
+            label: "l2".to_string(),
+            seed: 2,
+            block_rate: 1.0,
+        });
+        config.clocks.push(ClockConfig {
+            label: "chain".to_string(),
+            environments: vec!["l1".to_string(), "l2".to_string()],
+            genesis_timestamp: 0,
+            block_time: 12,
+        });
+        let mut simulation = config.build().await.unwrap();
+        assert!(simulation.contracts["l1"].contains_key("exchange"));
+        simulation.manager.stop_environment("l1").unwrap();
+        simulation.manager.stop_environment("l2").unwrap();
+    }
+
+    #[tokio::test]
+    async fn unknown_labels_are_not_addresses() {
+        let mut config: SimulationConfig = toml::from_str(CONFIG).unwrap();
+        config.mints[0].to = "nobody".to_string();
+        assert!(matches!(
+            config.build().await,
+            Err(SimulationError::Configuration(
+                ConfigurationError::InvalidFieldError(_)
+            ))
+        ));
+    }
+}
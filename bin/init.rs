@@ -4,8 +4,8 @@ use std::{env, io, process::Command};
 ///
 /// This function does the following:
 /// 1. Clones the `arbiter-template` from GitHub into a new directory named
-///    after the provided project name.
-/// Template link is here https://github.com/primitivefinance/arbiter-template
+///    after the provided project name. The template is at
+///    <https://github.com/primitivefinance/arbiter-template>.
 /// 2. Changes the current directory to the cloned project.
 /// 3. Executes the `forge install` command.
 ///
@@ -15,8 +15,7 @@ use std::{env, io, process::Command};
 /// # Arguments
 ///
 /// * `name` - The name of the new project. This will also be the name of the
-///   directory
-/// where the project is initialized.
+///   directory where the project is initialized.
 ///
 /// # Returns
 ///
@@ -25,7 +24,6 @@ use std::{env, io, process::Command};
 /// - Network issues or repository being unavailable leading to git clone
 ///   failure.
 /// - The `forge install` command failing.
-
 pub(crate) fn init_project(name: &str) -> io::Result<()> {
     let status = Command::new("git")
         .arg("clone")
@@ -35,10 +33,7 @@ pub(crate) fn init_project(name: &str) -> io::Result<()> {
 
     if !status.success() {
         println!("Failed to clone the repository.");
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "Failed to clone the repository.",
-        ));
+        return Err(io::Error::other("Failed to clone the repository."));
     }
 
     env::set_current_dir(name)?;
@@ -50,10 +45,7 @@ pub(crate) fn init_project(name: &str) -> io::Result<()> {
     } else {
         let err_str = String::from_utf8_lossy(&install_output.stderr);
         println!("Command failed, error: {}, is forge installed?", err_str);
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Command failed",
-        ));
+        return Err(std::io::Error::other("Command failed"));
     }
 
     let output = Command::new("forge")
@@ -73,10 +65,7 @@ pub(crate) fn init_project(name: &str) -> io::Result<()> {
     } else {
         let err_str = String::from_utf8_lossy(&output.stderr);
         println!("Command failed, error: {}, is forge installed?", err_str);
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Command failed",
-        ));
+        return Err(std::io::Error::other("Command failed"));
     }
 
     println!(
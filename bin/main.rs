@@ -12,6 +12,10 @@
 //!   simulations.
 //! - Contract Bindings: Generate necessary bindings for interfacing with
 //!   different contracts.
+//! - Simulation Runs: Run a simulation described by a `.toml` configuration
+//!   file and write its results to disk.
+//! - JSON-RPC Server: Serve an environment over HTTP and WebSocket so that
+//!   tools outside of Rust can talk to it like a local node.
 //!
 //!
 //! This CLI leverages the power of Rust's type system to
@@ -19,11 +23,14 @@
 
 use std::error::Error;
 
-use clap::{command, CommandFactory, Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use thiserror::Error;
 
 mod bind;
+mod config;
 mod init;
+mod serve;
+mod simulate;
 
 /// Represents command-line arguments passed to the `Arbiter` tool.
 #[derive(Parser)]
@@ -55,6 +62,11 @@ pub enum ConfigurationError {
     /// file.
     #[error("missing fields in toml file")]
     MissingFieldsError(String),
+
+    /// Indicates that a field in the `.toml` file has a value that cannot be
+    /// used, e.g., a reference to a label that does not exist.
+    #[error("invalid field in toml file: {0}")]
+    InvalidFieldError(String),
 }
 
 /// Provides functionality for classes that need to be configured using a
@@ -86,12 +98,41 @@ enum Commands {
         #[clap(index = 1)]
         simulation_name: String,
     },
+
+    /// Represents the `Simulate` subcommand to run a configured simulation.
+    Simulate {
+        /// The path to the `.toml` configuration file of the simulation.
+        #[clap(index = 1)]
+        config_path: String,
+
+        /// The directory the results are written to. Overrides the
+        /// `output_directory` of the configuration file.
+        #[clap(short, long)]
+        output: Option<String>,
+    },
+
+    /// Represents the `Serve` subcommand to serve an environment over
+    /// JSON-RPC.
+    Serve {
+        /// The local address to listen on.
+        #[clap(short, long, default_value = "127.0.0.1:8545")]
+        address: String,
+
+        /// The average amount of transactions per block.
+        #[clap(short, long, default_value_t = 1.0)]
+        block_rate: f64,
+
+        /// The seed of the block sizes.
+        #[clap(short, long, default_value_t = 0)]
+        seed: u64,
+    },
 }
 
 /// The main entry point for the `Arbiter` tool.
 ///
 /// This function parses command line arguments, and based on the provided
-/// subcommand, either initializes a new simulation or generates bindings.
+/// subcommand, either initializes a new simulation, generates bindings, runs
+/// a simulation or serves an environment.
 ///
 /// # Returns
 ///
@@ -109,6 +150,21 @@ fn main() -> Result<(), Box<dyn Error>> {
             println!("Generating bindings...");
             bind::forge_bind()?;
         }
+        Some(Commands::Simulate {
+            config_path,
+            output,
+        }) => {
+            println!("Running simulation...");
+            simulate::simulate(config_path, output.as_deref())?;
+        }
+        Some(Commands::Serve {
+            address,
+            block_rate,
+            seed,
+        }) => {
+            println!("Starting environment...");
+            serve::serve(address, *block_rate, *seed)?;
+        }
         None => {
             Args::command()
                 .print_long_help()
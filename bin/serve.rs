@@ -0,0 +1,39 @@
+#![warn(missing_docs)]
+
+//! Serves a fresh environment over JSON-RPC so that tools such as `cast`,
+//! Foundry scripts, web3.py or frontends can talk to it like a local node.
+//!
+//! The environment runs until the process is interrupted, at which point it
+//! stops being served and is stopped.
+
+use std::error::Error;
+
+use arbiter_core::{environment::EnvironmentParameters, manager::Manager};
+
+/// The label of the served environment.
+const ENVIRONMENT_LABEL: &str = "arbiter";
+
+/// Starts an environment with the given block rate and seed, serves it over
+/// HTTP and WebSocket on `address` and waits for the process to be
+/// interrupted.
+///
+/// # Returns
+///
+/// * `Ok(())` once the process was interrupted and the environment stopped.
+/// * `Err(Box<dyn Error>)` if the environment could not be started or served.
+pub(crate) fn serve(address: &str, block_rate: f64, seed: u64) -> Result<(), Box<dyn Error>> {
+    let mut manager = Manager::new();
+    let params = EnvironmentParameters { block_rate, seed };
+    manager.add_environment(ENVIRONMENT_LABEL, params)?;
+    manager.start_environment(ENVIRONMENT_LABEL)?;
+    let address = manager.serve_rpc(ENVIRONMENT_LABEL, address)?;
+    println!(
+        "Serving JSON-RPC on http://{} and ws://{}, press Ctrl-C to stop",
+        address, address
+    );
+
+    tokio::runtime::Runtime::new()?.block_on(tokio::signal::ctrl_c())?;
+    manager.stop_rpc(ENVIRONMENT_LABEL)?;
+    manager.stop_environment(ENVIRONMENT_LABEL)?;
+    Ok(())
+}
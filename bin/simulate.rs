@@ -0,0 +1,415 @@
+#![warn(missing_docs)]
+
+//! Runs a simulation described by a `.toml` configuration file.
+//!
+//! Every environment is run on its own thread up to the configured block
+//! number. The configured actions are sent at the start of every block and, if
+//! they do not fill the block on their own, the block is mined so that the
+//! environment moves on to the next one. Once the configured timeout has
+//! passed, every environment that is still running is stopped, which also
+//! releases the ones waiting on their clock.
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use arbiter_core::{manager::ManagerError, middleware::RevmMiddleware};
+use ethers::{
+    abi::Token,
+    contract::Contract,
+    types::{Log, TransactionReceipt},
+};
+use serde_json::json;
+
+use crate::{
+    config::{contract_artifacts, Simulation, SimulationConfig, SimulationError},
+    Configurable, ConfigurationError,
+};
+
+/// A configured action whose arguments have been resolved.
+struct Action {
+    /// The contract to call.
+    contract: Contract<RevmMiddleware>,
+
+    /// The name of the function to call.
+    function: String,
+
+    /// The arguments to call the function with.
+    args: Vec<Token>,
+}
+
+/// What happened in a single environment while it was running.
+struct EnvironmentReport {
+    /// The block number the environment reached.
+    block_number: u64,
+
+    /// The amount of transactions sent by the simulation.
+    transactions: u64,
+
+    /// Whether the simulation was stopped before reaching the last block.
+    stopped_early: bool,
+
+    /// Every log emitted by the transactions sent by the simulation.
+    logs: Vec<Log>,
+}
+
+/// Loads the configuration at `config_path`, runs the simulation it describes
+/// and writes the results to the configured output directory, or to
+/// `output_directory` if given.
+///
+/// # Returns
+///
+/// * `Ok(())` if the simulation ran and its results were written.
+/// * `Err(Box<dyn Error>)` if the configuration is invalid, the simulation
+///   could not be built or the results could not be written.
+pub(crate) fn simulate(
+    config_path: &str,
+    output_directory: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let config = SimulationConfig::configure(config_path)?;
+    let run = config.run.clone().ok_or_else(|| {
+        ConfigurationError::MissingFieldsError("a [run] table is required to simulate".to_string())
+    })?;
+    let output_directory = output_directory.unwrap_or(&run.output_directory);
+    let start = Instant::now();
+    let deadline = run
+        .timeout_seconds
+        .map(|seconds| start + Duration::from_secs(seconds));
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut simulation = runtime.block_on(config.build())?;
+    println!(
+        "Built {} environment(s) with {} deployment(s)",
+        config.environments.len(),
+        config.deployments.len()
+    );
+
+    // Resolve the actions up front since this may create new clients
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    for action in &run.actions {
+        let deployed = simulation.contracts[&action.environment][&action.contract].clone();
+        // The configuration has been validated so the contract and function are known.
+        let (abi, _) = contract_artifacts(&deployed.contract).ok_or_else(|| {
+            ConfigurationError::InvalidFieldError(format!("unknown contract {}", deployed.contract))
+        })?;
+        let inputs = abi.function(&action.function)?.inputs.clone();
+        if inputs.len() != action.args.len() {
+            return Err(ConfigurationError::InvalidFieldError(format!(
+                "function {} expects {} arguments but {} were given",
+                action.function,
+                inputs.len(),
+                action.args.len()
+            ))
+            .into());
+        }
+        let mut args = vec![];
+        for (input, arg) in inputs.iter().zip(&action.args) {
+            args.push(simulation.tokenize(&action.environment, &input.kind, arg)?);
+        }
+        let client = simulation.client(&action.environment, &action.sender)?;
+        actions
+            .entry(action.environment.clone())
+            .or_default()
+            .push(Action {
+                contract: Contract::new(deployed.address, abi, client),
+                function: action.function.clone(),
+                args,
+            });
+    }
+
+    // Run every environment on its own thread since environments that share a
+    // clock wait on each other
+    let (report_sender, report_receiver) = mpsc::channel();
+    for environment in &config.environments {
+        let label = environment.label.clone();
+        let actions = actions.remove(&label).unwrap_or_default();
+        let miner = RevmMiddleware::new(&simulation.manager.environments[&label], None);
+        let report_sender = report_sender.clone();
+        let blocks = run.blocks;
+        thread::spawn(move || {
+            let report = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| SimulationError::Transaction(e.to_string()))
+                .and_then(|runtime| {
+                    runtime.block_on(run_environment(&label, actions, miner, blocks, deadline))
+                });
+            report_sender.send((label, report)).ok();
+        });
+    }
+    drop(report_sender);
+
+    // Stop each environment as soon as it is done so that it does not hold back
+    // the environments it shares a clock with
+    let mut reports = HashMap::new();
+    let mut stopped = HashSet::new();
+    let mut stop_result = Ok(());
+    let mut deadline = deadline;
+    loop {
+        let received = match deadline {
+            Some(at) => report_receiver.recv_timeout(at.saturating_duration_since(Instant::now())),
+            None => report_receiver
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok((label, report)) => {
+                stop_environment(&mut simulation, &label, &mut stopped, &mut stop_result);
+                match &report {
+                    Ok(report) => {
+                        println!("[{}] finished at block {}", label, report.block_number)
+                    }
+                    Err(e) => println!("[{}] failed: {}", label, e),
+                }
+                reports.insert(label, report);
+            }
+            // An environment waiting on its clock cannot notice the deadline on its own, so
+            // stop every environment that is still running
+            Err(RecvTimeoutError::Timeout) => {
+                for environment in &config.environments {
+                    stop_environment(
+                        &mut simulation,
+                        &environment.label,
+                        &mut stopped,
+                        &mut stop_result,
+                    );
+                }
+                deadline = None;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    write_results(
+        Path::new(output_directory),
+        &simulation,
+        &reports,
+        start.elapsed(),
+    )?;
+    println!("Results written to {}", output_directory);
+    stop_result?;
+    Ok(())
+}
+
+/// Stops the environment with the given `label` unless it is among the
+/// `stopped` ones already. A failure to stop it is kept in `result` unless an
+/// earlier one is, so that every environment is still stopped and the results
+/// collected so far are still written.
+fn stop_environment(
+    simulation: &mut Simulation,
+    label: &str,
+    stopped: &mut HashSet<String>,
+    result: &mut Result<(), ManagerError>,
+) {
+    if !stopped.insert(label.to_string()) {
+        return;
+    }
+    if let Err(e) = simulation.manager.stop_environment(label) {
+        println!("[{}] failed to stop: {}", label, e);
+        if result.is_ok() {
+            *result = Err(e);
+        }
+    }
+}
+
+/// Runs a single environment up to `blocks` or until the `deadline` passes,
+/// after which failures are due to the environment having been stopped.
+async fn run_environment(
+    label: &str,
+    actions: Vec<Action>,
+    miner: RevmMiddleware,
+    blocks: u64,
+    deadline: Option<Instant>,
+) -> Result<EnvironmentReport, SimulationError> {
+    let mut report = EnvironmentReport {
+        block_number: 0,
+        transactions: 0,
+        stopped_early: false,
+        logs: vec![],
+    };
+    let progress_step = (blocks / 10).max(1);
+    let past_deadline = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+    while report.block_number < blocks {
+        if past_deadline() {
+            report.stopped_early = true;
+            break;
+        }
+        let block_number = report.block_number;
+        match run_block(&mut report, &actions, &miner).await {
+            Ok(()) => {}
+            Err(_) if past_deadline() => {
+                report.stopped_early = true;
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+        if report.block_number / progress_step > block_number / progress_step {
+            println!("[{}] block {}/{}", label, report.block_number, blocks);
+        }
+    }
+    Ok(report)
+}
+
+/// Sends the `actions` of a block and mines it if they did not move the
+/// environment on to the next block already.
+async fn run_block(
+    report: &mut EnvironmentReport,
+    actions: &[Action],
+    miner: &RevmMiddleware,
+) -> Result<(), SimulationError> {
+    let block_number = report.block_number;
+    for action in actions {
+        let receipt = action
+            .contract
+            .method::<_, ()>(&action.function, action.args.as_slice())
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?
+            .send()
+            .await
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?
+            .await
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?;
+        record(report, receipt);
+    }
+    if report.block_number == block_number {
+        let next_block = miner
+            .mine()
+            .map_err(|e| SimulationError::Transaction(e.to_string()))?;
+        report.block_number = next_block.as_u64();
+    }
+    Ok(())
+}
+
+/// Adds a transaction receipt to an [`EnvironmentReport`].
+fn record(report: &mut EnvironmentReport, receipt: Option<TransactionReceipt>) {
+    report.transactions += 1;
+    if let Some(receipt) = receipt {
+        let block_number = receipt.block_number.unwrap_or_default();
+        report.block_number = report.block_number.max(block_number.as_u64());
+        report.logs.extend(receipt.logs.into_iter().map(|mut log| {
+            log.block_number = Some(block_number);
+            log
+        }));
+    }
+}
+
+/// Writes a `summary.json` describing every environment along with a
+/// `<environment>.logs.jsonl` file holding the logs emitted in each
+/// environment.
+fn write_results(
+    output_directory: &Path,
+    simulation: &Simulation,
+    reports: &HashMap<String, Result<EnvironmentReport, SimulationError>>,
+    elapsed: Duration,
+) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(output_directory)?;
+    let mut environments = serde_json::Map::new();
+    for (label, report) in reports {
+        let contracts: serde_json::Map<String, serde_json::Value> = simulation
+            .contracts
+            .get(label)
+            .into_iter()
+            .flatten()
+            .map(|(name, deployed)| {
+                (
+                    name.clone(),
+                    json!({ "contract": deployed.contract, "address": deployed.address }),
+                )
+            })
+            .collect();
+        let summary = match report {
+            Ok(report) => {
+                let mut writer = BufWriter::new(File::create(
+                    output_directory.join(format!("{}.logs.jsonl", label)),
+                )?);
+                for log in &report.logs {
+                    writeln!(writer, "{}", serde_json::to_string(log)?)?;
+                }
+                writer.flush()?;
+                json!({
+                    "block_number": report.block_number,
+                    "transactions": report.transactions,
+                    "stopped_early": report.stopped_early,
+                    "contracts": contracts,
+                })
+            }
+            Err(e) => json!({ "error": e.to_string(), "contracts": contracts }),
+        };
+        environments.insert(label.clone(), summary);
+    }
+    let summary = json!({
+        "elapsed_seconds": elapsed.as_secs_f64(),
+        "environments": environments,
+    });
+    fs::write(
+        output_directory.join("summary.json"),
+        serde_json::to_string_pretty(&summary)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [[environments]]
+        label = "l1"
+        seed = 1
+        block_rate = 2.0
+
+        [[deployments]]
+        label = "token_x"
+        environment = "l1"
+        contract = "ArbiterToken"
+        deployer = "admin"
+        args = ["Token X", "TKX", 18]
+
+        [run]
+        blocks = 5
+
+        [[run.actions]]
+        environment = "l1"
+        sender = "arbitrageur"
+        contract = "token_x"
+        function = "approve"
+        args = ["admin", "1"]
+    "#;
+
+    #[test]
+    fn simulate_writes_results() {
+        let directory = std::env::temp_dir().join(format!(
+            "arbiter_simulate_writes_results_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        let config_path = directory.join("simulation.toml");
+        fs::write(&config_path, CONFIG).unwrap();
+        let output_directory = directory.join("output");
+
+        simulate(
+            config_path.to_str().unwrap(),
+            Some(output_directory.to_str().unwrap()),
+        )
+        .unwrap();
+
+        let summary: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output_directory.join("summary.json")).unwrap(),
+        )
+        .unwrap();
+        let l1 = &summary["environments"]["l1"];
+        assert_eq!(l1["block_number"], 5);
+        assert_eq!(l1["stopped_early"], false);
+        assert_eq!(l1["contracts"]["token_x"]["contract"], "ArbiterToken");
+        let logs = fs::read_to_string(output_directory.join("l1.logs.jsonl")).unwrap();
+        // Every block starts with an approval
+        assert!(logs.lines().count() >= 5);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
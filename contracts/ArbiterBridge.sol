@@ -0,0 +1,42 @@
+pragma solidity ^0.8.17;
+
+/**
+ * @dev A minimal bridge endpoint used to test message passing between
+ * Arbiter environments. Balances sent on one side are burned and emitted as a
+ * `MessageSent` event which a relayer delivers on the other side.
+ */
+contract ArbiterBridge {
+    mapping(address => uint256) public balanceOf;
+
+    // The only account allowed to mint balances and deliver messages
+    address public operator;
+
+    event MessageSent(address indexed sender, address indexed recipient, uint256 amount);
+    event MessageDelivered(address indexed recipient, uint256 amount);
+
+    constructor(address _operator) {
+        operator = _operator;
+    }
+
+    modifier onlyOperator() {
+        require(msg.sender == operator);
+        _;
+    }
+
+    function mint(address receiver, uint256 amount) public onlyOperator {
+        balanceOf[receiver] += amount;
+    }
+
+    // Burn `amount` from the caller and ask the relayer to credit `recipient` on the other side
+    function send(address recipient, uint256 amount) public {
+        require(balanceOf[msg.sender] >= amount);
+        balanceOf[msg.sender] -= amount;
+        emit MessageSent(msg.sender, recipient, amount);
+    }
+
+    // Called by the relayer once a message sent on the other side is delivered
+    function deliver(address recipient, uint256 amount) public onlyOperator {
+        balanceOf[recipient] += amount;
+        emit MessageDelivered(recipient, amount);
+    }
+}
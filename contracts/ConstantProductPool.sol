@@ -0,0 +1,62 @@
+pragma solidity ^0.8.17;
+import "solmate/tokens/ERC20.sol";
+
+/**
+ * @dev A minimal constant product pool between two tokens with a 0.3% fee,
+ * used as a second venue next to the `LiquidExchange`. Liquidity can be added
+ * by anyone but never removed.
+ */
+contract ConstantProductPool {
+    address public tokenX;
+    address public tokenY;
+    uint256 public reserveX;
+    uint256 public reserveY;
+
+    event Swap(address tokenIn, address tokenOut, uint256 amountIn, uint256 amountOut, address to);
+    event LiquidityAdded(address provider, uint256 amountX, uint256 amountY);
+
+    constructor(address tokenX_, address tokenY_) {
+        tokenX = tokenX_;
+        tokenY = tokenY_;
+    }
+
+    function addLiquidity(uint256 amountX, uint256 amountY) public {
+        require(ERC20(tokenX).transferFrom(msg.sender, address(this), amountX));
+        require(ERC20(tokenY).transferFrom(msg.sender, address(this), amountY));
+        reserveX += amountX;
+        reserveY += amountY;
+        emit LiquidityAdded(msg.sender, amountX, amountY);
+    }
+
+    // The amount of the other token received for `amountIn` of `tokenIn`
+    function getAmountOut(address tokenIn, uint256 amountIn) public view returns (uint256) {
+        (uint256 reserveIn, uint256 reserveOut,) = _reserves(tokenIn);
+        uint256 amountInWithFee = amountIn * 997;
+        return amountInWithFee * reserveOut / (reserveIn * 1000 + amountInWithFee);
+    }
+
+    function swap(address tokenIn, uint256 amountIn) public returns (uint256 amountOut) {
+        (,, address tokenOut) = _reserves(tokenIn);
+        amountOut = getAmountOut(tokenIn, amountIn);
+        require(amountOut > 0);
+        require(ERC20(tokenIn).transferFrom(msg.sender, address(this), amountIn));
+        require(ERC20(tokenOut).transfer(msg.sender, amountOut));
+        if (tokenIn == tokenX) {
+            reserveX += amountIn;
+            reserveY -= amountOut;
+        } else {
+            reserveY += amountIn;
+            reserveX -= amountOut;
+        }
+        emit Swap(tokenIn, tokenOut, amountIn, amountOut, msg.sender);
+    }
+
+    function _reserves(address tokenIn) internal view returns (uint256, uint256, address) {
+        if (tokenIn == tokenX) {
+            return (reserveX, reserveY, tokenY);
+        } else if (tokenIn == tokenY) {
+            return (reserveY, reserveX, tokenX);
+        }
+        revert();
+    }
+}